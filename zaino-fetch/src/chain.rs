@@ -1,7 +1,12 @@
 //! Zaino-Fetch Block, Transaction and Mempool Parser.
 
 pub mod block;
+pub mod blockcache;
 pub mod error;
+pub mod fee;
+pub mod frontier;
 pub mod mempool;
+pub mod merkle;
+pub mod reorg;
 pub mod transaction;
 pub mod utils;
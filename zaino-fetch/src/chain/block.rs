@@ -2,6 +2,7 @@
 
 use crate::{
     chain::{
+        blockcache::compact::compact_block_from_serialized,
         error::{BlockCacheError, ParseError},
         transaction::FullTransaction,
         utils::{
@@ -9,7 +10,10 @@ use crate::{
             CompactSize, ParseFromSlice,
         },
     },
-    jsonrpc::{connector::JsonRpcConnector, response::GetBlockResponse},
+    jsonrpc::{
+        connector::{Auth, JsonRpcConnector},
+        response::GetBlockResponse,
+    },
 };
 use sha2::{Digest, Sha256};
 use std::io::Cursor;
@@ -383,15 +387,19 @@ impl FullBlock {
 /// TODO: Return more representative error type.
 pub async fn get_block_from_node(
     zebra_uri: &http::Uri,
+    auth: Option<Auth>,
+    height: &u32,
+) -> Result<CompactBlock, BlockCacheError> {
+    let zebrad_client = JsonRpcConnector::new_with_auth(zebra_uri.clone(), auth);
+    get_block_from_connector(&zebrad_client, height).await
+}
+
+/// Returns a compact block, fetched through `zebrad_client` as in [`get_block_from_node`].
+pub async fn get_block_from_connector(
+    zebrad_client: &JsonRpcConnector,
     height: &u32,
 ) -> Result<CompactBlock, BlockCacheError> {
-    let zebrad_client = JsonRpcConnector::new(
-        zebra_uri.clone(),
-        Some("xxxxxx".to_string()),
-        Some("xxxxxx".to_string()),
-    )
-    .await;
-    let block_1 = zebrad_client.get_block(height.to_string(), Some(1)).await;
+    let block_1 = zebrad_client.get_block(*height, Some(1)).await;
     match block_1 {
         Ok(GetBlockResponse::Object {
             hash,
@@ -400,8 +408,9 @@ pub async fn get_block_from_node(
             time: _,
             tx,
             trees,
+            ..
         }) => {
-            let block_0 = zebrad_client.get_block(hash.0.to_string(), Some(0)).await;
+            let block_0 = zebrad_client.get_block(hash.0, Some(0)).await;
             match block_0 {
                 Ok(GetBlockResponse::Object {
                     hash: _,
@@ -410,12 +419,13 @@ pub async fn get_block_from_node(
                     time: _,
                     tx: _,
                     trees: _,
+                    ..
                 }) => Err(BlockCacheError::ParseError(ParseError::InvalidData(
                     "Received object block type, this should not be possible here.".to_string(),
                 ))),
-                Ok(GetBlockResponse::Raw(block_hex)) => Ok(FullBlock::parse_to_compact(
-                    block_hex.as_ref(),
-                    Some(display_txids_to_server(tx)?),
+                Ok(GetBlockResponse::Raw(block_hex)) => Ok(compact_block_from_serialized(
+                    &block_hex,
+                    display_txids_to_server(tx.txids())?,
                     trees.sapling.size as u32,
                     trees.orchard.size as u32,
                 )?),
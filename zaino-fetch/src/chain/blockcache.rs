@@ -0,0 +1,492 @@
+//! Zingo-Indexer in-memory compact block cache, with an optional on-disk layer for finalized blocks.
+
+pub mod compact;
+pub mod disk;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use zaino_proto::proto::{compact_formats::CompactBlock, service::TreeState};
+
+use crate::{
+    chain::{block::get_block_from_connector, error::BlockCacheError},
+    jsonrpc::connector::{Auth, JsonRpcConnector},
+    task::spawn_named,
+};
+
+use self::disk::{DiskCache, DiskCacheStatus};
+
+/// Maximum depth of a chain reorganisation, the default reorg confirmation depth.
+///
+/// Zcash nodes will not reorg deeper than 100 blocks, so blocks at this depth are immutable.
+/// A lower depth can be set with [`BlockCache::with_reorg_confirmation_depth`], e.g. on regtest where reorgs are shallow.
+pub const MAX_REORG_DEPTH: u32 = 100;
+
+/// Holds the hit and miss counts of a [`BlockCache`].
+#[derive(Debug, Clone, Default)]
+pub struct BlockCacheStatus {
+    /// Number of lookups served from the cache.
+    hits: Arc<AtomicU64>,
+    /// Number of lookups not held in the cache.
+    misses: Arc<AtomicU64>,
+    /// Entry, size and hit counts of the on-disk layer, None if disabled.
+    disk: Option<DiskCacheStatus>,
+}
+
+impl BlockCacheStatus {
+    /// Returns the number of lookups served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups not held in the cache.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the counts of the on-disk layer, None if disabled.
+    ///
+    /// Lookups served from disk are also counted as cache hits.
+    pub fn disk(&self) -> Option<&DiskCacheStatus> {
+        self.disk.as_ref()
+    }
+
+    /// Records a cache lookup.
+    fn record(&self, hit: bool) {
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Cached blocks with their hash and recency indexes.
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Cached compact blocks keyed by height, with the tick of their last access.
+    blocks: BTreeMap<u32, (CompactBlock, u64)>,
+    /// Block heights keyed by block hash.
+    heights: HashMap<Vec<u8>, u32>,
+    /// Block heights keyed by the tick of their last access, least recently used first.
+    recency: BTreeMap<u64, u32>,
+    /// Access counter used to order blocks by recency.
+    tick: u64,
+}
+
+impl CacheState {
+    /// Returns the block at the given height, marking it as most recently used.
+    fn get(&mut self, height: u32) -> Option<CompactBlock> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (block, last_used) = self.blocks.get_mut(&height)?;
+        self.recency.remove(last_used);
+        self.recency.insert(tick, height);
+        *last_used = tick;
+        Some(block.clone())
+    }
+
+    /// Returns the block with the given hash, marking it as most recently used.
+    ///
+    /// Only returns a block if the block held at the indexed height still has the given hash.
+    fn get_by_hash(&mut self, hash: &[u8]) -> Option<CompactBlock> {
+        let height = *self.heights.get(hash)?;
+        match self.blocks.get(&height) {
+            Some((block, _)) if block.hash == hash => self.get(height),
+            _ => {
+                self.heights.remove(hash);
+                None
+            }
+        }
+    }
+
+    /// Adds a block as most recently used, replacing any block held at the same height.
+    ///
+    /// Both the height and hash indexes are updated so a replaced block can not be returned by either key.
+    fn insert(&mut self, block: CompactBlock) {
+        let height = block.height as u32;
+        self.remove(height);
+        if let Some(indexed_height) = self.heights.get(&block.hash).copied() {
+            self.remove(indexed_height);
+        }
+        self.tick += 1;
+        self.heights.insert(block.hash.clone(), height);
+        self.recency.insert(self.tick, height);
+        self.blocks.insert(height, (block, self.tick));
+    }
+
+    /// Removes the block at the given height from the height, hash and recency indexes.
+    fn remove(&mut self, height: u32) -> Option<CompactBlock> {
+        let (block, last_used) = self.blocks.remove(&height)?;
+        if self.heights.get(&block.hash) == Some(&height) {
+            self.heights.remove(&block.hash);
+        }
+        self.recency.remove(&last_used);
+        Some(block)
+    }
+
+    /// Returns the lowest height from which held blocks conflict with `block`, if any.
+    ///
+    /// A block held at the same height with a different hash, or a held parent / child that does not link
+    /// to `block`, belongs to a chain that has been reorganised away.
+    fn fork_height(&self, block: &CompactBlock) -> Option<u32> {
+        let height = block.height as u32;
+        let parent_conflicts = height
+            .checked_sub(1)
+            .and_then(|parent_height| self.blocks.get(&parent_height))
+            .is_some_and(|(parent, _)| parent.hash != block.prev_hash);
+        let block_conflicts = self
+            .blocks
+            .get(&height)
+            .is_some_and(|(held, _)| held.hash != block.hash);
+        let child_conflicts = self
+            .blocks
+            .get(&height.saturating_add(1))
+            .is_some_and(|(child, _)| child.prev_hash != block.hash);
+        if parent_conflicts {
+            Some(height - 1)
+        } else if block_conflicts {
+            Some(height)
+        } else if child_conflicts {
+            Some(height + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Removes all blocks at or above the given height, returns the number of blocks removed.
+    fn invalidate_from(&mut self, height: u32) -> usize {
+        let stale: Vec<u32> = self.blocks.range(height..).map(|(h, _)| *h).collect();
+        for stale_height in stale.iter() {
+            self.remove(*stale_height);
+        }
+        stale.len()
+    }
+
+    /// Removes the least recently used blocks until at most `target` blocks remain, returns the number of blocks removed.
+    fn evict_lru(&mut self, target: usize) -> usize {
+        let mut removed = 0;
+        while self.blocks.len() > target {
+            let height = match self.recency.first_key_value() {
+                Some((_, height)) => *height,
+                None => break,
+            };
+            self.remove(height);
+            removed += 1;
+        }
+        removed
+    }
+}
+
+/// In-memory LRU cache of compact blocks keyed by height and hash, shared between workers.
+///
+/// The cache is bounded by `capacity`: inserting into a full cache evicts the least recently used blocks, caching is disabled if `capacity` is 0.
+/// A background GC (see [`BlockCache::spawn_gc`]) trims the cache further, down to `gc_target`, while the cache is idle.
+/// Both paths use [`BlockCache::trim_to`] so active eviction and GC always agree on which blocks to drop.
+///
+/// Blocks that conflict with newly inserted blocks, or with the node's best chain (see [`BlockCache::update_tip`]),
+/// are dropped so stale blocks above a fork point are never served.
+///
+/// With an on-disk layer (see [`BlockCache::with_disk_cache`]), blocks at least the reorg confirmation depth
+/// (see [`BlockCache::reorg_confirmation_depth`]) below the node's tip are also written to disk, and lookups missing
+/// the in-memory cache are read back from disk.
+#[derive(Debug, Clone)]
+pub struct BlockCache {
+    /// Cached compact blocks.
+    state: Arc<Mutex<CacheState>>,
+    /// Time of the last cache access, used by the GC to detect idle periods.
+    last_access: Arc<Mutex<Instant>>,
+    /// Maximum number of blocks held in the cache.
+    capacity: usize,
+    /// Number of blocks the GC trims the cache down to when idle.
+    gc_target: usize,
+    /// Cache hit and miss counts.
+    status: BlockCacheStatus,
+    /// On-disk layer for finalized blocks, None if disabled.
+    disk: Option<DiskCache>,
+    /// Height of the node's best chain tip, as last seen by [`BlockCache::update_tip`].
+    tip_height: Arc<AtomicU32>,
+    /// Number of confirmations after which a block is considered final.
+    reorg_confirmation_depth: u32,
+}
+
+impl BlockCache {
+    /// Returns a new, empty, block cache.
+    ///
+    /// `gc_target` is clamped to `capacity`.
+    pub fn new(capacity: usize, gc_target: usize) -> Self {
+        BlockCache {
+            state: Arc::new(Mutex::new(CacheState::default())),
+            last_access: Arc::new(Mutex::new(Instant::now())),
+            capacity,
+            gc_target: gc_target.min(capacity),
+            status: BlockCacheStatus::default(),
+            disk: None,
+            tip_height: Arc::new(AtomicU32::new(0)),
+            reorg_confirmation_depth: MAX_REORG_DEPTH,
+        }
+    }
+
+    /// Adds an on-disk layer holding finalized blocks and treestates.
+    pub fn with_disk_cache(mut self, disk: DiskCache) -> Self {
+        self.status.disk = Some(disk.status());
+        self.disk = Some(disk);
+        self
+    }
+
+    /// Sets the number of confirmations after which a block is considered final, [`MAX_REORG_DEPTH`] by default.
+    ///
+    /// The depth bounds the blocks dropped on a reorg and the blocks written to the on-disk layer, and is used by
+    /// [`crate::chain::reorg::ChainTipMonitor`] to size its tracked chain.
+    pub fn with_reorg_confirmation_depth(mut self, depth: u32) -> Self {
+        self.reorg_confirmation_depth = depth;
+        self
+    }
+
+    /// Returns the number of confirmations after which a block is considered final.
+    pub fn reorg_confirmation_depth(&self) -> u32 {
+        self.reorg_confirmation_depth
+    }
+
+    /// Returns the block at the given height if held in the cache.
+    pub fn get(&self, height: u32) -> Option<CompactBlock> {
+        if self.capacity == 0 {
+            return None;
+        }
+        self.touch();
+        let block = self
+            .state
+            .lock()
+            .ok()
+            .and_then(|mut state| state.get(height))
+            .or_else(|| self.load(|disk| disk.get_block_at(height)));
+        self.status.record(block.is_some());
+        block
+    }
+
+    /// Returns the block with the given hash if held in the cache.
+    pub fn get_by_hash(&self, hash: &[u8]) -> Option<CompactBlock> {
+        if self.capacity == 0 {
+            return None;
+        }
+        self.touch();
+        let block = self
+            .state
+            .lock()
+            .ok()
+            .and_then(|mut state| state.get_by_hash(hash))
+            .or_else(|| self.load(|disk| disk.get_block(hash)));
+        self.status.record(block.is_some());
+        block
+    }
+
+    /// Adds a block to the cache, evicting the least recently used blocks if the cache is full.
+    ///
+    /// Held blocks that conflict with `block` are dropped from the fork point upwards.
+    /// Finalized blocks are also written to the on-disk layer.
+    pub fn insert(&self, block: CompactBlock) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.touch();
+        if let Some(disk) = self.disk.as_ref() {
+            if self.is_finalized(block.height) {
+                if let Err(e) = disk.insert_block(&block) {
+                    eprintln!(
+                        "Failed to write block {} to disk cache: {}.",
+                        block.height, e
+                    );
+                }
+            }
+        }
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(fork_height) = state.fork_height(&block) {
+                state.invalidate_from(fork_height);
+            }
+            state.insert(block);
+            state.evict_lru(self.capacity);
+        }
+    }
+
+    /// Checks the cache against the node's best chain tip, returns the number of blocks removed.
+    ///
+    /// If the cache holds a different block at the tip height, or blocks above the tip, the node has reorganised
+    /// and all blocks within the reorg confirmation depth of the tip are dropped.
+    pub fn update_tip(&self, height: u32, hash: &[u8]) -> usize {
+        self.tip_height.store(height, Ordering::Relaxed);
+        match self.state.lock() {
+            Ok(mut state) => {
+                let tip_conflicts = state
+                    .blocks
+                    .get(&height)
+                    .is_some_and(|(block, _)| block.hash != hash);
+                let above_tip = state
+                    .blocks
+                    .range(height.saturating_add(1)..)
+                    .next()
+                    .is_some();
+                if tip_conflicts || above_tip {
+                    state.invalidate_from(height.saturating_sub(self.reorg_confirmation_depth))
+                } else {
+                    0
+                }
+            }
+            Err(_) => 0,
+        }
+    }
+
+    /// Returns the treestate of the block with the given hash, hex encoded in display order, if held on disk.
+    pub fn get_treestate(&self, hash_hex: &str) -> Option<TreeState> {
+        self.disk.as_ref()?.get_treestate(hash_hex)
+    }
+
+    /// Returns the treestate of the block at the given height if held on disk.
+    pub fn get_treestate_at(&self, height: u32) -> Option<TreeState> {
+        self.disk.as_ref()?.get_treestate_at(height)
+    }
+
+    /// Writes a treestate to the on-disk layer if its block is finalized.
+    pub fn insert_treestate(&self, treestate: &TreeState) {
+        if let Some(disk) = self.disk.as_ref() {
+            if self.is_finalized(treestate.height) {
+                if let Err(e) = disk.insert_treestate(treestate) {
+                    eprintln!(
+                        "Failed to write treestate {} to disk cache: {}.",
+                        treestate.height, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Removes all blocks at or above the given height, returns the number of blocks removed.
+    ///
+    /// Blocks held on disk are finalized and left in place.
+    pub fn invalidate_from(&self, height: u32) -> usize {
+        match self.state.lock() {
+            Ok(mut state) => state.invalidate_from(height),
+            Err(_) => 0,
+        }
+    }
+
+    /// Trims the cache to hold at most `target` blocks, dropping the least recently used blocks first.
+    ///
+    /// Returns the number of blocks removed.
+    pub fn trim_to(&self, target: usize) -> usize {
+        match self.state.lock() {
+            Ok(mut state) => state.evict_lru(target),
+            Err(_) => 0,
+        }
+    }
+
+    /// Returns the number of blocks held in the cache.
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .map(|state| state.blocks.len())
+            .unwrap_or(0)
+    }
+
+    /// Returns true if the cache holds no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of blocks held in the cache.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the cache's hit and miss counts.
+    pub fn status(&self) -> BlockCacheStatus {
+        self.status.clone()
+    }
+
+    /// Fetches the node's `count` most recent best chain blocks into the cache, returns the number of blocks fetched.
+    ///
+    /// Used to warm the cache before requests are served, `count` is clamped to the cache's capacity.
+    /// The cache's tip is updated before the blocks are fetched so finalized blocks are also written to disk.
+    /// Requests to the node are authenticated with `auth`.
+    pub async fn preload(
+        &self,
+        zebra_uri: &http::Uri,
+        auth: Option<Auth>,
+        count: u32,
+    ) -> Result<u32, BlockCacheError> {
+        let count = count.min(self.capacity.try_into().unwrap_or(u32::MAX));
+        if count == 0 {
+            return Ok(0);
+        }
+        let zebrad_client = JsonRpcConnector::new_with_auth(zebra_uri.clone(), auth);
+        let blockchain_info = zebrad_client.get_blockchain_info().await?;
+        let tip_height = blockchain_info.blocks.0;
+        self.update_tip(tip_height, &blockchain_info.best_block_hash.0);
+        let start_height = tip_height.saturating_sub(count - 1);
+        // NOTE: Blocks are fetched in height order so each block's parent is held when it is inserted.
+        for height in start_height..=tip_height {
+            self.insert(get_block_from_connector(&zebrad_client, &height).await?);
+        }
+        Ok(tip_height - start_height + 1)
+    }
+
+    /// Spawns the cache GC.
+    ///
+    /// Every `interval` the GC checks whether the cache has been accessed in the last `idle_period`,
+    /// if not the cache is trimmed down to `gc_target`. Runs while `online` is true.
+    pub fn spawn_gc(
+        &self,
+        interval: Duration,
+        idle_period: Duration,
+        online: Arc<AtomicBool>,
+    ) -> tokio::task::JoinHandle<()> {
+        let cache = self.clone();
+        spawn_named("block_cache_gc", async move {
+            let mut ticker = tokio::time::interval(interval);
+            while online.load(Ordering::SeqCst) {
+                ticker.tick().await;
+                if cache.idle_for() >= idle_period {
+                    cache.trim_to(cache.gc_target);
+                }
+            }
+        })
+    }
+
+    /// Returns true if the block at `height` is at least the reorg confirmation depth below the node's tip.
+    ///
+    /// No block is finalized before the tip is known.
+    pub fn is_finalized(&self, height: u64) -> bool {
+        let tip_height = self.tip_height.load(Ordering::Relaxed) as u64;
+        tip_height > 0 && height + self.reorg_confirmation_depth as u64 <= tip_height
+    }
+
+    /// Reads a block from the on-disk layer, promoting it into the in-memory cache.
+    fn load(&self, read: impl FnOnce(&DiskCache) -> Option<CompactBlock>) -> Option<CompactBlock> {
+        let block = read(self.disk.as_ref()?)?;
+        if let Ok(mut state) = self.state.lock() {
+            state.insert(block.clone());
+            state.evict_lru(self.capacity);
+        }
+        Some(block)
+    }
+
+    /// Returns the time since the cache was last accessed.
+    fn idle_for(&self) -> Duration {
+        self.last_access
+            .lock()
+            .map(|last_access| last_access.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Updates the time of the last cache access.
+    fn touch(&self) {
+        if let Ok(mut last_access) = self.last_access.lock() {
+            *last_access = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Compact block construction from full serialized blocks.
+
+use zaino_proto::proto::compact_formats::CompactBlock;
+
+use crate::{
+    chain::{block::FullBlock, error::ParseError},
+    primitives::block::SerializedBlock,
+};
+
+/// Builds a lightwalletd [`CompactBlock`] from a full serialized block.
+///
+/// `txids` are the block's transaction ids in block order, as returned by a `getblock` verbose = 1 call.
+///
+/// Only transactions holding Sapling spends / outputs or Orchard actions produce a compact transaction.
+/// Each compact transaction keeps its index in the full block, so the coinbase and transparent-only transactions
+/// are not returned but still count towards the indexes of the transactions that follow them.
+pub fn compact_block_from_serialized(
+    block: &SerializedBlock,
+    txids: Vec<Vec<u8>>,
+    sapling_commitment_tree_size: u32,
+    orchard_commitment_tree_size: u32,
+) -> Result<CompactBlock, ParseError> {
+    FullBlock::parse_to_compact(
+        block.as_ref(),
+        Some(txids),
+        sapling_commitment_tree_size,
+        orchard_commitment_tree_size,
+    )
+}
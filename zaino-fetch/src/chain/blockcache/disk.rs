@@ -0,0 +1,208 @@
+//! Zingo-Indexer on-disk cache of finalized compact blocks and treestates.
+//!
+//! Entries are stored as prost encoded files under the cache directory:
+//! - `blocks/<hash>.bin`: compact blocks, keyed by block hash.
+//! - `treestates/<hash>.bin`: treestates, keyed by block hash.
+//! - `heights/<height>`: the hash of the block at each height.
+//!
+//! Hashes are hex encoded in display (big-endian) order. Only blocks at least the block cache's reorg confirmation
+//! depth (see [`super::BlockCache::reorg_confirmation_depth`]) below the tip are written, so entries never need to
+//! be invalidated.
+
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use prost::Message;
+use zaino_proto::proto::{compact_formats::CompactBlock, service::TreeState};
+
+const BLOCKS_DIR: &str = "blocks";
+const TREESTATES_DIR: &str = "treestates";
+const HEIGHTS_DIR: &str = "heights";
+
+/// Holds the entry, size and hit counts of a [`DiskCache`].
+#[derive(Debug, Clone, Default)]
+pub struct DiskCacheStatus {
+    /// Number of blocks and treestates held on disk.
+    entries: Arc<AtomicU64>,
+    /// Total size of the held blocks and treestates, in bytes.
+    bytes: Arc<AtomicU64>,
+    /// Number of lookups served from disk.
+    hits: Arc<AtomicU64>,
+}
+
+impl DiskCacheStatus {
+    /// Returns the number of blocks and treestates held on disk.
+    pub fn entries(&self) -> u64 {
+        self.entries.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total size of the held blocks and treestates, in bytes.
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups served from disk.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Records a new entry of `size` bytes.
+    fn record_write(&self, size: u64) {
+        self.entries.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(size, Ordering::Relaxed);
+    }
+
+    /// Records a lookup served from disk.
+    fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Disk-backed cache of finalized compact blocks and treestates, keyed by block hash.
+///
+/// Entries are read from disk lazily on lookup, opening a cache only counts the entries already held.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    /// Cache directory.
+    dir: PathBuf,
+    /// Entry, size and hit counts.
+    status: DiskCacheStatus,
+}
+
+impl DiskCache {
+    /// Opens the cache at `dir`, creating the directory if it does not exist.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        let status = DiskCacheStatus::default();
+        for sub_dir in [BLOCKS_DIR, TREESTATES_DIR, HEIGHTS_DIR] {
+            fs::create_dir_all(dir.join(sub_dir))?;
+        }
+        for sub_dir in [BLOCKS_DIR, TREESTATES_DIR] {
+            for entry in fs::read_dir(dir.join(sub_dir))? {
+                let entry = entry?;
+                if entry.path().extension().is_some_and(|ext| ext == "bin") {
+                    status.record_write(entry.metadata()?.len());
+                }
+            }
+        }
+        Ok(DiskCache { dir, status })
+    }
+
+    /// Returns the cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cache's entry, size and hit counts.
+    pub fn status(&self) -> DiskCacheStatus {
+        self.status.clone()
+    }
+
+    /// Returns the block with the given hash, in internal byte order, if held on disk.
+    pub fn get_block(&self, hash: &[u8]) -> Option<CompactBlock> {
+        self.read_entry(BLOCKS_DIR, &display_hex(hash))
+    }
+
+    /// Returns the block at the given height if held on disk.
+    pub fn get_block_at(&self, height: u32) -> Option<CompactBlock> {
+        self.read_entry(BLOCKS_DIR, &self.hash_at(height)?)
+    }
+
+    /// Writes a block to disk, indexing it by its height.
+    pub fn insert_block(&self, block: &CompactBlock) -> io::Result<()> {
+        let key = display_hex(&block.hash);
+        self.write_entry(BLOCKS_DIR, &key, &block.encode_to_vec())?;
+        write_atomic(
+            &self.dir.join(HEIGHTS_DIR).join(block.height.to_string()),
+            key.as_bytes(),
+        )
+    }
+
+    /// Returns the treestate of the block with the given hash, hex encoded in display order, if held on disk.
+    pub fn get_treestate(&self, hash_hex: &str) -> Option<TreeState> {
+        self.read_entry(TREESTATES_DIR, &hash_hex.to_lowercase())
+    }
+
+    /// Returns the treestate of the block at the given height if held on disk.
+    pub fn get_treestate_at(&self, height: u32) -> Option<TreeState> {
+        self.read_entry(TREESTATES_DIR, &self.hash_at(height)?)
+    }
+
+    /// Writes a treestate to disk, indexing its block by height.
+    pub fn insert_treestate(&self, treestate: &TreeState) -> io::Result<()> {
+        let key = treestate.hash.to_lowercase();
+        self.write_entry(TREESTATES_DIR, &key, &treestate.encode_to_vec())?;
+        write_atomic(
+            &self
+                .dir
+                .join(HEIGHTS_DIR)
+                .join(treestate.height.to_string()),
+            key.as_bytes(),
+        )
+    }
+
+    /// Returns the hash of the block at the given height, hex encoded in display order, if indexed.
+    fn hash_at(&self, height: u32) -> Option<String> {
+        fs::read_to_string(self.dir.join(HEIGHTS_DIR).join(height.to_string())).ok()
+    }
+
+    /// Returns the path of the entry `key` in `sub_dir`.
+    fn entry_path(&self, sub_dir: &str, key: &str) -> PathBuf {
+        self.dir.join(sub_dir).join(format!("{}.bin", key))
+    }
+
+    /// Reads and decodes the entry `key` in `sub_dir`.
+    ///
+    /// Entries that can not be read or decoded are treated as missing.
+    fn read_entry<M: Message + Default>(&self, sub_dir: &str, key: &str) -> Option<M> {
+        let data = fs::read(self.entry_path(sub_dir, key)).ok()?;
+        match M::decode(data.as_slice()) {
+            Ok(entry) => {
+                self.status.record_hit();
+                Some(entry)
+            }
+            Err(e) => {
+                eprintln!(
+                    "Failed to decode disk cache entry {}/{}: {}.",
+                    sub_dir, key, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Writes the entry `key` in `sub_dir`, existing entries are left in place.
+    fn write_entry(&self, sub_dir: &str, key: &str, data: &[u8]) -> io::Result<()> {
+        let path = self.entry_path(sub_dir, key);
+        match fs::metadata(&path) {
+            Ok(_) => return Ok(()),
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+            Err(_) => {}
+        }
+        write_atomic(&path, data)?;
+        self.status.record_write(data.len() as u64);
+        Ok(())
+    }
+}
+
+/// Writes `data` to a temporary file beside `path` and renames it into place,
+/// so an interrupted write never leaves a partial entry.
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, data)?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Hex encodes a hash held in internal byte order in display order.
+fn display_hex(hash: &[u8]) -> String {
+    let mut display = hash.to_vec();
+    display.reverse();
+    hex::encode(display)
+}
@@ -13,6 +13,15 @@ pub enum ParseError {
     #[error("Invalid Data Error: {0}")]
     InvalidData(String),
 
+    /// A length prefixed body does not match the number of bytes that follow the prefix.
+    #[error("Body Length Mismatch: expected {expected} bytes, found {actual}")]
+    BodyLengthMismatch {
+        /// Body length read from the length prefix.
+        expected: usize,
+        /// Number of bytes following the length prefix.
+        actual: usize,
+    },
+
     // /// Errors from the JsonRPC client.
     // #[error("JsonRPC Connector Error: {0}")]
     // JsonRpcError(#[from] JsonRpcConnectorError),
@@ -33,6 +42,32 @@ pub enum ParseError {
     ProstDecodeError(#[from] prost::DecodeError),
 }
 
+impl ParseError {
+    /// Maps ParseError to tonic::Status.
+    ///
+    /// Parse failures on data received from the node are reported as Internal, only a generic message is returned to the client.
+    /// Prost decode failures originate from client supplied bytes and are reported as InvalidArgument.
+    pub fn to_grpc_status(&self) -> tonic::Status {
+        eprintln!("Error occurred: {}.", self);
+
+        match self {
+            ParseError::ProstDecodeError(_) => {
+                tonic::Status::invalid_argument("Failed to decode request data")
+            }
+            ParseError::BodyLengthMismatch { .. } => {
+                tonic::Status::invalid_argument("Request body length mismatch")
+            }
+            _ => tonic::Status::internal("Failed to parse chain data received from node"),
+        }
+    }
+}
+
+impl From<ParseError> for tonic::Status {
+    fn from(err: ParseError) -> Self {
+        err.to_grpc_status()
+    }
+}
+
 /// Parser Error Type.
 #[derive(Debug, thiserror::Error)]
 pub enum BlockCacheError {
@@ -44,10 +79,46 @@ pub enum BlockCacheError {
     JsonRpcError(#[from] JsonRpcConnectorError),
 }
 
+impl BlockCacheError {
+    /// Maps BlockCacheError to tonic::Status.
+    pub fn to_grpc_status(&self) -> tonic::Status {
+        match self {
+            BlockCacheError::ParseError(e) => e.to_grpc_status(),
+            BlockCacheError::JsonRpcError(e) => e.to_grpc_status(),
+        }
+    }
+}
+
+impl From<BlockCacheError> for tonic::Status {
+    fn from(err: BlockCacheError) -> Self {
+        err.to_grpc_status()
+    }
+}
+
 /// Mempool Error struct.
 #[derive(thiserror::Error, Debug)]
 pub enum MempoolError {
     /// Errors from the JsonRPC client.
     #[error("JsonRPC Connector Error: {0}")]
     JsonRpcError(#[from] JsonRpcConnectorError),
+
+    /// Errors parsing mempool transactions.
+    #[error("Parser Error: {0}")]
+    ParseError(#[from] ParseError),
+}
+
+impl MempoolError {
+    /// Maps MempoolError to tonic::Status.
+    pub fn to_grpc_status(&self) -> tonic::Status {
+        match self {
+            MempoolError::JsonRpcError(e) => e.to_grpc_status(),
+            MempoolError::ParseError(e) => e.to_grpc_status(),
+        }
+    }
+}
+
+impl From<MempoolError> for tonic::Status {
+    fn from(err: MempoolError) -> Self {
+        err.to_grpc_status()
+    }
 }
@@ -0,0 +1,45 @@
+//! Zingo-Indexer mempool fee estimation.
+
+use crate::jsonrpc::response::MempoolEntry;
+
+/// Assumed maximum size of a block, in bytes.
+pub const BLOCK_SIZE_BYTES: u64 = 2_000_000;
+
+/// Target spacing between blocks, in seconds.
+pub const BLOCK_TARGET_SPACING_SECS: u64 = 75;
+
+/// Network minimum relay fee rate, in zatoshis per 1000 bytes.
+///
+/// Returned when there is no mempool competition for the target confirmation window.
+pub const MIN_RELAY_FEE_RATE: u64 = 100;
+
+/// Returns the fee rate of a mempool transaction, in zatoshis per 1000 bytes.
+pub fn fee_rate(entry: &MempoolEntry) -> u64 {
+    entry.fee.saturating_mul(1000) / entry.size.max(1)
+}
+
+/// Returns the recommended fee rate, in zatoshis per 1000 bytes, to be mined within `target_blocks` blocks.
+///
+/// Mempool transactions are sorted by fee rate, highest first, and assumed to fill [`BLOCK_SIZE_BYTES`] blocks
+/// in that order. The returned rate is the fee rate of the first transaction that does not fit in the target
+/// window, i.e. the fee rate at the `window / mempool size` percentile. If the whole mempool fits in the window
+/// (including an empty mempool) [`MIN_RELAY_FEE_RATE`] is returned. The result is never below [`MIN_RELAY_FEE_RATE`].
+///
+/// A `target_blocks` of 0 is treated as 1.
+pub fn estimate_fee_rate(entries: &[MempoolEntry], target_blocks: u32) -> u64 {
+    let window = BLOCK_SIZE_BYTES.saturating_mul(target_blocks.max(1) as u64);
+    let mut rates: Vec<(u64, u64)> = entries
+        .iter()
+        .map(|entry| (fee_rate(entry), entry.size))
+        .collect();
+    rates.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+
+    let mut filled: u64 = 0;
+    for (rate, size) in rates {
+        filled = filled.saturating_add(size);
+        if filled > window {
+            return rate.max(MIN_RELAY_FEE_RATE);
+        }
+    }
+    MIN_RELAY_FEE_RATE
+}
@@ -0,0 +1,199 @@
+//! Zingo-Indexer note commitment tree frontiers, parsed from the node's `z_gettreestate` final states.
+//!
+//! zcashd and zebrad serialize a tree's final state in the legacy `CommitmentTree` encoding: an optional left leaf,
+//! an optional right leaf and a CompactSize prefixed vector of optional parents, each optional node being a presence
+//! byte followed by the 32 byte node if present. The frontier is the tree's rightmost path, its rightmost leaf and
+//! the roots of the complete subtrees to its left, enough to append further leaves and maintain witnesses.
+
+use std::io::Cursor;
+
+use crate::{
+    chain::{
+        error::ParseError,
+        utils::{read_bytes, CompactSize},
+    },
+    jsonrpc::response::GetTreestateResponse,
+};
+
+/// Maximum depth of a note commitment tree, both the Sapling and Orchard trees have depth 32.
+pub const MAX_TREE_DEPTH: usize = 32;
+
+/// Rightmost path of a note commitment tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreeFrontier {
+    /// Number of leaves appended to the tree.
+    pub size: u64,
+    /// Rightmost leaf of the tree, None if the tree is empty.
+    pub leaf: Option<[u8; 32]>,
+    /// Roots of the complete subtrees left of the rightmost path, from the leaf level up.
+    ///
+    /// The ommer at each level whose bit is set in the leaf's position (`size - 1`), in level order.
+    pub ommers: Vec<[u8; 32]>,
+}
+
+impl TreeFrontier {
+    /// Parses the hex-encoded final state of a `z_gettreestate` pool, None if the state is empty.
+    ///
+    /// The node returns an empty final state for pools not yet active at the requested height.
+    pub fn from_final_state(final_state: &str) -> Result<Option<Self>, ParseError> {
+        if final_state.is_empty() {
+            return Ok(None);
+        }
+        let bytes = hex::decode(final_state)
+            .map_err(|e| ParseError::InvalidData(format!("Invalid tree state hex: {}", e)))?;
+        Self::parse(&bytes).map(Some)
+    }
+
+    /// Parses a tree serialized in the legacy `CommitmentTree` encoding.
+    pub fn parse(data: &[u8]) -> Result<Self, ParseError> {
+        let mut cursor = Cursor::new(data);
+        let left = read_optional_node(&mut cursor)?;
+        let right = read_optional_node(&mut cursor)?;
+        let parent_count = CompactSize::read(&mut cursor)?;
+        if parent_count >= MAX_TREE_DEPTH as u64 {
+            return Err(ParseError::InvalidData(format!(
+                "Tree state holds {} parents, trees have at most {} levels",
+                parent_count, MAX_TREE_DEPTH
+            )));
+        }
+        let parents = (0..parent_count)
+            .map(|_| read_optional_node(&mut cursor))
+            .collect::<Result<Vec<_>, _>>()?;
+        if cursor.position() != data.len() as u64 {
+            return Err(ParseError::InvalidData(
+                "Unexpected trailing data in tree state".to_string(),
+            ));
+        }
+
+        let left = match left {
+            Some(left) => left,
+            None if right.is_none() && parents.iter().all(Option::is_none) => {
+                return Ok(TreeFrontier {
+                    size: 0,
+                    leaf: None,
+                    ommers: Vec::new(),
+                })
+            }
+            None => {
+                return Err(ParseError::InvalidData(
+                    "Tree state holds nodes but no left leaf".to_string(),
+                ))
+            }
+        };
+        // The leaf's position has bit 0 set if the right leaf is present, and bit `level` set for each parent present.
+        let mut position = 0u64;
+        let mut ommers = Vec::new();
+        let leaf = match right {
+            Some(right) => {
+                position |= 1;
+                ommers.push(left);
+                right
+            }
+            None => left,
+        };
+        for (index, parent) in parents.iter().enumerate() {
+            if let Some(parent) = parent {
+                position |= 1 << (index + 1);
+                ommers.push(*parent);
+            }
+        }
+        Ok(TreeFrontier {
+            size: position + 1,
+            leaf: Some(leaf),
+            ommers,
+        })
+    }
+
+    /// Returns the position of the rightmost leaf, None if the tree is empty.
+    pub fn position(&self) -> Option<u64> {
+        self.size.checked_sub(1)
+    }
+
+    /// Serializes the frontier in the legacy `CommitmentTree` encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let (position, leaf) = match (self.position(), self.leaf) {
+            (Some(position), Some(leaf)) => (position, leaf),
+            _ => {
+                // Empty tree: no left or right leaf and no parents.
+                return vec![0, 0, 0];
+            }
+        };
+        let mut ommers = self.ommers.iter();
+        if position & 1 == 1 {
+            write_optional_node(&mut bytes, ommers.next());
+            write_optional_node(&mut bytes, Some(&leaf));
+        } else {
+            write_optional_node(&mut bytes, Some(&leaf));
+            write_optional_node(&mut bytes, None);
+        }
+        let levels = (u64::BITS - (position >> 1).leading_zeros()) as usize;
+        // NOTE: Writing to a Vec can not fail.
+        let _ = CompactSize::write(&mut bytes, levels);
+        for level in 1..=levels {
+            let parent = if position & (1 << level) != 0 {
+                ommers.next()
+            } else {
+                None
+            };
+            write_optional_node(&mut bytes, parent);
+        }
+        bytes
+    }
+}
+
+/// Sapling and Orchard frontiers of the note commitment trees at a block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreeFrontiers {
+    /// Height of the block.
+    pub height: u32,
+    /// Hash of the block, hex-encoded.
+    pub hash: String,
+    /// Sapling frontier, None below Sapling activation.
+    pub sapling: Option<TreeFrontier>,
+    /// Orchard frontier, None below NU5 activation.
+    pub orchard: Option<TreeFrontier>,
+}
+
+impl TryFrom<&GetTreestateResponse> for TreeFrontiers {
+    type Error = ParseError;
+
+    fn try_from(treestate: &GetTreestateResponse) -> Result<Self, Self::Error> {
+        let height = u32::try_from(treestate.height).map_err(|_| {
+            ParseError::InvalidData(format!("Invalid tree state height {}", treestate.height))
+        })?;
+        Ok(TreeFrontiers {
+            height,
+            hash: treestate.hash.clone(),
+            sapling: TreeFrontier::from_final_state(&treestate.sapling.commitments.final_state)?,
+            orchard: TreeFrontier::from_final_state(&treestate.orchard.commitments.final_state)?,
+        })
+    }
+}
+
+/// Reads a presence byte followed by a 32 byte node if present.
+fn read_optional_node(cursor: &mut Cursor<&[u8]>) -> Result<Option<[u8; 32]>, ParseError> {
+    match read_bytes(cursor, 1, "Error reading tree node presence")?[0] {
+        0 => Ok(None),
+        1 => {
+            let mut node = [0; 32];
+            node.copy_from_slice(&read_bytes(cursor, 32, "Error reading tree node")?);
+            Ok(Some(node))
+        }
+        flag => Err(ParseError::InvalidData(format!(
+            "Invalid tree node presence byte {}",
+            flag
+        ))),
+    }
+}
+
+/// Writes a presence byte followed by the 32 byte node if present.
+fn write_optional_node(bytes: &mut Vec<u8>, node: Option<&[u8; 32]>) {
+    match node {
+        Some(node) => {
+            bytes.push(1);
+            bytes.extend_from_slice(node);
+        }
+        None => bytes.push(0),
+    }
+}
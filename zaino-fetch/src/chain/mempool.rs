@@ -1,11 +1,18 @@
 //! Zingo-Indexer mempool state functionality.
 
-use std::{collections::HashSet, time::SystemTime};
+use std::{
+    collections::{HashMap, HashSet},
+    time::SystemTime,
+};
 use tokio::sync::{Mutex, RwLock};
 
 use crate::{
-    chain::error::MempoolError, jsonrpc::connector::JsonRpcConnector,
-    primitives::block::BlockHash,
+    chain::{error::MempoolError, transaction::FullTransaction, utils::ParseFromSlice},
+    jsonrpc::{connector::JsonRpcConnector, response::GetTransactionResponse},
+    primitives::{
+        address::TransparentAddress, block::BlockHash, chain::NetworkKind,
+        transaction::TransactionHash,
+    },
 };
 
 /// Mempool state information.
@@ -37,31 +44,24 @@ impl Mempool {
         }
     }
 
-    /// Updates the mempool, returns true if the current block in the mempool has been mined.
-    pub async fn update(&self, zebrad_uri: &http::Uri) -> Result<bool, MempoolError> {
+    /// Updates the mempool from the node behind `zebrad_client`, returns true if the current block in the mempool
+    /// has been mined.
+    pub async fn update(&self, zebrad_client: &JsonRpcConnector) -> Result<bool, MempoolError> {
         self.update_last_sync_time().await?;
-        let mined = self.check_and_update_best_block_hash(zebrad_uri).await?;
+        let mined = self.check_and_update_best_block_hash(zebrad_client).await?;
         if mined {
             self.reset_txids().await?;
-            self.update_txids(zebrad_uri).await?;
+            self.update_txids(zebrad_client).await?;
             Ok(true)
         } else {
-            self.update_txids(zebrad_uri).await?;
+            self.update_txids(zebrad_client).await?;
             Ok(false)
         }
     }
 
     /// Updates the txids in the mempool.
-    async fn update_txids(&self, zebrad_uri: &http::Uri) -> Result<(), MempoolError> {
-        let node_txids = JsonRpcConnector::new(
-            zebrad_uri.clone(),
-            Some("xxxxxx".to_string()),
-            Some("xxxxxx".to_string()),
-        )
-        .await
-        .get_raw_mempool()
-        .await?
-        .transactions;
+    async fn update_txids(&self, zebrad_client: &JsonRpcConnector) -> Result<(), MempoolError> {
+        let node_txids = zebrad_client.get_raw_mempool().await?.transactions;
         let mut txids_seen = self.txids_seen.lock().await;
         let mut txids = self.txids.write().await;
         for txid in node_txids {
@@ -83,17 +83,9 @@ impl Mempool {
     /// Updates the mempool blockchain info, returns true if the current block in the mempool has been mined.
     async fn check_and_update_best_block_hash(
         &self,
-        zebrad_uri: &http::Uri,
+        zebrad_client: &JsonRpcConnector,
     ) -> Result<bool, MempoolError> {
-        let node_best_block_hash = JsonRpcConnector::new(
-            zebrad_uri.clone(),
-            Some("xxxxxx".to_string()),
-            Some("xxxxxx".to_string()),
-        )
-        .await
-        .get_blockchain_info()
-        .await?
-        .best_block_hash;
+        let node_best_block_hash = zebrad_client.get_blockchain_info().await?.best_block_hash;
 
         let mut last_best_block_hash = self.best_block_hash.write().await;
 
@@ -128,3 +120,180 @@ impl Mempool {
         Ok(*best_block_hash)
     }
 }
+
+/// Exclude list used to filter mempool transactions, as given to GetMempoolTx.
+///
+/// Full length txids are held in a `HashSet<TransactionHash>` for O(1) membership checks.
+/// Shortened txids (prefixes) are grouped by length so each mempool txid is only checked once per distinct prefix length.
+///
+/// All txids are held in internal (little-endian) byte order, as sent by the wallet.
+#[derive(Debug, Default)]
+pub struct MempoolExcludeFilter {
+    /// Full length (32 byte) txids to exclude.
+    txids: HashSet<TransactionHash>,
+    /// Shortened txids to exclude, keyed by prefix length.
+    prefixes: HashMap<usize, HashSet<Vec<u8>>>,
+}
+
+impl MempoolExcludeFilter {
+    /// Builds a new filter from the raw txid bytes of an exclude list.
+    ///
+    /// Empty entries and entries longer than 32 bytes can never match a txid and are ignored.
+    pub fn new(exclude: Vec<Vec<u8>>) -> Self {
+        let mut filter = MempoolExcludeFilter::default();
+        for txid in exclude {
+            match txid.len() {
+                32 => {
+                    let mut hash = [0u8; 32];
+                    hash.copy_from_slice(&txid);
+                    filter.txids.insert(TransactionHash(hash));
+                }
+                1..=31 => {
+                    filter.prefixes.entry(txid.len()).or_default().insert(txid);
+                }
+                _ => {}
+            }
+        }
+        filter
+    }
+
+    /// Returns true if there are no txids in the exclude list.
+    pub fn is_empty(&self) -> bool {
+        self.txids.is_empty() && self.prefixes.is_empty()
+    }
+
+    /// Returns the mempool txids not matched by the exclude list, preserving their order.
+    ///
+    /// If more than one mempool txid matches a shortened txid none of them are excluded.
+    pub fn filter(&self, mempool_txids: Vec<TransactionHash>) -> Vec<TransactionHash> {
+        if self.is_empty() {
+            return mempool_txids;
+        }
+
+        let mut prefix_matches: HashMap<&[u8], usize> = HashMap::new();
+        for txid in mempool_txids.iter() {
+            for (len, prefixes) in self.prefixes.iter() {
+                if let Some(prefix) = prefixes.get(&txid.0[..*len]) {
+                    *prefix_matches.entry(prefix.as_slice()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        mempool_txids
+            .into_iter()
+            .filter(|txid| {
+                if self.txids.contains(txid) {
+                    return false;
+                }
+                !self.prefixes.iter().any(|(len, prefixes)| {
+                    prefixes.contains(&txid.0[..*len])
+                        && prefix_matches.get(&txid.0[..*len]) == Some(&1)
+                })
+            })
+            .collect()
+    }
+}
+
+/// Transparent address list used to filter mempool transactions, as given to GetTaddressMempoolStream.
+///
+/// A transaction matches if one of its transparent outputs pays to a listed address, or one of its transparent inputs
+/// spends an output paid to a listed address. Addresses are compared in their Base58Check encoding, so the list may
+/// hold mainnet and testnet / regtest addresses.
+#[derive(Debug, Default)]
+pub struct MempoolAddressFilter {
+    /// Base58Check encoded addresses to match.
+    addresses: HashSet<String>,
+}
+
+impl MempoolAddressFilter {
+    /// Builds a new filter from the addresses of an address list.
+    pub fn new(addresses: Vec<String>) -> Self {
+        MempoolAddressFilter {
+            addresses: addresses.into_iter().collect(),
+        }
+    }
+
+    /// Returns true if there are no addresses in the address list, no transaction matches an empty filter.
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    /// Returns true if the output script pays to a listed address.
+    pub fn matches_script(&self, script_pubkey: &[u8]) -> bool {
+        [NetworkKind::Mainnet, NetworkKind::Testnet]
+            .into_iter()
+            .filter_map(|network_kind| {
+                TransparentAddress::from_script_pubkey(script_pubkey, network_kind)
+            })
+            .any(|address| self.addresses.contains(&address.to_string()))
+    }
+
+    /// Returns true if the transaction spends from or pays to a listed address.
+    ///
+    /// Outputs are checked first, the transactions holding the outputs spent by the transaction's inputs are only
+    /// fetched from the node if no output matches.
+    pub async fn matches(
+        &self,
+        transaction: &FullTransaction,
+        zebrad_client: &JsonRpcConnector,
+    ) -> Result<bool, MempoolError> {
+        if self.is_empty() {
+            return Ok(false);
+        }
+        if transaction
+            .raw_transaction
+            .transparent_outputs
+            .iter()
+            .any(|output| self.matches_script(&output.script_pubkey))
+        {
+            return Ok(true);
+        }
+
+        let mut spent_outputs: HashMap<&[u8], Vec<u32>> = HashMap::new();
+        for input in transaction.raw_transaction.transparent_inputs.iter() {
+            if !input.is_coinbase() {
+                spent_outputs
+                    .entry(input.prev_tx_hash.as_slice())
+                    .or_default()
+                    .push(input.prev_tx_out_index);
+            }
+        }
+        for (prev_tx_hash, indexes) in spent_outputs {
+            let prev_txid = hex::encode(prev_tx_hash.iter().rev().copied().collect::<Vec<u8>>());
+            let raw_transaction = match zebrad_client
+                .get_raw_transaction(prev_txid, Some(0))
+                .await?
+            {
+                GetTransactionResponse::Raw(raw) => raw.bytes,
+                GetTransactionResponse::Object { hex, .. } => hex.bytes,
+            };
+            let (_, prev_transaction) = FullTransaction::parse_from_slice(
+                &raw_transaction,
+                Some(vec![prev_tx_hash.to_vec()]),
+                None,
+            )?;
+            let prev_outputs = &prev_transaction.raw_transaction.transparent_outputs;
+            if indexes.iter().any(|index| {
+                prev_outputs
+                    .get(*index as usize)
+                    .is_some_and(|output| self.matches_script(&output.script_pubkey))
+            }) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// A change to the mempool, emitted by a mempool poller diffing successive snapshots of the node's mempool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MempoolUpdate {
+    /// A transaction has entered the mempool.
+    Added(TransactionHash),
+    /// A transaction has left the mempool (mined, expired or evicted).
+    Removed(TransactionHash),
+    /// The subscriber fell behind and the given number of updates were dropped.
+    ///
+    /// Subscribers receiving this should re-fetch the full mempool to resync.
+    Lagged(u64),
+}
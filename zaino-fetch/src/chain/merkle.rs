@@ -0,0 +1,91 @@
+//! Zingo-Indexer transaction Merkle tree, used to prove a transaction's inclusion in a block.
+//!
+//! Zcash inherits Bitcoin's transaction Merkle tree: leaves are txids in internal byte order, each parent is the
+//! double SHA-256 of its two children, and a level with an odd number of nodes pairs its last node with itself.
+
+use sha2::{Digest, Sha256};
+
+use crate::primitives::{block::MerkleRoot, transaction::TransactionHash};
+
+/// Merkle branch proving the inclusion of a transaction in a block's transaction Merkle tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerkleBranch {
+    /// Index of the transaction in the block.
+    pub index: u32,
+    /// Sibling hashes from the transaction's leaf up to the root, in internal byte order.
+    pub hashes: Vec<[u8; 32]>,
+}
+
+impl MerkleBranch {
+    /// Returns the branch of the transaction at `index` in `txids`, None if `index` is out of range.
+    ///
+    /// `txids` must hold all of the block's transactions, in block order.
+    pub fn new(txids: &[TransactionHash], index: usize) -> Option<Self> {
+        if index >= txids.len() {
+            return None;
+        }
+        let mut level: Vec<[u8; 32]> = txids.iter().map(|txid| txid.0).collect();
+        let mut position = index;
+        let mut hashes = Vec::new();
+        while level.len() > 1 {
+            let sibling = match level.get(position ^ 1) {
+                Some(sibling) => *sibling,
+                None => level[position],
+            };
+            hashes.push(sibling);
+            level = parent_level(&level);
+            position /= 2;
+        }
+        Some(MerkleBranch {
+            index: index as u32,
+            hashes,
+        })
+    }
+
+    /// Returns the Merkle root obtained by hashing `txid` up the branch.
+    ///
+    /// The transaction is included in the block if the root matches the block header's Merkle root.
+    pub fn root(&self, txid: &TransactionHash) -> MerkleRoot {
+        let mut node = txid.0;
+        let mut position = self.index;
+        for sibling in &self.hashes {
+            node = if position & 1 == 0 {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            };
+            position >>= 1;
+        }
+        MerkleRoot(node)
+    }
+}
+
+/// Returns the Merkle root of a block's transactions, None if `txids` is empty.
+pub fn merkle_root(txids: &[TransactionHash]) -> Option<MerkleRoot> {
+    let mut level: Vec<[u8; 32]> = txids.iter().map(|txid| txid.0).collect();
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level.first().map(|root| MerkleRoot(*root))
+}
+
+/// Returns the parents of a level of the tree, pairing an odd last node with itself.
+fn parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+/// Returns the double SHA-256 of the concatenated nodes.
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut node = [0; 64];
+    node[..32].copy_from_slice(left);
+    node[32..].copy_from_slice(right);
+    double_sha256(&node)
+}
+
+/// Returns the double SHA-256 of `data`, the hash used for txids of pre-v5 transactions and Merkle tree nodes.
+pub fn double_sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
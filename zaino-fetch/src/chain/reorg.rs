@@ -0,0 +1,339 @@
+//! Zingo-Indexer chain tip tracking and reorg detection.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::broadcast;
+
+use crate::{
+    chain::blockcache::{BlockCache, MAX_REORG_DEPTH},
+    jsonrpc::{
+        connector::JsonRpcConnector, error::JsonRpcConnectorError, response::GetBlockResponse,
+    },
+    primitives::block::BlockHash,
+    task::spawn_named,
+};
+
+/// The tip of a chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainTip {
+    /// Height of the tip block.
+    pub height: u32,
+    /// Hash of the tip block.
+    pub hash: BlockHash,
+}
+
+/// A chain reorganisation, emitted when the node's best chain no longer extends the previously tracked chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorgEvent {
+    /// Tip of the abandoned chain.
+    pub old_tip: ChainTip,
+    /// Tip of the new best chain.
+    pub new_tip: ChainTip,
+    /// Height of the highest block shared by the old and new chains, blocks above this height were rolled back.
+    ///
+    /// If the fork point lies below the tracked window this is the lowest height that could be checked.
+    pub fork_height: u32,
+}
+
+/// The node's chain state as of the most recent successful `getblockchaininfo` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeSync {
+    /// Time of the call.
+    pub time: Instant,
+    /// Height of the node's best chain tip.
+    pub tip_height: u32,
+    /// The node's estimate of the network's chain height.
+    pub estimated_height: u32,
+}
+
+/// Holds the reorg and node sync status of a [`ChainTipTracker`].
+#[derive(Debug, Clone, Default)]
+pub struct ChainTipStatus {
+    /// Most recently detected reorg.
+    last_reorg: Arc<RwLock<Option<ReorgEvent>>>,
+    /// Number of reorgs detected.
+    reorg_count: Arc<AtomicU64>,
+    /// Most recent successful node sync.
+    last_sync: Arc<RwLock<Option<NodeSync>>>,
+}
+
+impl ChainTipStatus {
+    /// Returns the most recently detected reorg.
+    pub fn last_reorg(&self) -> Option<ReorgEvent> {
+        self.last_reorg
+            .read()
+            .ok()
+            .and_then(|last_reorg| *last_reorg)
+    }
+
+    /// Returns the number of reorgs detected.
+    pub fn reorg_count(&self) -> u64 {
+        self.reorg_count.load(Ordering::Relaxed)
+    }
+
+    /// Records a detected reorg.
+    fn record(&self, event: ReorgEvent) {
+        if let Ok(mut last_reorg) = self.last_reorg.write() {
+            *last_reorg = Some(event);
+        }
+        self.reorg_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the most recent successful node sync, None if the node has not been reached yet.
+    pub fn last_sync(&self) -> Option<NodeSync> {
+        self.last_sync.read().ok().and_then(|last_sync| *last_sync)
+    }
+
+    /// Records a successful node sync.
+    fn record_sync(&self, tip_height: u32, estimated_height: u32) {
+        if let Ok(mut last_sync) = self.last_sync.write() {
+            *last_sync = Some(NodeSync {
+                time: Instant::now(),
+                tip_height,
+                estimated_height,
+            });
+        }
+    }
+}
+
+/// Tracks the hashes of the last reorg confirmation depth blocks of the best chain across sync iterations,
+/// emitting a [`ReorgEvent`] when the chain is reorganised.
+///
+/// The depth is [`MAX_REORG_DEPTH`] unless set with [`ChainTipTracker::with_reorg_confirmation_depth`].
+pub struct ChainTipTracker {
+    /// Best chain block hashes keyed by height.
+    chain: BTreeMap<u32, BlockHash>,
+    /// Number of blocks below the tip tracked, reorgs deeper than this are reported at the bottom of the window.
+    reorg_confirmation_depth: u32,
+    /// Broadcast channel used to send reorgs to subscribers.
+    sender: broadcast::Sender<ReorgEvent>,
+    /// Reorg status.
+    status: ChainTipStatus,
+}
+
+impl Default for ChainTipTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChainTipTracker {
+    /// Capacity of the broadcast channel.
+    const CHANNEL_CAPACITY: usize = 64;
+
+    /// Returns a new tracker with no tracked chain.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(Self::CHANNEL_CAPACITY);
+        ChainTipTracker {
+            chain: BTreeMap::new(),
+            reorg_confirmation_depth: MAX_REORG_DEPTH,
+            sender,
+            status: ChainTipStatus::default(),
+        }
+    }
+
+    /// Sets the number of blocks below the tip tracked.
+    pub fn with_reorg_confirmation_depth(mut self, depth: u32) -> Self {
+        self.reorg_confirmation_depth = depth;
+        self
+    }
+
+    /// Returns the tip of the tracked chain.
+    pub fn tip(&self) -> Option<ChainTip> {
+        self.chain.last_key_value().map(|(height, hash)| ChainTip {
+            height: *height,
+            hash: *hash,
+        })
+    }
+
+    /// Returns the hash of the tracked chain's block at `height`, None if the height is outside the tracked window.
+    pub fn hash_at(&self, height: u32) -> Option<BlockHash> {
+        self.chain.get(&height).copied()
+    }
+
+    /// Returns a receiver for reorgs detected after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<ReorgEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the tracker's reorg status.
+    pub fn status(&self) -> ChainTipStatus {
+        self.status.clone()
+    }
+
+    /// Updates the tracked chain to the node's best chain `tip`, returns the reorg if one is detected.
+    ///
+    /// `fetch_hash` returns the hash of the node's best chain block at a given height. It is used to walk back
+    /// from the new tip until the new chain links back into the tracked chain, so multi-block rollbacks
+    /// report the correct fork height.
+    pub async fn sync<F, Fut, E>(
+        &mut self,
+        tip: ChainTip,
+        mut fetch_hash: F,
+    ) -> Result<Option<ReorgEvent>, E>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<BlockHash, E>>,
+    {
+        let old_tip = match self.tip() {
+            Some(old_tip) => old_tip,
+            None => {
+                self.chain.insert(tip.height, tip.hash);
+                return Ok(None);
+            }
+        };
+        if old_tip == tip {
+            return Ok(None);
+        }
+
+        // Walk back from the highest height held by both chains until the hashes agree.
+        let mut new_chain: HashMap<u32, BlockHash> = HashMap::from([(tip.height, tip.hash)]);
+        let mut height = tip.height.min(old_tip.height);
+        let fork_height = loop {
+            let new_hash = match new_chain.get(&height) {
+                Some(hash) => *hash,
+                None => {
+                    let hash = fetch_hash(height).await?;
+                    new_chain.insert(height, hash);
+                    hash
+                }
+            };
+            match self.chain.get(&height) {
+                Some(old_hash) if *old_hash != new_hash && height > 0 => height -= 1,
+                _ => break height,
+            }
+        };
+
+        // Fill the tracked window between the fork point and the new tip.
+        self.chain.retain(|height, _| *height <= fork_height);
+        let window_start = tip
+            .height
+            .saturating_sub(self.reorg_confirmation_depth)
+            .max(fork_height.saturating_add(1));
+        for height in window_start..=tip.height {
+            let hash = match new_chain.get(&height) {
+                Some(hash) => *hash,
+                None => fetch_hash(height).await?,
+            };
+            self.chain.insert(height, hash);
+        }
+        let window_end = tip.height.saturating_sub(self.reorg_confirmation_depth);
+        self.chain.retain(|height, _| *height >= window_end);
+
+        if fork_height >= old_tip.height {
+            return Ok(None);
+        }
+        let event = ReorgEvent {
+            old_tip,
+            new_tip: tip,
+            fork_height,
+        };
+        self.status.record(event);
+        // NOTE: Send only fails when there are no active subscribers, events are dropped in that case.
+        let _ = self.sender.send(event);
+        Ok(Some(event))
+    }
+}
+
+/// Polls the node's best chain tip, dropping reorganised blocks from the block cache and broadcasting reorgs to subscribers.
+pub struct ChainTipMonitor {
+    /// Broadcast channel used to send reorgs to subscribers.
+    sender: broadcast::Sender<ReorgEvent>,
+    /// Reorg status.
+    status: ChainTipStatus,
+    /// Handle of the polling task.
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl ChainTipMonitor {
+    /// Spawns a chain tip monitor polling zebrad / zcashd through `zebrad_client` every `poll_interval` while `online`
+    /// is true.
+    ///
+    /// The chain is tracked to the block cache's reorg confirmation depth.
+    pub fn spawn(
+        zebrad_client: JsonRpcConnector,
+        block_cache: BlockCache,
+        poll_interval: Duration,
+        online: Arc<AtomicBool>,
+    ) -> Self {
+        let mut tracker = ChainTipTracker::new()
+            .with_reorg_confirmation_depth(block_cache.reorg_confirmation_depth());
+        let sender = tracker.sender.clone();
+        let status = tracker.status();
+        let handle = spawn_named("chain_tip_monitor", async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            while online.load(Ordering::SeqCst) {
+                interval.tick().await;
+                let tip = match zebrad_client.get_blockchain_info().await {
+                    Ok(blockchain_info) => {
+                        tracker.status.record_sync(
+                            blockchain_info.blocks.0,
+                            blockchain_info.estimated_height.0,
+                        );
+                        block_cache.update_tip(
+                            blockchain_info.blocks.0,
+                            &blockchain_info.best_block_hash.0,
+                        );
+                        ChainTip {
+                            height: blockchain_info.blocks.0,
+                            hash: blockchain_info.best_block_hash,
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to fetch chain tip: {}.", e);
+                        continue;
+                    }
+                };
+                let fetch_hash = |height: u32| {
+                    let zebrad_client = &zebrad_client;
+                    async move {
+                        match zebrad_client.get_block(height, Some(1)).await? {
+                            GetBlockResponse::Object { hash, .. } => Ok(hash.0),
+                            GetBlockResponse::Raw(_) => Err(JsonRpcConnectorError::new(
+                                "Received raw block type, this should not be possible here.",
+                            )),
+                        }
+                    }
+                };
+                match tracker.sync(tip, fetch_hash).await {
+                    Ok(Some(event)) => {
+                        println!(
+                            "Chain reorg detected: fork at height {}, old tip {} -> new tip {}.",
+                            event.fork_height, event.old_tip.height, event.new_tip.height
+                        );
+                        block_cache.invalidate_from(event.fork_height.saturating_add(1));
+                    }
+                    Ok(None) => {}
+                    Err(e) => eprintln!("Failed to sync chain tip: {}.", e),
+                }
+            }
+        });
+        ChainTipMonitor {
+            sender,
+            status,
+            handle,
+        }
+    }
+
+    /// Returns a receiver for reorgs detected after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<ReorgEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the monitor's reorg status.
+    pub fn status(&self) -> ChainTipStatus {
+        self.status.clone()
+    }
+
+    /// Stops the polling task.
+    pub fn shutdown(self) {
+        self.handle.abort();
+    }
+}
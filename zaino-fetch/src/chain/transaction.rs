@@ -1,19 +1,33 @@
 //! Transaction fetching and deserialization functionality.
 
-use crate::chain::{
-    error::ParseError,
-    utils::{read_bytes, read_u32, read_u64, skip_bytes, CompactSize, ParseFromSlice},
+use crate::{
+    chain::{
+        error::ParseError,
+        utils::{read_bytes, read_u32, read_u64, skip_bytes, CompactSize, ParseFromSlice},
+    },
+    primitives::chain::TipConsensusBranch,
 };
 use std::io::Cursor;
 use zaino_proto::proto::compact_formats::{
     CompactOrchardAction, CompactSaplingOutput, CompactSaplingSpend, CompactTx,
 };
 
+/// Maximum size of a transaction, in bytes.
+///
+/// Transactions can not be larger than a 2MB block.
+pub const MAX_TRANSACTION_SIZE: usize = 2_000_000;
+
 /// Txin format as described in https://en.bitcoin.it/wiki/Transaction
 #[derive(Debug)]
 pub struct TxIn {
-    // PrevTxHash [IGNORED] - Size[bytes]: 32
-    // PrevTxOutIndex [IGNORED] - Size[bytes]: 4
+    /// Txid of the transaction holding the spent output, in internal byte order.
+    ///
+    /// Size[bytes]: 32
+    pub prev_tx_hash: Vec<u8>,
+    /// Index of the spent output.
+    ///
+    /// Size[bytes]: 4
+    pub prev_tx_out_index: u32,
     /// CompactSize-prefixed, could be a pubkey or a script
     ///
     /// Size[bytes]: CompactSize
@@ -39,8 +53,8 @@ impl ParseFromSlice for TxIn {
         }
         let mut cursor = Cursor::new(data);
 
-        skip_bytes(&mut cursor, 32, "Error skipping TxIn::PrevTxHash")?;
-        skip_bytes(&mut cursor, 4, "Error skipping TxIn::PrevTxOutIndex")?;
+        let prev_tx_hash = read_bytes(&mut cursor, 32, "Error reading TxIn::PrevTxHash")?;
+        let prev_tx_out_index = read_u32(&mut cursor, "Error reading TxIn::PrevTxOutIndex")?;
         let script_sig = {
             let compact_length = CompactSize::read(&mut cursor)?;
             read_bytes(
@@ -51,7 +65,21 @@ impl ParseFromSlice for TxIn {
         };
         skip_bytes(&mut cursor, 4, "Error skipping TxIn::SequenceNumber")?;
 
-        Ok((&data[cursor.position() as usize..], TxIn { script_sig }))
+        Ok((
+            &data[cursor.position() as usize..],
+            TxIn {
+                prev_tx_hash,
+                prev_tx_out_index,
+                script_sig,
+            },
+        ))
+    }
+}
+
+impl TxIn {
+    /// Returns true if the input is a coinbase input, which does not spend a previous output.
+    pub fn is_coinbase(&self) -> bool {
+        self.prev_tx_out_index == u32::MAX && self.prev_tx_hash.iter().all(|byte| *byte == 0)
     }
 }
 
@@ -62,7 +90,10 @@ pub struct TxOut {
     ///
     /// Size[bytes]: 8
     pub value: u64,
-    // Script [IGNORED] - Size[bytes]: CompactSize
+    /// CompactSize-prefixed script setting the conditions to spend the output.
+    ///
+    /// Size[bytes]: CompactSize
+    pub script_pubkey: Vec<u8>,
 }
 
 impl ParseFromSlice for TxOut {
@@ -84,14 +115,22 @@ impl ParseFromSlice for TxOut {
         let mut cursor = Cursor::new(data);
 
         let value = read_u64(&mut cursor, "Error TxOut::reading Value")?;
-        let compact_length = CompactSize::read(&mut cursor)?;
-        skip_bytes(
-            &mut cursor,
-            compact_length as usize,
-            "Error skipping TxOut::Script",
-        )?;
+        let script_pubkey = {
+            let compact_length = CompactSize::read(&mut cursor)?;
+            read_bytes(
+                &mut cursor,
+                compact_length as usize,
+                "Error reading TxOut::Script",
+            )?
+        };
 
-        Ok((&data[cursor.position() as usize..], TxOut { value }))
+        Ok((
+            &data[cursor.position() as usize..],
+            TxOut {
+                value,
+                script_pubkey,
+            },
+        ))
     }
 }
 
@@ -777,6 +816,47 @@ impl FullTransaction {
         })
     }
 
+    /// Parses a raw transaction submitted for relay, rejecting submissions that can not be valid.
+    ///
+    /// Rejects empty and oversized (see [`MAX_TRANSACTION_SIZE`]) payloads, payloads that do not parse as a single
+    /// transaction and v5 transactions whose consensus branch id matches neither the node's chain tip nor next block.
+    /// This is only a sanity check, full validation is left to the node.
+    pub fn parse_for_relay(
+        data: &[u8],
+        consensus: &TipConsensusBranch,
+    ) -> Result<Self, ParseError> {
+        if data.is_empty() {
+            return Err(ParseError::InvalidData("transaction is empty".to_string()));
+        }
+        if data.len() > MAX_TRANSACTION_SIZE {
+            return Err(ParseError::InvalidData(format!(
+                "transaction size {} exceeds the maximum of {} bytes",
+                data.len(),
+                MAX_TRANSACTION_SIZE
+            )));
+        }
+        let (remaining_data, transaction) =
+            FullTransaction::parse_from_slice(data, Some(vec![Vec::new()]), None)?;
+        if !remaining_data.is_empty() {
+            return Err(ParseError::InvalidData(format!(
+                "{} trailing bytes after transaction",
+                remaining_data.len()
+            )));
+        }
+        if transaction.raw_transaction.version >= 5 {
+            let branch_id = transaction.raw_transaction.consensus_branch_id;
+            let chain_tip = u32::from(consensus.chain_tip.0);
+            let next_block = u32::from(consensus.next_block.0);
+            if branch_id != chain_tip && branch_id != next_block {
+                return Err(ParseError::InvalidData(format!(
+                    "v{} transaction consensus branch id {:08x} does not match the current branch id {:08x}",
+                    transaction.raw_transaction.version, branch_id, next_block
+                )));
+            }
+        }
+        Ok(transaction)
+    }
+
     /// Returns true if the transaction contains either sapling spends or outputs.
     pub fn has_shielded_elements(&self) -> bool {
         !self.raw_transaction.shielded_spends.is_empty()
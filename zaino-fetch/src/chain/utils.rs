@@ -31,11 +31,18 @@ pub fn skip_bytes(cursor: &mut Cursor<&[u8]>, n: usize, error_msg: &str) -> Resu
 }
 
 /// Reads the next n bytes from cursor into a vec<u8>, returns error message given if eof is reached..
+///
+/// `n` is checked against the bytes remaining in the cursor before the buffer is allocated, so a hostile length
+/// prefix can not trigger an allocation larger than the input.
 pub fn read_bytes(
     cursor: &mut Cursor<&[u8]>,
     n: usize,
     error_msg: &str,
 ) -> Result<Vec<u8>, ParseError> {
+    let remaining = (cursor.get_ref().len() as u64).saturating_sub(cursor.position());
+    if n as u64 > remaining {
+        return Err(ParseError::InvalidData(error_msg.to_string()));
+    }
     let mut buf = vec![0; n];
     cursor
         .read_exact(&mut buf)
@@ -43,6 +50,24 @@ pub fn read_bytes(
     Ok(buf)
 }
 
+/// Reads a CompactSize length prefix from cursor, returns an InvalidData error if the length is above `max_len`.
+///
+/// Used for lengths read from untrusted input so oversized lengths are rejected before any read or allocation.
+pub fn read_length_prefix(
+    cursor: &mut Cursor<&[u8]>,
+    max_len: usize,
+    error_msg: &str,
+) -> Result<usize, ParseError> {
+    let len = CompactSize::read(&mut *cursor)?;
+    match usize::try_from(len) {
+        Ok(len) if len <= max_len => Ok(len),
+        _ => Err(ParseError::InvalidData(format!(
+            "{}: length {} is above the maximum of {}",
+            error_msg, len, max_len
+        ))),
+    }
+}
+
 /// Reads the next 8 bytes from cursor into a u64, returns error message given if eof is reached..
 pub fn read_u64(cursor: &mut Cursor<&[u8]>, error_msg: &str) -> Result<u64, ParseError> {
     cursor
@@ -171,6 +196,8 @@ impl CompactSize {
     }
 
     /// Writes the provided `usize` value to the provided Writer in compact form.
+    ///
+    /// Always emits the canonical (shortest) 1, 3, 5 or 9 byte encoding, as required by [`CompactSize::read`].
     pub fn write<W: Write>(mut writer: W, size: usize) -> io::Result<()> {
         match size {
             s if s < 253 => writer.write_u8(s as u8),
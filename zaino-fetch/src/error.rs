@@ -0,0 +1,76 @@
+//! Hold the unified error type for Zaino-Fetch.
+
+use std::fmt::Write;
+
+use crate::{
+    chain::error::{BlockCacheError, MempoolError, ParseError},
+    jsonrpc::error::JsonRpcConnectorError,
+    primitives::error::SerializationError,
+};
+
+/// Zaino-Fetch errors, wraps the errors of each subsystem.
+///
+/// The wrapped error is returned by [`std::error::Error::source`], use [`error_chain`] to log the full chain.
+#[derive(Debug, thiserror::Error)]
+pub enum ZainoError {
+    /// Errors from the JsonRPC client.
+    #[error("JsonRPC connector error")]
+    JsonRpc(#[from] JsonRpcConnectorError),
+
+    /// Errors parsing blocks and transactions.
+    #[error("Parse error")]
+    Parse(#[from] ParseError),
+
+    /// Errors from the block cache.
+    #[error("Block cache error")]
+    BlockCache(#[from] BlockCacheError),
+
+    /// Errors from the mempool.
+    #[error("Mempool error")]
+    Mempool(#[from] MempoolError),
+
+    /// Errors (de)serializing primitives.
+    #[error("Serialization error")]
+    Serialization(#[from] SerializationError),
+}
+
+impl ZainoError {
+    /// Maps ZainoError to tonic::Status, using the mapping of the wrapped error.
+    pub fn to_grpc_status(&self) -> tonic::Status {
+        match self {
+            ZainoError::JsonRpc(e) => e.to_grpc_status(),
+            ZainoError::Parse(e) => e.to_grpc_status(),
+            ZainoError::BlockCache(e) => e.to_grpc_status(),
+            ZainoError::Mempool(e) => e.to_grpc_status(),
+            ZainoError::Serialization(_) => {
+                eprintln!("Error occurred: {}.", error_chain(self));
+                tonic::Status::internal(self.to_string())
+            }
+        }
+    }
+}
+
+impl From<ZainoError> for tonic::Status {
+    fn from(err: ZainoError) -> Self {
+        err.to_grpc_status()
+    }
+}
+
+/// Returns the messages of `error` and each of its sources, separated by ": ".
+///
+/// Several errors already include their source's message in their own, a source is only appended
+/// if the preceding message does not already end with it.
+pub fn error_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut chain = error.to_string();
+    let mut previous = chain.clone();
+    let mut source = error.source();
+    while let Some(error) = source {
+        let message = error.to_string();
+        if !previous.ends_with(&message) {
+            let _ = write!(chain, ": {}", message);
+        }
+        previous = message;
+        source = error.source();
+    }
+    chain
+}
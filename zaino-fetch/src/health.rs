@@ -0,0 +1,215 @@
+//! Zingo-Indexer health check endpoint.
+//!
+//! Serves `/healthz`, returning 200 while the node is reachable and synced and 503 otherwise, for use as a readiness probe.
+//! While the indexer is warming up its caches `/healthz` reports `starting`.
+
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, StatusCode,
+};
+
+use crate::{
+    chain::reorg::{ChainTipStatus, NodeSync},
+    primitives::height::ChainHeight,
+    task::spawn_named,
+};
+
+/// Thresholds used to decide whether Zaino is healthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthConfig {
+    /// Maximum time since the last successful `getblockchaininfo` call.
+    pub max_staleness: Duration,
+    /// Maximum number of blocks the node's tip may be behind its estimated network height.
+    pub max_block_lag: u32,
+}
+
+/// A failed health check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCheck {
+    /// The node has not been reached within [`HealthConfig::max_staleness`].
+    Stale,
+    /// The node's tip is more than [`HealthConfig::max_block_lag`] blocks behind its estimated height.
+    Lagging,
+    /// The indexer has not finished warming up, see [`WarmupStatus`].
+    Starting,
+}
+
+/// Tracks whether the indexer has finished warming up its caches, clones share the same state.
+#[derive(Debug, Clone)]
+pub struct WarmupStatus {
+    /// True once warm-up has completed or been abandoned.
+    complete: Arc<AtomicBool>,
+    /// True if warm-up was abandoned and the indexer started with cold caches.
+    degraded: Arc<AtomicBool>,
+}
+
+impl WarmupStatus {
+    /// Returns a status for an indexer that has not finished warming up.
+    pub fn starting() -> Self {
+        WarmupStatus {
+            complete: Arc::new(AtomicBool::new(false)),
+            degraded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns a status for an indexer that does not warm up.
+    pub fn complete() -> Self {
+        let status = WarmupStatus::starting();
+        status.mark_complete();
+        status
+    }
+
+    /// Records that warm-up has completed.
+    pub fn mark_complete(&self) {
+        self.complete.store(true, Ordering::SeqCst);
+    }
+
+    /// Records that warm-up was abandoned, the indexer serves requests with cold caches.
+    pub fn mark_degraded(&self) {
+        self.degraded.store(true, Ordering::SeqCst);
+        self.complete.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true once warm-up has completed or been abandoned.
+    pub fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::SeqCst)
+    }
+
+    /// Returns true if warm-up was abandoned.
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::SeqCst)
+    }
+}
+
+/// Result of the health checks, returned as the JSON body of `/healthz`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct HealthReport {
+    /// True if all checks passed.
+    pub healthy: bool,
+    /// Checks that failed.
+    pub failed_checks: Vec<HealthCheck>,
+    /// Seconds since the last successful `getblockchaininfo` call, None if the node has not been reached yet.
+    pub seconds_since_sync: Option<u64>,
+    /// Height of the node's best chain tip.
+    pub tip_height: Option<u32>,
+    /// The node's estimate of the network's chain height.
+    pub estimated_height: Option<u32>,
+    /// True if the indexer started without warming up its caches.
+    pub degraded: bool,
+}
+
+impl HealthReport {
+    /// Evaluates the health checks against the most recent node sync at time `now`.
+    pub fn evaluate(last_sync: Option<NodeSync>, now: Instant, config: &HealthConfig) -> Self {
+        let mut failed_checks = Vec::new();
+        let age = last_sync.map(|sync| now.saturating_duration_since(sync.time));
+        if age.map_or(true, |age| age > config.max_staleness) {
+            failed_checks.push(HealthCheck::Stale);
+        }
+        if let Some(sync) = last_sync {
+            let block_lag = ChainHeight(sync.estimated_height)
+                .saturating_sub(sync.tip_height)
+                .0;
+            if block_lag > config.max_block_lag {
+                failed_checks.push(HealthCheck::Lagging);
+            }
+        }
+        HealthReport {
+            healthy: failed_checks.is_empty(),
+            failed_checks,
+            seconds_since_sync: age.map(|age| age.as_secs()),
+            tip_height: last_sync.map(|sync| sync.tip_height),
+            estimated_height: last_sync.map(|sync| sync.estimated_height),
+            degraded: false,
+        }
+    }
+
+    /// Adds the indexer's warm-up state to the report, the report is unhealthy until warm-up has completed.
+    pub fn with_warmup(mut self, warmup: &WarmupStatus) -> Self {
+        if !warmup.is_complete() {
+            self.failed_checks.insert(0, HealthCheck::Starting);
+            self.healthy = false;
+        }
+        self.degraded = warmup.is_degraded();
+        self
+    }
+
+    /// Returns the HTTP status code for the report.
+    pub fn status_code(&self) -> StatusCode {
+        if self.healthy {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+/// Returns the `/healthz` response for the current chain tip and warm-up status.
+fn health_response(
+    status: &ChainTipStatus,
+    warmup: &WarmupStatus,
+    config: &HealthConfig,
+) -> Response<Body> {
+    let report =
+        HealthReport::evaluate(status.last_sync(), Instant::now(), config).with_warmup(warmup);
+    Response::builder()
+        .status(report.status_code())
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::to_string(&report).unwrap_or_default(),
+        ))
+        .unwrap_or_default()
+}
+
+/// Spawns an HTTP server serving the health check at `listen_addr`/healthz.
+///
+/// Health is evaluated from `status` and `warmup` on each request. Runs while `online` is true.
+pub fn spawn_health_server(
+    listen_addr: SocketAddr,
+    status: ChainTipStatus,
+    warmup: WarmupStatus,
+    config: HealthConfig,
+    online: Arc<AtomicBool>,
+) -> Result<tokio::task::JoinHandle<()>, hyper::Error> {
+    let make_service = make_service_fn(move |_conn| {
+        let status = status.clone();
+        let warmup = warmup.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let response = if request.uri().path() == "/healthz" {
+                    health_response(&status, &warmup, &config)
+                } else {
+                    Response::builder()
+                        .status(StatusCode::NOT_FOUND)
+                        .body(Body::empty())
+                        .unwrap_or_default()
+                };
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+    let server = hyper::Server::try_bind(&listen_addr)?
+        .serve(make_service)
+        .with_graceful_shutdown(async move {
+            while online.load(Ordering::SeqCst) {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
+    println!("Health check server listening at {}.", listen_addr);
+    Ok(spawn_named("health_server", async move {
+        if let Err(e) = server.await {
+            eprintln!("Health check server error: {}.", e);
+        }
+    }))
+}
@@ -1,5 +1,7 @@
 //! JsonRPC client used to send requests to Zebrad.
 
+pub mod connection_pool;
 pub mod connector;
 pub mod error;
 pub mod response;
+pub mod validator_pool;
@@ -0,0 +1,194 @@
+//! Keep-alive HTTP connection pool shared by JsonRpcConnectors.
+//!
+//! zcashd and zebrad serve JsonRPC over HTTP/1.1, so connections are reused with keep-alive rather than multiplexed.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use hyper::{body::Bytes, client::HttpConnector, Body, Client, Request, StatusCode};
+use hyper_tls::HttpsConnector;
+use tokio::sync::Semaphore;
+
+use crate::jsonrpc::error::JsonRpcConnectorError;
+
+/// Size and idle timeout of a [`ConnectionPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionPoolConfig {
+    /// Maximum number of connections open at once, further requests wait for a connection to be released.
+    pub max_connections: usize,
+    /// Time an idle connection is kept open for reuse.
+    pub idle_timeout: Duration,
+}
+
+impl Default for ConnectionPoolConfig {
+    fn default() -> Self {
+        ConnectionPoolConfig {
+            max_connections: 8,
+            idle_timeout: Duration::from_secs(90),
+        }
+    }
+}
+
+/// Holds the utilization and retry counts of a [`ConnectionPool`].
+#[derive(Debug, Clone)]
+pub struct ConnectionPoolStatus {
+    /// Maximum number of connections open at once.
+    max_connections: usize,
+    /// Number of requests currently holding a connection.
+    in_flight: Arc<AtomicUsize>,
+    /// Highest number of requests that held a connection at once.
+    peak_in_flight: Arc<AtomicUsize>,
+    /// Number of requests sent.
+    requests: Arc<AtomicU64>,
+    /// Number of requests retried on a fresh connection after their connection was dropped.
+    retries: Arc<AtomicU64>,
+}
+
+impl ConnectionPoolStatus {
+    /// Returns a status with no requests recorded.
+    fn new(max_connections: usize) -> Self {
+        ConnectionPoolStatus {
+            max_connections,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            peak_in_flight: Arc::new(AtomicUsize::new(0)),
+            requests: Arc::new(AtomicU64::new(0)),
+            retries: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns the maximum number of connections open at once.
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Returns the number of requests currently holding a connection.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Returns the highest number of requests that held a connection at once.
+    pub fn peak_in_flight(&self) -> usize {
+        self.peak_in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Returns the fraction of connections currently held by requests, between 0 and 1.
+    pub fn utilization(&self) -> f64 {
+        self.in_flight() as f64 / self.max_connections.max(1) as f64
+    }
+
+    /// Returns the number of requests sent.
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests retried on a fresh connection after their connection was dropped.
+    pub fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+}
+
+/// Bounded pool of keep-alive HTTP connections, clones share the same connections.
+///
+/// Requests whose connection is dropped before a response is received are retried once on a fresh connection
+/// if they are idempotent.
+#[derive(Debug, Clone)]
+pub struct ConnectionPool {
+    /// Pooling HTTP client.
+    client: Client<HttpsConnector<HttpConnector>>,
+    /// Permits for open connections.
+    permits: Arc<Semaphore>,
+    /// Utilization and retry counts.
+    status: ConnectionPoolStatus,
+}
+
+impl ConnectionPool {
+    /// Returns a new pool with no open connections.
+    pub fn new(config: ConnectionPoolConfig) -> Self {
+        let max_connections = config.max_connections.max(1);
+        ConnectionPool {
+            client: Client::builder()
+                .pool_max_idle_per_host(max_connections)
+                .pool_idle_timeout(config.idle_timeout)
+                .build(HttpsConnector::new()),
+            permits: Arc::new(Semaphore::new(max_connections)),
+            status: ConnectionPoolStatus::new(max_connections),
+        }
+    }
+
+    /// Returns the pool's utilization and retry counts.
+    pub fn status(&self) -> ConnectionPoolStatus {
+        self.status.clone()
+    }
+
+    /// Sends the request built by `build_request` and returns the response status and body.
+    ///
+    /// `build_request` is called again if the request is retried.
+    pub(crate) async fn send(
+        &self,
+        build_request: impl Fn() -> Result<Request<Body>, JsonRpcConnectorError>,
+        idempotent: bool,
+    ) -> Result<(StatusCode, Bytes), JsonRpcConnectorError> {
+        let request = build_request()?;
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|_| JsonRpcConnectorError::new("Connection pool closed"))?;
+        let _in_flight = InFlight::new(&self.status);
+        match send_with(&self.client, request).await {
+            Err(e) if idempotent && is_dropped_connection(&e) => {
+                self.status.retries.fetch_add(1, Ordering::Relaxed);
+                // A client without idle connections always opens a new connection.
+                let fresh_client = Client::builder()
+                    .pool_max_idle_per_host(0)
+                    .build(HttpsConnector::new());
+                send_with(&fresh_client, build_request()?)
+                    .await
+                    .map_err(JsonRpcConnectorError::HyperError)
+            }
+            result => result.map_err(JsonRpcConnectorError::HyperError),
+        }
+    }
+}
+
+/// Counts a request as in flight until dropped, including when the request is cancelled by a timeout.
+struct InFlight<'a>(&'a ConnectionPoolStatus);
+
+impl<'a> InFlight<'a> {
+    /// Records a request taking a connection.
+    fn new(status: &'a ConnectionPoolStatus) -> Self {
+        status.requests.fetch_add(1, Ordering::Relaxed);
+        let in_flight = status.in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+        status
+            .peak_in_flight
+            .fetch_max(in_flight, Ordering::Relaxed);
+        InFlight(status)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Sends `request` with `client` and reads the whole response body.
+async fn send_with(
+    client: &Client<HttpsConnector<HttpConnector>>,
+    request: Request<Body>,
+) -> Result<(StatusCode, Bytes), hyper::Error> {
+    let response = client.request(request).await?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    Ok((status, body))
+}
+
+/// Returns true if `error` was caused by the connection closing before the response was fully received.
+fn is_dropped_connection(error: &hyper::Error) -> bool {
+    error.is_incomplete_message() || error.is_canceled() || error.is_closed()
+}
@@ -2,22 +2,65 @@
 //!
 //! TODO: - Add option for http connector.
 
+use futures::{StreamExt, TryStreamExt};
 use http::Uri;
 use hyper::{http, Body, Client, Request};
 use hyper_tls::HttpsConnector;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::atomic::{AtomicI32, Ordering};
-
-use crate::jsonrpc::{
-    error::JsonRpcConnectorError,
-    response::{
-        BestBlockHashResponse, GetBalanceResponse, GetBlockResponse, GetBlockchainInfoResponse,
-        GetInfoResponse, GetSubtreesResponse, GetTransactionResponse, GetTreestateResponse,
-        GetUtxosResponse, SendTransactionResponse, TxidsResponse,
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicI32, AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    chain::fee::estimate_fee_rate,
+    jsonrpc::{
+        connection_pool::{ConnectionPool, ConnectionPoolConfig},
+        error::{JsonRpcConnectorError, SendTransactionError},
+        response::{
+            BestBlockHashResponse, GetAddressDeltasResponse, GetBalanceResponse, GetBlockHash,
+            GetBlockResponse, GetBlockchainInfoResponse, GetInfoResponse, GetNetworkInfoResponse,
+            GetRawMempoolVerboseResponse, GetSubtreesResponse, GetTransactionResponse,
+            GetTreestateResponse, GetUtxosPage, GetUtxosResponse, GetUtxosSummaryResponse,
+            MempoolEntry, PoolBalances, PoolValue, PoolValueResponse, SendTransactionResponse,
+            TxidsResponse, UtxoCursor, ValuePool,
+        },
+        validator_pool::ValidatorPool,
+    },
+    metrics::{MethodKind, MethodTimer},
+    primitives::{
+        address::TransparentAddress,
+        block::{BlockHash, BlockIdentifier},
+        height::ChainHeight,
     },
+    spans::{instrument_jsonrpc, record_endpoint},
 };
 
+/// A single request of a JsonRPC batch, see [`JsonRpcConnector::batch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRequest {
+    /// JsonRPC method name.
+    pub method: String,
+    /// Positional method parameters.
+    pub params: Vec<Value>,
+}
+
+impl BatchRequest {
+    /// Returns a new batch request calling `method` with `params`.
+    pub fn new(method: impl Into<String>, params: Vec<Value>) -> Self {
+        BatchRequest {
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+/// Result of a single request of a JsonRPC batch, the raw JSON result or the error returned for the entry.
+pub type BatchResult = Result<Value, JsonRpcConnectorError>;
+
 #[derive(Serialize, Deserialize, Debug)]
 struct RpcRequest<T> {
     jsonrpc: String,
@@ -26,14 +69,33 @@ struct RpcRequest<T> {
     id: i32,
 }
 
+/// Address and value of a single unspent output, as returned by `getaddressutxos`.
+#[derive(Deserialize, Debug)]
+struct AddressOutput {
+    address: String,
+    satoshis: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct RpcResponse<T> {
     id: i32,
     jsonrpc: Option<String>,
+    /// Null, or omitted by zebrad, when `error` is set.
+    #[serde(default)]
     result: T,
     error: Option<RpcError>,
 }
 
+/// A single response of a JsonRPC batch, nodes omit `result` from entries that failed.
+///
+/// `id` is null in errors answering the batch as a whole.
+#[derive(Deserialize, Debug)]
+struct BatchResponse {
+    id: Option<i32>,
+    result: Option<Value>,
+    error: Option<RpcError>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct RpcError {
     code: i32,
@@ -41,40 +103,224 @@ struct RpcError {
     data: Option<Value>,
 }
 
+/// Authentication source used by the JsonRpcConnector.
+#[derive(Debug, Clone)]
+pub enum Auth {
+    /// Static rpcuser / rpcpassword credentials.
+    UserPass(String, String),
+    /// Path to a zcashd / zebrad `.cookie` file holding `__cookie__:<password>`.
+    ///
+    /// The file is read on each connection attempt so cookie rotation is picked up.
+    CookieFile(PathBuf),
+}
+
+impl Auth {
+    /// Returns the value of the "Authorization" header for this auth source.
+    fn authorization_header(&self) -> Result<String, JsonRpcConnectorError> {
+        let credentials = match self {
+            Auth::UserPass(user, password) => format!("{}:{}", user, password),
+            Auth::CookieFile(path) => read_cookie_file(path)?,
+        };
+        Ok(format!("Basic {}", base64::encode(credentials)))
+    }
+}
+
+/// Reads and validates the contents of a node cookie file.
+fn read_cookie_file(path: &Path) -> Result<String, JsonRpcConnectorError> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        JsonRpcConnectorError::AuthError(format!(
+            "Could not read cookie file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let cookie = contents.trim();
+    match cookie.split_once(':') {
+        Some((user, password)) if !user.is_empty() && !password.is_empty() => {
+            Ok(cookie.to_string())
+        }
+        _ => Err(JsonRpcConnectorError::AuthError(format!(
+            "Malformed cookie file {}: expected \"__cookie__:<password>\"",
+            path.display()
+        ))),
+    }
+}
+
+/// Returns true if the JsonRPC `method` can be safely resent to the node, i.e. it does not change the node's state.
+fn is_idempotent(method: &str) -> bool {
+    method != "sendrawtransaction"
+}
+
+/// Maximum number of `z_gettreestate` requests in flight in [`JsonRpcConnector::get_treestates`].
+const TREESTATE_RANGE_CONCURRENCY: usize = 8;
+
+/// Default time a JsonRPC request may take before it fails with [`JsonRpcConnectorError::Timeout`].
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Request timeout used by new JsonRpcConnectors, in milliseconds.
+static REQUEST_TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_REQUEST_TIMEOUT.as_millis() as u64);
+
+/// Sets the request timeout used by JsonRpcConnectors created after this call.
+pub fn set_default_request_timeout(timeout: Duration) {
+    REQUEST_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::SeqCst);
+}
+
+/// Returns the request timeout used by new JsonRpcConnectors.
+pub fn default_request_timeout() -> Duration {
+    Duration::from_millis(REQUEST_TIMEOUT_MS.load(Ordering::SeqCst))
+}
+
 /// JsonRPC Client config data.
 #[derive(Debug)]
 pub struct JsonRpcConnector {
     uri: http::Uri,
     id_counter: AtomicI32,
-    user: Option<String>,
-    password: Option<String>,
+    auth: Option<Auth>,
+    request_timeout: Duration,
+    pool: Option<ValidatorPool>,
+    connection_pool: ConnectionPool,
 }
 
 impl JsonRpcConnector {
     /// Returns a new JsonRpcConnector instance, tests uri and returns error if connection is not established.
     pub async fn new(uri: http::Uri, user: Option<String>, password: Option<String>) -> Self {
+        let auth = match (user, password) {
+            (Some(user), Some(password)) => Some(Auth::UserPass(user, password)),
+            _ => None,
+        };
+        Self::new_with_auth(uri, auth)
+    }
+
+    /// Returns a new JsonRpcConnector instance sending all requests to `uri`, using the given authentication source.
+    ///
+    /// The connector opens connections from its own [`ConnectionPool`], see [`JsonRpcConnector::with_connection_pool`].
+    pub fn new_with_auth(uri: http::Uri, auth: Option<Auth>) -> Self {
         Self {
             uri,
             id_counter: AtomicI32::new(0),
-            user,
-            password,
+            auth,
+            request_timeout: default_request_timeout(),
+            pool: None,
+            connection_pool: ConnectionPool::new(ConnectionPoolConfig::default()),
+        }
+    }
+
+    /// Returns a new JsonRpcConnector instance routing requests through `pool`, starting with its active endpoint.
+    pub fn new_with_pool(pool: ValidatorPool, auth: Option<Auth>) -> Self {
+        let uri = pool.endpoints()[0].clone();
+        Self {
+            pool: Some(pool),
+            ..Self::new_with_auth(uri, auth)
+        }
+    }
+
+    /// Returns a JsonRpcConnector to the same node using `timeout` as its request timeout.
+    ///
+    /// Used to override the timeout of individual calls, e.g. `connector.with_timeout(timeout).get_block(..)`.
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        Self {
+            uri: self.uri.clone(),
+            id_counter: AtomicI32::new(self.id_counter.load(Ordering::SeqCst)),
+            auth: self.auth.clone(),
+            request_timeout: timeout,
+            pool: self.pool.clone(),
+            connection_pool: self.connection_pool.clone(),
         }
     }
 
+    /// Returns the JsonRpcConnector sending its requests over connections from `connection_pool`.
+    ///
+    /// By default each connector opens its own pool, connectors to the same node should share a pool so their connections
+    /// are reused and bounded together.
+    pub fn with_connection_pool(mut self, connection_pool: ConnectionPool) -> Self {
+        self.connection_pool = connection_pool;
+        self
+    }
+
     /// Returns the uri the JsonRpcConnector is configured to send requests to.
     pub fn uri(&self) -> &Uri {
         &self.uri
     }
 
+    /// Returns the time a request may take before it fails with [`JsonRpcConnectorError::Timeout`].
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+    }
+
     /// Sends a jsonRPC request and returns the response.
     ///
     /// TODO: This function currently resends the call up to 5 times on a server response of "Work queue depth exceeded".
     /// This is because the node's queue can become overloaded and stop servicing RPCs.
     /// This functionality is weak and should be incorporated in Zingo-Indexer's queue mechanism [WIP] that handles various errors appropriately.
+    ///
+    /// Fails with [`JsonRpcConnectorError::Timeout`] if no response is received within the connector's request timeout,
+    /// the in-flight HTTP request is dropped, closing its connection.
     async fn send_request<T: Serialize, R: for<'de> Deserialize<'de>>(
         &self,
         method: &str,
         params: T,
+    ) -> Result<R, JsonRpcConnectorError> {
+        let _timer = MethodTimer::start(MethodKind::JsonRpc, method);
+        let params = &params;
+        instrument_jsonrpc(
+            method,
+            self.route(move |uri| self.send_request_inner(uri, method, params)),
+        )
+        .await
+    }
+
+    /// Sends a request built by `request` to the connector's node within the request timeout.
+    ///
+    /// With a validator pool the request is sent to the pool's endpoints in turn, until an endpoint is reached;
+    /// each endpoint is given the full request timeout.
+    async fn route<R, F, Fut>(&self, request: F) -> Result<R, JsonRpcConnectorError>
+    where
+        F: Fn(Uri) -> Fut,
+        Fut: Future<Output = Result<R, JsonRpcConnectorError>>,
+    {
+        let pool = match &self.pool {
+            Some(pool) => pool,
+            None => {
+                record_endpoint(&self.uri);
+                return self.with_request_timeout(request(self.uri.clone())).await;
+            }
+        };
+        let mut last_error = None;
+        for index in pool.candidates() {
+            record_endpoint(pool.endpoint(index));
+            match self
+                .with_request_timeout(request(pool.endpoint(index).clone()))
+                .await
+            {
+                Err(e) if e.is_unreachable() => {
+                    pool.record_failure(index);
+                    last_error = Some(e);
+                }
+                result => {
+                    pool.record_success(index);
+                    return result;
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| JsonRpcConnectorError::new("No validator endpoints")))
+    }
+
+    /// Fails `request` with [`JsonRpcConnectorError::Timeout`] if it does not complete within the request timeout.
+    async fn with_request_timeout<R>(
+        &self,
+        request: impl Future<Output = Result<R, JsonRpcConnectorError>>,
+    ) -> Result<R, JsonRpcConnectorError> {
+        tokio::time::timeout(self.request_timeout, request)
+            .await
+            .map_err(|_| JsonRpcConnectorError::Timeout(self.request_timeout))?
+    }
+
+    /// Sends a jsonRPC request to `uri` and returns the response, without a timeout.
+    async fn send_request_inner<T: Serialize, R: for<'de> Deserialize<'de>>(
+        &self,
+        uri: Uri,
+        method: &str,
+        params: &T,
     ) -> Result<R, JsonRpcConnectorError> {
         let id = self.id_counter.fetch_add(1, Ordering::SeqCst);
         let req = RpcRequest {
@@ -83,33 +329,157 @@ impl JsonRpcConnector {
             params,
             id,
         };
+        let request_body =
+            serde_json::to_string(&req).map_err(JsonRpcConnectorError::SerdeJsonError)?;
+        let body_bytes = self.post(uri, request_body, is_idempotent(method)).await?;
+        // The error is checked before the result is deserialized, as the result is null in error responses.
+        let response: RpcResponse<Value> =
+            serde_json::from_slice(&body_bytes).map_err(JsonRpcConnectorError::SerdeJsonError)?;
+        match response.error {
+            Some(error) => Err(JsonRpcConnectorError::RpcError {
+                code: error.code,
+                message: error.message,
+            }),
+            None => serde_json::from_value(response.result)
+                .map_err(JsonRpcConnectorError::SerdeJsonError),
+        }
+    }
+
+    /// Sends a JsonRPC 2.0 batch in a single HTTP request and returns each entry's result, in request order.
+    ///
+    /// Each request is sent with a distinct id and responses are matched back to their request by id, as the node
+    /// may answer out of order. Errors returned for individual entries, or entries missing from the response,
+    /// are returned in place without failing the batch.
+    ///
+    /// Returns an empty vec without contacting the node if `requests` is empty.
+    pub async fn batch(
+        &self,
+        requests: Vec<BatchRequest>,
+    ) -> Result<Vec<BatchResult>, JsonRpcConnectorError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let _timer = MethodTimer::start(MethodKind::JsonRpc, "batch");
+        let requests = &requests;
+        instrument_jsonrpc(
+            "batch",
+            self.route(move |uri| self.batch_inner(uri, requests)),
+        )
+        .await
+    }
+
+    /// Sends a JsonRPC 2.0 batch to `uri` and returns each entry's result, without a timeout.
+    async fn batch_inner(
+        &self,
+        uri: Uri,
+        requests: &[BatchRequest],
+    ) -> Result<Vec<BatchResult>, JsonRpcConnectorError> {
+        let first_id = self
+            .id_counter
+            .fetch_add(requests.len() as i32, Ordering::SeqCst);
+        let batch: Vec<RpcRequest<&[Value]>> = requests
+            .iter()
+            .zip(first_id..)
+            .map(|(request, id)| RpcRequest {
+                jsonrpc: "2.0".to_string(),
+                method: request.method.clone(),
+                params: request.params.as_slice(),
+                id,
+            })
+            .collect();
+        let request_body =
+            serde_json::to_string(&batch).map_err(JsonRpcConnectorError::SerdeJsonError)?;
+        let idempotent = requests
+            .iter()
+            .all(|request| is_idempotent(&request.method));
+        let body_bytes = self.post(uri, request_body, idempotent).await?;
+        let responses: Vec<BatchResponse> = match serde_json::from_slice(&body_bytes) {
+            Ok(responses) => responses,
+            Err(e) => {
+                // Nodes answer a batch they can not process with a single error object.
+                return match serde_json::from_slice::<BatchResponse>(&body_bytes) {
+                    Ok(BatchResponse {
+                        error: Some(error), ..
+                    }) => Err(JsonRpcConnectorError::RpcError {
+                        code: error.code,
+                        message: error.message,
+                    }),
+                    _ => Err(JsonRpcConnectorError::SerdeJsonError(e)),
+                };
+            }
+        };
+        let mut results: Vec<Option<BatchResult>> = batch.iter().map(|_| None).collect();
+        for response in responses {
+            let index = match response
+                .id
+                .and_then(|id| id.checked_sub(first_id))
+                .and_then(|index| usize::try_from(index).ok())
+            {
+                Some(index) if index < results.len() => index,
+                // Ignore responses to ids that were not part of this batch.
+                _ => continue,
+            };
+            results[index] = Some(match response.error {
+                Some(error) => Err(JsonRpcConnectorError::RpcError {
+                    code: error.code,
+                    message: error.message,
+                }),
+                None => Ok(response.result.unwrap_or(Value::Null)),
+            });
+        }
+        Ok(results
+            .into_iter()
+            .zip(batch)
+            .map(|(result, request)| {
+                result.unwrap_or_else(|| {
+                    Err(JsonRpcConnectorError::new(format!(
+                        "No response to batch request {} ({})",
+                        request.id, request.method
+                    )))
+                })
+            })
+            .collect())
+    }
+
+    /// Posts a serialized JsonRPC request body to the node and returns the response body.
+    ///
+    /// Retries on a "Work queue depth exceeded" response, and once on a 401 response when authenticating with a cookie file.
+    /// If `idempotent` the request is also retried once on a fresh connection if its pooled connection is dropped.
+    async fn post(
+        &self,
+        uri: Uri,
+        request_body: String,
+        idempotent: bool,
+    ) -> Result<hyper::body::Bytes, JsonRpcConnectorError> {
         let max_attempts = 5;
         let mut attempts = 0;
+        let mut auth_retried = false;
         loop {
             attempts += 1;
-            let client = Client::builder().build(HttpsConnector::new());
-            let mut request_builder = Request::builder()
-                .method("POST")
-                .uri(self.uri.clone())
-                .header("Content-Type", "application/json");
-            if let (Some(user), Some(password)) = (&self.user, &self.password) {
-                let auth = base64::encode(format!("{}:{}", user, password));
-                request_builder =
-                    request_builder.header("Authorization", format!("Basic {}", auth));
+            let build_request = || {
+                let mut request_builder = Request::builder()
+                    .method("POST")
+                    .uri(uri.clone())
+                    .header("Content-Type", "application/json");
+                if let Some(auth) = &self.auth {
+                    request_builder =
+                        request_builder.header("Authorization", auth.authorization_header()?);
+                }
+                request_builder
+                    .body(Body::from(request_body.clone()))
+                    .map_err(JsonRpcConnectorError::HttpError)
+            };
+            let (status, body_bytes) = self.connection_pool.send(build_request, idempotent).await?;
+            if status == http::StatusCode::UNAUTHORIZED {
+                // The node may have rotated its cookie, re-read it once before giving up.
+                if matches!(self.auth, Some(Auth::CookieFile(_))) && !auth_retried {
+                    auth_retried = true;
+                    continue;
+                }
+                return Err(JsonRpcConnectorError::AuthError(
+                    "Node rejected the provided credentials (401 Unauthorized)".to_string(),
+                ));
             }
-            let request_body =
-                serde_json::to_string(&req).map_err(JsonRpcConnectorError::SerdeJsonError)?;
-            let request = request_builder
-                .body(Body::from(request_body))
-                .map_err(JsonRpcConnectorError::HttpError)?;
-            let response = client
-                .request(request)
-                .await
-                .map_err(JsonRpcConnectorError::HyperError)?;
-            let body_bytes = hyper::body::to_bytes(response.into_body())
-                .await
-                .map_err(JsonRpcConnectorError::HyperError)?;
-
             let body_str = String::from_utf8_lossy(&body_bytes);
             if body_str.contains("Work queue depth exceeded") {
                 if attempts >= max_attempts {
@@ -120,15 +490,7 @@ impl JsonRpcConnector {
                 tokio::time::sleep(std::time::Duration::from_millis(500)).await;
                 continue;
             }
-            let response: RpcResponse<R> = serde_json::from_slice(&body_bytes)
-                .map_err(JsonRpcConnectorError::SerdeJsonError)?;
-            return match response.error {
-                Some(error) => Err(JsonRpcConnectorError::new(format!(
-                    "RPC Error {}: {}",
-                    error.code, error.message
-                ))),
-                None => Ok(response.result),
-            };
+            return Ok(body_bytes);
         }
     }
 
@@ -142,6 +504,16 @@ impl JsonRpcConnector {
             .await
     }
 
+    /// Returns P2P networking state information, as a [`GetNetworkInfoResponse`] JSON struct.
+    ///
+    /// zcashd reference: [`getnetworkinfo`](https://zcash.github.io/rpc/getnetworkinfo.html)
+    /// method: post
+    /// tags: network
+    pub async fn get_network_info(&self) -> Result<GetNetworkInfoResponse, JsonRpcConnectorError> {
+        self.send_request::<(), GetNetworkInfoResponse>("getnetworkinfo", ())
+            .await
+    }
+
     /// Returns blockchain state information, as a [`GetBlockChainInfo`] JSON struct.
     ///
     /// zcashd reference: [`getblockchaininfo`](https://zcash.github.io/rpc/getblockchaininfo.html)
@@ -154,6 +526,88 @@ impl JsonRpcConnector {
             .await
     }
 
+    /// Returns the chain value of each value pool at the current tip, built on the `valuePools` field of [`GetBlockchainInfoResponse`].
+    ///
+    /// Returns an error if the node does not report `valuePools`.
+    ///
+    /// zcashd reference: [`getblockchaininfo`](https://zcash.github.io/rpc/getblockchaininfo.html)
+    /// method: post
+    /// tags: blockchain
+    pub async fn get_pool_balances(&self) -> Result<PoolBalances, JsonRpcConnectorError> {
+        let value_pools = self
+            .get_blockchain_info()
+            .await?
+            .value_pools
+            .ok_or_else(|| {
+                JsonRpcConnectorError::new("Node did not report valuePools in getblockchaininfo")
+            })?;
+        Ok(PoolBalances::from_value_pools(&value_pools))
+    }
+
+    /// Returns the chain value of each value pool as of `block`, built on the `valuePools` field of the verbose block.
+    ///
+    /// Pools whose network upgrade has not activated at the block's height, or that the node does not track,
+    /// are absent rather than reported as zero. Activation heights are read from [`JsonRpcConnector::get_blockchain_info`].
+    ///
+    /// Returns an error if the node does not report `valuePools` for the block.
+    ///
+    /// zcashd reference: [`getblock`](https://zcash.github.io/rpc/getblock.html)
+    /// method: post
+    /// tags: blockchain
+    ///
+    /// # Parameters
+    ///
+    /// - `block`: The hash or height of the block.
+    pub async fn get_pool_values(
+        &self,
+        block: impl Into<BlockIdentifier>,
+    ) -> Result<PoolValueResponse, JsonRpcConnectorError> {
+        let (hash, height, value_pools) = match self.get_block(block, Some(1)).await? {
+            GetBlockResponse::Object {
+                hash,
+                height,
+                value_pools,
+                ..
+            } => (hash.0, height, value_pools),
+            GetBlockResponse::Raw(_) => {
+                return Err(JsonRpcConnectorError::new(
+                    "Received raw block type, this should not be possible here.",
+                ))
+            }
+        };
+        let height = height
+            .ok_or_else(|| JsonRpcConnectorError::new("Node did not report the block height"))?;
+        let value_pools = value_pools.ok_or_else(|| {
+            JsonRpcConnectorError::new("Node did not report valuePools in getblock")
+        })?;
+        let upgrades = self.get_blockchain_info().await?.upgrades;
+        let is_active = |pool: ValuePool| match pool.activation_upgrade() {
+            None => true,
+            Some(upgrade) => upgrades.values().any(|info| {
+                info.name == upgrade
+                    && info.status != NetworkUpgradeStatus::Disabled
+                    && info.activation_height <= height
+            }),
+        };
+        let pools = value_pools
+            .iter()
+            .filter(|pool_balance| pool_balance.monitored)
+            .filter_map(|pool_balance| {
+                let pool = ValuePool::from_id(&pool_balance.id)?;
+                let chain_value_zat = pool_balance.chain_value_zat?;
+                is_active(pool).then_some(PoolValue {
+                    pool,
+                    chain_value_zat,
+                })
+            })
+            .collect();
+        Ok(PoolValueResponse {
+            hash,
+            height,
+            pools,
+        })
+    }
+
     /// Returns the total balance of a provided `addresses` in an [`AddressBalance`] instance.
     ///
     /// zcashd reference: [`getaddressbalance`](https://zcash.github.io/rpc/getaddressbalance.html)
@@ -165,15 +619,69 @@ impl JsonRpcConnector {
     /// - `address_strings`: (object, example={"addresses": ["tmYXBYJj1K7vhejSec5osXK2QsGa5MTisUQ"]}) A JSON map with a single entry
     ///     - `addresses`: (array of strings) A list of base-58 encoded addresses.
     ///
+    /// Returns an InvalidArgument error if `addresses` is empty.
+    ///
     /// NOTE: Currently unused by Zingo-Indexer and untested!
     pub async fn get_address_balance(
         &self,
         addresses: Vec<String>,
     ) -> Result<GetBalanceResponse, JsonRpcConnectorError> {
+        if addresses.is_empty() {
+            return Err(JsonRpcConnectorError::InvalidArgument(
+                "At least one address is required to fetch a balance".to_string(),
+            ));
+        }
         let params = vec![serde_json::to_value(addresses)?];
         self.send_request("getaddressbalance", params).await
     }
 
+    /// Returns the balance of each of the provided `addresses`, in zatoshis.
+    ///
+    /// Balances are summed from the addresses' unspent outputs, fetched from the node in a single call.
+    /// Each distinct address is returned once, in the order it first appears in `addresses`, addresses holding no outputs have a balance of 0.
+    ///
+    /// Returns an empty vec without contacting the node if `addresses` is empty.
+    ///
+    /// zcashd reference: [`getaddressutxos`](https://zcash.github.io/rpc/getaddressutxos.html)
+    /// method: post
+    /// tags: address
+    pub async fn get_address_balances(
+        &self,
+        addresses: &[TransparentAddress],
+    ) -> Result<Vec<(TransparentAddress, u64)>, JsonRpcConnectorError> {
+        let mut balances: Vec<(TransparentAddress, u64)> = Vec::new();
+        for address in addresses {
+            if !balances.iter().any(|(held, _)| held == address) {
+                balances.push((address.clone(), 0));
+            }
+        }
+        if balances.is_empty() {
+            return Ok(balances);
+        }
+        let encoded: Vec<String> = balances
+            .iter()
+            .map(|(address, _)| address.to_string())
+            .collect();
+        let params = vec![serde_json::to_value(&encoded)?];
+        let outputs: Vec<AddressOutput> = self.send_request("getaddressutxos", params).await?;
+        for output in outputs {
+            let index = encoded
+                .iter()
+                .position(|address| *address == output.address)
+                .ok_or_else(|| {
+                    JsonRpcConnectorError::new(format!(
+                        "Node returned an output for unrequested address {}",
+                        output.address
+                    ))
+                })?;
+            balances[index].1 = balances[index]
+                .1
+                .checked_add(output.satoshis)
+                .ok_or_else(|| JsonRpcConnectorError::new("Address balance overflowed"))?;
+        }
+        Ok(balances)
+    }
+
     /// Sends the raw bytes of a signed transaction to the local node's mempool, if the transaction is valid.
     /// Returns the [`SentTransactionHash`] for the transaction, as a JSON string.
     ///
@@ -184,17 +692,25 @@ impl JsonRpcConnector {
     /// # Parameters
     ///
     /// - `raw_transaction_hex`: (string, required, example="signedhex") The hex-encoded raw transaction bytes.
+    ///
+    /// Rejections are returned as [`JsonRpcConnectorError::TransactionRejected`].
     pub async fn send_raw_transaction(
         &self,
         raw_transaction_hex: String,
     ) -> Result<SendTransactionResponse, JsonRpcConnectorError> {
         let params = vec![serde_json::to_value(raw_transaction_hex)?];
-        self.send_request("sendrawtransaction", params).await
+        self.send_request("sendrawtransaction", params)
+            .await
+            .map_err(|e| match e {
+                JsonRpcConnectorError::RpcError { code, message } => {
+                    SendTransactionError::from_rpc_error(code, &message).into()
+                }
+                e => e,
+            })
     }
 
     /// Returns the requested block by hash or height, as a [`GetBlock`] JSON string.
-    /// If the block is not in Zebra's state, returns
-    /// [error code `-8`.](https://github.com/zcash/zcash/issues/5758)
+    /// If the block is not in Zebra's state, returns a BlockNotFound error.
     ///
     /// zcashd reference: [`getblock`](https://zcash.github.io/rpc/getblock.html)
     /// method: post
@@ -202,24 +718,46 @@ impl JsonRpcConnector {
     ///
     /// # Parameters
     ///
-    /// - `hash_or_height`: (string, required, example="1") The hash or height for the block to be returned.
+    /// - `block_id`: (string, required, example="1") The hash or height for the block to be returned.
     /// - `verbosity`: (number, optional, default=1, example=1) 0 for hex encoded data, 1 for a json object, and 2 for json object with transaction data.
     pub async fn get_block(
         &self,
-        hash_or_height: String,
+        block_id: impl Into<BlockIdentifier>,
         verbosity: Option<u8>,
     ) -> Result<GetBlockResponse, JsonRpcConnectorError> {
-        let params = match verbosity {
-            Some(v) => vec![
-                serde_json::to_value(hash_or_height)?,
-                serde_json::to_value(v)?,
-            ],
-            None => vec![
-                serde_json::to_value(hash_or_height)?,
-                serde_json::to_value(1)?,
-            ],
-        };
-        self.send_request("getblock", params).await
+        let block_id = block_id.into();
+        let params = vec![
+            serde_json::to_value(block_id)?,
+            serde_json::to_value(verbosity.unwrap_or(1))?,
+        ];
+        match self.send_request("getblock", params).await {
+            Err(e) if e.is_not_found() => Err(JsonRpcConnectorError::BlockNotFound(block_id)),
+            result => result,
+        }
+    }
+
+    /// Returns the requested block by hash or height, rejecting blocks that are not in the node's best chain.
+    ///
+    /// Same as [`JsonRpcConnector::get_block`], but a block reported on a side chain is returned as a
+    /// [`JsonRpcConnectorError::BlockNotOnMainChain`] error rather than served as stale data after a reorg.
+    /// `verbosity` must be 1 or 2, raw blocks carry no chain context.
+    pub async fn get_main_chain_block(
+        &self,
+        block_id: impl Into<BlockIdentifier>,
+        verbosity: Option<u8>,
+    ) -> Result<GetBlockResponse, JsonRpcConnectorError> {
+        if verbosity == Some(0) {
+            return Err(JsonRpcConnectorError::InvalidArgument(
+                "Main chain check requires a verbose block, verbosity must be 1 or 2".to_string(),
+            ));
+        }
+        let block = self.get_block(block_id, verbosity).await?;
+        match &block {
+            GetBlockResponse::Object { hash, .. } if block.is_main_chain() == Some(false) => {
+                Err(JsonRpcConnectorError::BlockNotOnMainChain(hash.0))
+            }
+            _ => Ok(block),
+        }
     }
 
     /// Returns the hash of the current best blockchain tip block, as a [`GetBlockHash`] JSON string.
@@ -236,6 +774,65 @@ impl JsonRpcConnector {
             .await
     }
 
+    /// Returns the hash of the best chain block at `height`.
+    ///
+    /// Returns a HeightOutOfRange error if `height` is above the best chain tip.
+    ///
+    /// zcashd reference: [`getblockhash`](https://zcash.github.io/rpc/getblockhash.html)
+    /// method: post
+    /// tags: blockchain
+    ///
+    /// # Parameters
+    ///
+    /// - `index`: (numeric, required) The block height.
+    pub async fn get_block_hash(
+        &self,
+        height: ChainHeight,
+    ) -> Result<GetBlockHash, JsonRpcConnectorError> {
+        let params = vec![serde_json::to_value(height.0)?];
+        match self.send_request("getblockhash", params).await {
+            Err(e) if e.is_not_found() => Err(JsonRpcConnectorError::HeightOutOfRange(height.0)),
+            result => result,
+        }
+    }
+
+    /// Returns the height of the current best blockchain tip block.
+    ///
+    /// Cheaper than [`JsonRpcConnector::get_blockchain_info`] when only the tip height is needed.
+    /// A node holding only the genesis block returns a height of 0.
+    ///
+    /// zcashd reference: [`getblockcount`](https://zcash.github.io/rpc/getblockcount.html)
+    /// method: post
+    /// tags: blockchain
+    pub async fn get_block_count(&self) -> Result<ChainHeight, JsonRpcConnectorError> {
+        let height: ChainHeight = self
+            .send_request::<(), ChainHeight>("getblockcount", ())
+            .await?;
+        if height > ChainHeight::MAX {
+            return Err(JsonRpcConnectorError::new(format!(
+                "Node returned block count {} above the maximum height",
+                height.0
+            )));
+        }
+        Ok(height)
+    }
+
+    /// Returns the height and hash of the current best blockchain tip block.
+    ///
+    /// Both are read from a single `getblockchaininfo` response, so the hash is always the one at the returned height,
+    /// unlike separate [`JsonRpcConnector::get_block_count`] and [`JsonRpcConnector::get_best_block_hash`] calls
+    /// which can straddle a new block or a reorg.
+    ///
+    /// zcashd reference: [`getblockchaininfo`](https://zcash.github.io/rpc/getblockchaininfo.html)
+    /// method: post
+    /// tags: blockchain
+    pub async fn get_best_block_height(
+        &self,
+    ) -> Result<(ChainHeight, BlockHash), JsonRpcConnectorError> {
+        let blockchain_info = self.get_blockchain_info().await?;
+        Ok((blockchain_info.blocks, blockchain_info.best_block_hash))
+    }
+
     /// Returns all transaction ids in the memory pool, as a JSON array.
     ///
     /// zcashd reference: [`getrawmempool`](https://zcash.github.io/rpc/getrawmempool.html)
@@ -246,6 +843,32 @@ impl JsonRpcConnector {
             .await
     }
 
+    /// Returns the size, fee and entry time of each transaction in the memory pool.
+    ///
+    /// zcashd reference: [`getrawmempool`](https://zcash.github.io/rpc/getrawmempool.html)
+    /// method: post
+    /// tags: blockchain
+    ///
+    /// # Parameters
+    ///
+    /// - `verbose`: (boolean, optional, default=false) Sent as true, returns a json object keyed by txid.
+    pub async fn get_raw_mempool_verbose(
+        &self,
+    ) -> Result<Vec<MempoolEntry>, JsonRpcConnectorError> {
+        let params = vec![serde_json::to_value(true)?];
+        let response: GetRawMempoolVerboseResponse =
+            self.send_request("getrawmempool", params).await?;
+        Ok(response.entries)
+    }
+
+    /// Returns the recommended fee rate, in zatoshis per 1000 bytes, to be mined within `target_blocks` blocks.
+    ///
+    /// Computed from the verbose mempool entries, see [`estimate_fee_rate`].
+    pub async fn estimate_fee(&self, target_blocks: u32) -> Result<u64, JsonRpcConnectorError> {
+        let entries = self.get_raw_mempool_verbose().await?;
+        Ok(estimate_fee_rate(&entries, target_blocks))
+    }
+
     /// Returns information about the given block's Sapling & Orchard tree state.
     ///
     /// zcashd reference: [`z_gettreestate`](https://zcash.github.io/rpc/z_gettreestate.html)
@@ -263,6 +886,79 @@ impl JsonRpcConnector {
         self.send_request("z_gettreestate", params).await
     }
 
+    /// Returns information about the given block's Sapling & Orchard tree state, retrying on transient not-found errors.
+    ///
+    /// During a reorg the block at a given height can briefly be unavailable while the node switches chains,
+    /// in this case the treestate is refetched up to `max_attempts` times. When called with a height, each attempt
+    /// re-resolves the height to the hash of the best chain block at that height with `getblockhash` and fetches
+    /// the treestate of that block, so a retry is answered from the new best chain.
+    ///
+    /// # Parameters
+    ///
+    /// - `hash | height`: (string, required) The block hash or height.
+    /// - `max_attempts`: (number, required) The maximum number of fetch attempts.
+    /// - `retry_delay`: (duration, required) The delay between attempts.
+    pub async fn get_treestate_with_retry(
+        &self,
+        hash_or_height: String,
+        max_attempts: u8,
+        retry_delay: std::time::Duration,
+    ) -> Result<GetTreestateResponse, JsonRpcConnectorError> {
+        let height = hash_or_height.parse::<u32>().ok().map(ChainHeight);
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = match height {
+                Some(height) => match self.get_block_hash(height).await {
+                    Ok(hash) => self.get_treestate(hash.0.to_string()).await,
+                    Err(e) => Err(e),
+                },
+                None => self.get_treestate(hash_or_height.clone()).await,
+            };
+            match result {
+                Err(e) if e.is_not_found() && attempts < max_attempts => {
+                    tokio::time::sleep(retry_delay).await;
+                }
+                Err(e) if e.is_not_found() => {
+                    return Err(JsonRpcConnectorError::new(format!(
+                        "Treestate for block {} not found after {} attempts, the chain may be reorganising: {}",
+                        hash_or_height, attempts, e
+                    )));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Returns the Sapling & Orchard tree states of the blocks from `start` to `end` inclusive, ordered by height.
+    ///
+    /// Up to [`TREESTATE_RANGE_CONCURRENCY`] `z_gettreestate` requests are in flight at once. Heights below the
+    /// activation of a pool return an empty tree state for that pool, e.g. an empty Orchard tree state before NU5.
+    /// Returns [`JsonRpcConnectorError::InvalidHeightRange`] if `start` is above `end`, and the first error if any
+    /// request fails.
+    ///
+    /// # Parameters
+    ///
+    /// - `start`: (number, required) The height of the first block.
+    /// - `end`: (number, required) The height of the last block.
+    pub async fn get_treestates(
+        &self,
+        start: ChainHeight,
+        end: ChainHeight,
+    ) -> Result<Vec<GetTreestateResponse>, JsonRpcConnectorError> {
+        if start > end {
+            return Err(JsonRpcConnectorError::InvalidHeightRange {
+                start: start.0,
+                end: end.0,
+            });
+        }
+        futures::stream::iter(start.0..=end.0)
+            .map(|height| self.get_treestate(height.to_string()))
+            .buffered(TREESTATE_RANGE_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
     /// Returns information about a range of Sapling or Orchard subtrees.
     ///
     /// zcashd reference: [`z_getsubtreesbyindex`](https://zcash.github.io/rpc/z_getsubtreesbyindex.html) - TODO: fix link
@@ -275,13 +971,20 @@ impl JsonRpcConnector {
     /// - `start_index`: (number, required) The index of the first 2^16-leaf subtree to return.
     /// - `limit`: (number, optional) The maximum number of subtree values to return.
     ///
-    /// NOTE: Currently unused by Zingo-Indexer and untested!
+    /// Returns an InvalidArgument error if `pool` is not "sapling" or "orchard". The node returns fewer than `limit`
+    /// subtrees if fewer are complete from `start_index`.
     pub async fn get_subtrees_by_index(
         &self,
         pool: String,
         start_index: u16,
         limit: Option<u16>,
     ) -> Result<GetSubtreesResponse, JsonRpcConnectorError> {
+        if pool != "sapling" && pool != "orchard" {
+            return Err(JsonRpcConnectorError::InvalidArgument(format!(
+                "Unknown pool \"{}\", expected \"sapling\" or \"orchard\"",
+                pool
+            )));
+        }
         let params = match limit {
             Some(v) => vec![
                 serde_json::to_value(pool)?,
@@ -319,6 +1022,32 @@ impl JsonRpcConnector {
         self.send_request("getrawtransaction", params).await
     }
 
+    /// Returns the raw transaction data of each of the provided `txids`, fetched from the node in a single batch.
+    ///
+    /// Results are returned in the order of `txids`, a transaction the node does not hold fails only its own entry.
+    ///
+    /// zcashd reference: [`getrawtransaction`](https://zcash.github.io/rpc/getrawtransaction.html)
+    /// method: post
+    /// tags: transaction
+    pub async fn get_raw_transactions(
+        &self,
+        txids: Vec<String>,
+        verbose: Option<u8>,
+    ) -> Result<Vec<Result<GetTransactionResponse, JsonRpcConnectorError>>, JsonRpcConnectorError>
+    {
+        let verbose = serde_json::to_value(verbose.unwrap_or(0))?;
+        let requests = txids
+            .into_iter()
+            .map(|txid| BatchRequest::new("getrawtransaction", vec![txid.into(), verbose.clone()]))
+            .collect();
+        Ok(self
+            .batch(requests)
+            .await?
+            .into_iter()
+            .map(|result| Ok(serde_json::from_value(result?)?))
+            .collect())
+    }
+
     /// Returns the transaction ids made by the provided transparent addresses.
     ///
     /// zcashd reference: [`getaddresstxids`](https://zcash.github.io/rpc/getaddresstxids.html)
@@ -329,21 +1058,107 @@ impl JsonRpcConnector {
     ///
     /// - `request`: (object, required, example={\"addresses\": [\"tmYXBYJj1K7vhejSec5osXK2QsGa5MTisUQ\"], \"start\": 1000, \"end\": 2000}) A struct with the following named fields:
     ///     - `addresses`: (json array of string, required) The addresses to get transactions from.
-    ///     - `start`: (numeric, required) The lower height to start looking for transactions (inclusive).
-    ///     - `end`: (numeric, required) The top height to stop looking for transactions (inclusive).
+    ///     - `start`: (numeric, optional) The lower height to start looking for transactions (inclusive).
+    ///     - `end`: (numeric, optional) The top height to stop looking for transactions (inclusive).
+    ///
+    /// The full chain is searched if both `start` and `end` are None. If only `end` is set the search starts at height 1,
+    /// if only `start` is set the search ends at the node's best chain tip.
+    ///
+    /// Txids are returned in chain order, txids returned for more than one of the addresses are only returned once.
+    ///
+    /// Returns an empty list if `addresses` is empty, and [`JsonRpcConnectorError::InvalidHeightRange`] if `start` is
+    /// above `end`.
     pub async fn get_address_txids(
         &self,
         addresses: Vec<String>,
-        start: u32,
-        end: u32,
+        start: Option<u32>,
+        end: Option<u32>,
     ) -> Result<TxidsResponse, JsonRpcConnectorError> {
-        let params = serde_json::json!({
-            "addresses": addresses,
-            "start": start,
-            "end": end
-        });
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(JsonRpcConnectorError::InvalidHeightRange { start, end });
+            }
+        }
+        if addresses.is_empty() {
+            return Ok(TxidsResponse {
+                transactions: Vec::new(),
+            });
+        }
+        let params = self.address_range_params(addresses, start, end).await?;
+
+        self.send_request::<_, TxidsResponse>("getaddresstxids", vec![params])
+            .await
+            .map(TxidsResponse::deduplicated)
+    }
+
+    /// Returns the balance changes of the provided transparent addresses, each input spent and output received.
+    ///
+    /// zcashd reference: [`getaddressdeltas`](https://zcash.github.io/rpc/getaddressdeltas.html)
+    /// method: post
+    /// tags: address
+    ///
+    /// # Parameters
+    ///
+    /// - `request`: (object, required, example={\"addresses\": [\"tmYXBYJj1K7vhejSec5osXK2QsGa5MTisUQ\"], \"start\": 1000, \"end\": 2000}) A struct with the following named fields:
+    ///     - `addresses`: (json array of string, required) The addresses to get deltas for.
+    ///     - `start`: (numeric, optional) The lower height to start looking for deltas (inclusive).
+    ///     - `end`: (numeric, optional) The top height to stop looking for deltas (inclusive).
+    ///
+    /// The range is resolved as in [`JsonRpcConnector::get_address_txids`]. Requires the node to run with
+    /// `insightexplorer=1`.
+    ///
+    /// Deltas are returned in height order, spends have negative `satoshis`. Returns an empty list if `addresses` is
+    /// empty, and [`JsonRpcConnectorError::InvalidHeightRange`] if `start` is above `end`.
+    pub async fn get_address_deltas(
+        &self,
+        addresses: Vec<String>,
+        start: Option<u32>,
+        end: Option<u32>,
+    ) -> Result<GetAddressDeltasResponse, JsonRpcConnectorError> {
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(JsonRpcConnectorError::InvalidHeightRange { start, end });
+            }
+        }
+        if addresses.is_empty() {
+            return Ok(GetAddressDeltasResponse { deltas: Vec::new() });
+        }
+        let params = self.address_range_params(addresses, start, end).await?;
+
+        self.send_request::<_, GetAddressDeltasResponse>("getaddressdeltas", vec![params])
+            .await
+            .map(GetAddressDeltasResponse::height_ordered)
+    }
 
-        self.send_request("getaddresstxids", vec![params]).await
+    /// Returns the request object of an address index RPC searching `addresses` between `start` and `end`.
+    ///
+    /// zcashd only honours a range if both bounds are set, so a missing `start` is set to 1 and a missing `end` to the
+    /// node's best chain tip. Returns [`JsonRpcConnectorError::InvalidHeightRange`] if the resolved range is empty.
+    async fn address_range_params(
+        &self,
+        addresses: Vec<String>,
+        start: Option<u32>,
+        end: Option<u32>,
+    ) -> Result<serde_json::Value, JsonRpcConnectorError> {
+        let range = match (start, end) {
+            (None, None) => None,
+            (Some(start), Some(end)) => Some((start, end)),
+            (None, Some(end)) => Some((1, end)),
+            (Some(start), None) => Some((start, self.get_blockchain_info().await?.blocks.0)),
+        };
+        if let Some((start, end)) = range {
+            if start > end {
+                return Err(JsonRpcConnectorError::InvalidHeightRange { start, end });
+            }
+        }
+        Ok(match range {
+            Some((start, end)) => serde_json::json!({
+                "addresses": addresses,
+                "start": start,
+                "end": end
+            }),
+            None => serde_json::json!({ "addresses": addresses }),
+        })
     }
 
     /// Returns all unspent outputs for a list of addresses.
@@ -356,41 +1171,137 @@ impl JsonRpcConnector {
     ///
     /// - `addresses`: (array, required, example={\"addresses\": [\"tmYXBYJj1K7vhejSec5osXK2QsGa5MTisUQ\"]}) The addresses to get outputs from.
     ///
+    /// Returns an empty list if `addresses` is empty.
+    ///
     /// NOTE: Currently unused by Zingo-Indexer and untested!
     pub async fn get_address_utxos(
         &self,
         addresses: Vec<String>,
     ) -> Result<Vec<GetUtxosResponse>, JsonRpcConnectorError> {
+        if addresses.is_empty() {
+            return Ok(Vec::new());
+        }
         let params = vec![serde_json::to_value(addresses)?];
         self.send_request("getaddressutxos", params).await
     }
+
+    /// Returns a page of at most `max_entries` unspent outputs for a list of addresses, with a cursor to fetch the next page from.
+    ///
+    /// The first page is requested with `cursor` set to None and starts at `start_height`, later pages pass the previous page's `next` cursor
+    /// with the same `addresses` and `start_height`. The walk is anchored to the chain tip at the time of the first page,
+    /// so outputs mined after it started are not returned and can not cause duplicates or gaps.
+    ///
+    /// Returns an InvalidArgument error if `max_entries` is 0.
+    ///
+    /// zcashd reference: [`getaddressutxos`](https://zcash.github.io/rpc/getaddressutxos.html)
+    /// method: post
+    /// tags: address
+    ///
+    /// # Parameters
+    ///
+    /// - `addresses`: (array, required, example={\"addresses\": [\"tmYXBYJj1K7vhejSec5osXK2QsGa5MTisUQ\"]}) The addresses to get outputs from.
+    /// - `start_height`: (number, required) The lowest block height of outputs returned.
+    /// - `max_entries`: (number, required) The maximum number of outputs returned.
+    /// - `cursor`: (object, optional) The position to resume from.
+    pub async fn get_address_utxos_page(
+        &self,
+        addresses: Vec<String>,
+        start_height: ChainHeight,
+        max_entries: usize,
+        cursor: Option<UtxoCursor>,
+    ) -> Result<GetUtxosPage, JsonRpcConnectorError> {
+        if max_entries == 0 {
+            return Err(JsonRpcConnectorError::InvalidArgument(
+                "At least one entry is required per page of outputs".to_string(),
+            ));
+        }
+        if addresses.is_empty() {
+            return Ok(GetUtxosPage {
+                utxos: Vec::new(),
+                next: None,
+            });
+        }
+        let anchor_height = match cursor {
+            Some(cursor) => cursor.anchor_height,
+            None => self.get_blockchain_info().await?.blocks,
+        };
+        let utxos = self.get_address_utxos(addresses).await?;
+        Ok(GetUtxosPage::from_utxos(
+            utxos,
+            start_height,
+            max_entries,
+            anchor_height,
+            cursor.as_ref(),
+        ))
+    }
+
+    /// Returns all unspent outputs for a list of addresses along with their total value.
+    ///
+    /// If `min_confirmations` is given, outputs with fewer confirmations at the current tip are excluded from both the list and the total.
+    /// Returns an empty list with a total of 0 if `addresses` is empty.
+    ///
+    /// zcashd reference: [`getaddressutxos`](https://zcash.github.io/rpc/getaddressutxos.html)
+    /// method: post
+    /// tags: address
+    ///
+    /// # Parameters
+    ///
+    /// - `addresses`: (array, required, example={\"addresses\": [\"tmYXBYJj1K7vhejSec5osXK2QsGa5MTisUQ\"]}) The addresses to get outputs from.
+    /// - `min_confirmations`: (number, optional) The minimum number of confirmations an output must have to be included.
+    pub async fn get_address_utxos_summary(
+        &self,
+        addresses: Vec<String>,
+        min_confirmations: Option<u32>,
+    ) -> Result<GetUtxosSummaryResponse, JsonRpcConnectorError> {
+        if addresses.is_empty() {
+            return Ok(GetUtxosSummaryResponse {
+                utxos: Vec::new(),
+                total_satoshis: 0,
+            });
+        }
+        let mut utxos = self.get_address_utxos(addresses).await?;
+        if let Some(min_confirmations) = min_confirmations {
+            let tip_height = self.get_blockchain_info().await?.blocks;
+            utxos.retain(|utxo| {
+                tip_height
+                    .checked_sub(utxo.height.0)
+                    .is_some_and(|depth| depth.0 + 1 >= min_confirmations)
+            });
+        }
+        let total_satoshis = utxos
+            .iter()
+            .try_fold(0u64, |total, utxo| total.checked_add(utxo.satoshis))
+            .ok_or_else(|| JsonRpcConnectorError::new("UTXO total value overflowed"))?;
+        Ok(GetUtxosSummaryResponse {
+            utxos,
+            total_satoshis,
+        })
+    }
 }
 
 /// Tests connection with zebrad / zebrad.
-async fn test_node_connection(
+pub(crate) async fn test_node_connection(
     uri: Uri,
-    user: Option<String>,
-    password: Option<String>,
+    auth: Option<&Auth>,
 ) -> Result<(), JsonRpcConnectorError> {
     let client = Client::builder().build::<_, Body>(HttpsConnector::new());
 
-    let user = user.unwrap_or_else(|| "xxxxxx".to_string());
-    let password = password.unwrap_or_else(|| "xxxxxx".to_string());
-    let encoded_auth = base64::encode(format!("{}:{}", user, password));
-
-    let request = Request::builder()
+    let mut request_builder = Request::builder()
         .method("POST")
         .uri(uri.clone())
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Basic {}", encoded_auth))
+        .header("Content-Type", "application/json");
+    if let Some(auth) = auth {
+        request_builder = request_builder.header("Authorization", auth.authorization_header()?);
+    }
+    let request = request_builder
         .body(Body::from(
             r#"{"jsonrpc":"2.0","method":"getinfo","params":[],"id":1}"#,
         ))
         .map_err(JsonRpcConnectorError::HttpError)?;
-    let response =
-        tokio::time::timeout(tokio::time::Duration::from_secs(3), client.request(request))
-            .await
-            .map_err(JsonRpcConnectorError::TimeoutError)??;
+    let connection_timeout = Duration::from_secs(3);
+    let response = tokio::time::timeout(connection_timeout, client.request(request))
+        .await
+        .map_err(|_| JsonRpcConnectorError::Timeout(connection_timeout))??;
     let body_bytes = hyper::body::to_bytes(response.into_body())
         .await
         .map_err(JsonRpcConnectorError::HyperError)?;
@@ -399,11 +1310,27 @@ async fn test_node_connection(
     Ok(())
 }
 
-/// Tries to connect to zebrad/zcashd using IPv4 and IPv6 and returns the correct uri type, exits program with error message if connection cannot be established.
+/// Tries to connect to zebrad/zcashd using IPv4 and IPv6 and returns the correct uri type, returns an error if connection cannot be established.
+///
+/// Missing credentials default to "xxxxxx", see [`test_node_and_return_uri_with_auth`] for other authentication sources.
 pub async fn test_node_and_return_uri(
     port: &u16,
     user: Option<String>,
     password: Option<String>,
+) -> Result<Uri, JsonRpcConnectorError> {
+    let auth = Auth::UserPass(
+        user.unwrap_or_else(|| "xxxxxx".to_string()),
+        password.unwrap_or_else(|| "xxxxxx".to_string()),
+    );
+    test_node_and_return_uri_with_auth(port, Some(auth)).await
+}
+
+/// Tries to connect to zebrad/zcashd using IPv4 and IPv6, authenticating with `auth`, and returns the correct uri type.
+///
+/// Returns the last connection error if the node does not answer on either address after 3 attempts.
+pub async fn test_node_and_return_uri_with_auth(
+    port: &u16,
+    auth: Option<Auth>,
 ) -> Result<Uri, JsonRpcConnectorError> {
     let ipv4_uri: Uri = format!("http://127.0.0.1:{}", port)
         .parse()
@@ -412,26 +1339,28 @@ pub async fn test_node_and_return_uri(
         .parse()
         .map_err(JsonRpcConnectorError::InvalidUriError)?;
     let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
+    let mut last_error = None;
     for _ in 0..3 {
-        match test_node_connection(ipv4_uri.clone(), user.clone(), password.clone()).await {
+        match test_node_connection(ipv4_uri.clone(), auth.as_ref()).await {
             Ok(_) => {
                 println!("Connected to node using IPv4 at address {}.", ipv4_uri);
                 return Ok(ipv4_uri);
             }
-            Err(_e_ipv4) => {
-                match test_node_connection(ipv6_uri.clone(), user.clone(), password.clone()).await {
-                    Ok(_) => {
-                        println!("Connected to node using IPv6 at address {}.", ipv6_uri);
-                        return Ok(ipv6_uri);
-                    }
-                    Err(_e_ipv6) => {
-                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                    }
+            Err(_e_ipv4) => match test_node_connection(ipv6_uri.clone(), auth.as_ref()).await {
+                Ok(_) => {
+                    println!("Connected to node using IPv6 at address {}.", ipv6_uri);
+                    return Ok(ipv6_uri);
                 }
-            }
+                Err(e_ipv6) => {
+                    last_error = Some(e_ipv6);
+                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                }
+            },
         }
         interval.tick().await;
     }
-    eprintln!("Could not establish connection with node. \nPlease check config and confirm node is listening at the correct address and the correct authorisation details have been entered. \nExiting..");
-    std::process::exit(1);
+    eprintln!("Could not establish connection with node. \nPlease check config and confirm node is listening at the correct address and the correct authorisation details have been entered.");
+    Err(last_error.unwrap_or_else(|| {
+        JsonRpcConnectorError::CustomError("Could not establish connection with node".to_string())
+    }))
 }
@@ -1,5 +1,7 @@
 //! Hold error types for the JsonRpcConnector and related functionality.
 
+use crate::primitives::block::{BlockHash, BlockIdentifier};
+
 /// General error type for handling JsonRpcConnector errors.
 #[derive(Debug, thiserror::Error)]
 pub enum JsonRpcConnectorError {
@@ -7,6 +9,15 @@ pub enum JsonRpcConnectorError {
     #[error("{0}")]
     CustomError(String),
 
+    /// Error returned by the node in the JsonRPC response.
+    #[error("RPC Error {code}: {message}")]
+    RpcError {
+        /// JsonRPC error code.
+        code: i32,
+        /// JsonRPC error message.
+        message: String,
+    },
+
     /// Serialization/Deserialization Errors.
     #[error("Serialization/Deserialization Error: {0}")]
     SerdeJsonError(#[from] serde_json::Error),
@@ -27,9 +38,112 @@ pub enum JsonRpcConnectorError {
     #[error("UTF-8 Conversion Error")]
     Utf8Error(#[from] std::string::FromUtf8Error),
 
-    /// Request Timeout Errors.
-    #[error("Request Timeout Error")]
-    TimeoutError(#[from] tokio::time::error::Elapsed),
+    /// The node did not respond within the request timeout, the request was cancelled.
+    #[error("Request Timeout: no response after {0:?}")]
+    Timeout(std::time::Duration),
+
+    /// Invalid request arguments, rejected before being sent to the node.
+    #[error("Invalid Argument: {0}")]
+    InvalidArgument(String),
+
+    /// Node Authentication Errors.
+    #[error("Authentication Error: {0}")]
+    AuthError(String),
+
+    /// The requested block is not held in the node's best chain.
+    #[error("Block Not Found: {0}")]
+    BlockNotFound(BlockIdentifier),
+
+    /// The requested block is valid but on a side chain, it is not held in the node's best chain.
+    #[error("Block Not On Main Chain: {0}")]
+    BlockNotOnMainChain(BlockHash),
+
+    /// The requested height is above the node's best chain tip.
+    #[error("Height Out Of Range: {0}")]
+    HeightOutOfRange(u32),
+
+    /// The requested height range starts above its end.
+    #[error("Invalid Height Range: start {start} is above end {end}")]
+    InvalidHeightRange {
+        /// Start of the range.
+        start: u32,
+        /// End of the range.
+        end: u32,
+    },
+
+    /// The node rejected a transaction sent with `sendrawtransaction`.
+    #[error("Transaction Rejected: {0}")]
+    TransactionRejected(#[from] SendTransactionError),
+}
+
+/// Reason a transaction sent with `sendrawtransaction` was rejected by the node.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SendTransactionError {
+    /// The transaction has already been mined into the node's best chain.
+    #[error("Transaction already in chain")]
+    AlreadyInChain,
+
+    /// The transaction is already held in the node's mempool.
+    #[error("Transaction already in mempool")]
+    AlreadyInMempool,
+
+    /// The transaction spends outputs that are unknown to the node or already spent.
+    #[error("Missing inputs")]
+    MissingInputs,
+
+    /// The transaction does not pay the node's minimum relay / mempool fee.
+    #[error("Fee too low: {0}")]
+    FeeTooLow(String),
+
+    /// The transaction failed script or signature verification.
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+
+    /// Any other rejection, holds the node's error message.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl SendTransactionError {
+    /// Classifies a `sendrawtransaction` JsonRPC error returned by zcashd / zebrad.
+    ///
+    /// zcashd returns RPC_TRANSACTION_ALREADY_IN_CHAIN (-27) for mined transactions, RPC_TRANSACTION_ERROR (-25) for
+    /// missing inputs and RPC_TRANSACTION_REJECTED (-26) with a `<reject code>: <reason>` message for everything else.
+    /// zebrad returns -25 or -26 with its mempool / verifier error message, so the message is also matched.
+    pub fn from_rpc_error(code: i32, message: &str) -> Self {
+        let reason = message.to_lowercase();
+        if code == -27
+            || reason.contains("already in block chain")
+            || reason.contains("already in chain")
+            || reason.contains("committed to the best chain")
+        {
+            SendTransactionError::AlreadyInChain
+        } else if reason.contains("already-in-mempool")
+            || reason.contains("already in the mempool")
+            || reason.contains("already exists in mempool")
+            || reason.contains("already queued")
+        {
+            SendTransactionError::AlreadyInMempool
+        } else if reason.contains("missing inputs")
+            || reason.contains("missingorspent")
+            || reason.contains("input not found")
+            || reason.contains("inputs not found")
+        {
+            SendTransactionError::MissingInputs
+        } else if (reason.contains("fee") && !reason.contains("absurdly-high-fee"))
+            || reason.contains("insufficient priority")
+            || reason.contains("unpaid action")
+        {
+            SendTransactionError::FeeTooLow(message.to_string())
+        } else if reason.contains("script-verify-flag-failed")
+            || reason.contains("signature")
+            || reason.contains("script")
+        {
+            SendTransactionError::InvalidSignature(message.to_string())
+        } else {
+            SendTransactionError::Other(message.to_string())
+        }
+    }
 }
 
 impl JsonRpcConnectorError {
@@ -38,6 +152,49 @@ impl JsonRpcConnectorError {
         JsonRpcConnectorError::CustomError(msg.into())
     }
 
+    /// Returns true if the node reported that the requested block, transaction or treestate was not found.
+    ///
+    /// zcashd / zebrad return RPC_INVALID_ADDRESS_OR_KEY (-5) in this case, and RPC_INVALID_PARAMETER (-8) for heights
+    /// above the best chain tip. Other invalid parameter errors, e.g. a malformed hash, are not treated as not found.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            JsonRpcConnectorError::RpcError { code: -5, .. }
+            | JsonRpcConnectorError::BlockNotFound(_)
+            | JsonRpcConnectorError::HeightOutOfRange(_) => true,
+            JsonRpcConnectorError::RpcError { code: -8, message } => {
+                let message = message.to_lowercase();
+                ["out of range", "not found", "not in best chain"]
+                    .iter()
+                    .any(|reason| message.contains(reason))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the node does not serve the requested method, or does not accept its parameters.
+    ///
+    /// Nodes return RPC_METHOD_NOT_FOUND (-32601) or RPC_INVALID_PARAMS (-32602) in this case, e.g. for verbose
+    /// `getrawmempool` requests to validators that only serve txids.
+    pub fn is_method_not_found(&self) -> bool {
+        matches!(
+            self,
+            JsonRpcConnectorError::RpcError {
+                code: -32601 | -32602,
+                ..
+            }
+        )
+    }
+
+    /// Returns true if the node could not be reached or did not respond in time.
+    ///
+    /// Used by the [`crate::jsonrpc::validator_pool::ValidatorPool`] to decide whether to fail over to the next endpoint.
+    pub fn is_unreachable(&self) -> bool {
+        matches!(
+            self,
+            JsonRpcConnectorError::HyperError(_) | JsonRpcConnectorError::Timeout(_)
+        )
+    }
+
     /// Maps JsonRpcConnectorError to tonic::Status
     pub fn to_grpc_status(&self) -> tonic::Status {
         eprintln!("Error occurred: {}.", self);
@@ -47,7 +204,29 @@ impl JsonRpcConnectorError {
                 tonic::Status::invalid_argument(self.to_string())
             }
             JsonRpcConnectorError::HyperError(_) => tonic::Status::unavailable(self.to_string()),
+            JsonRpcConnectorError::Timeout(_) => tonic::Status::deadline_exceeded(self.to_string()),
             JsonRpcConnectorError::HttpError(_) => tonic::Status::internal(self.to_string()),
+            JsonRpcConnectorError::InvalidArgument(_)
+            | JsonRpcConnectorError::InvalidHeightRange { .. }
+            | JsonRpcConnectorError::RpcError { code: -8, .. } => {
+                tonic::Status::invalid_argument(self.to_string())
+            }
+            JsonRpcConnectorError::BlockNotFound(_)
+            | JsonRpcConnectorError::BlockNotOnMainChain(_) => {
+                tonic::Status::not_found(self.to_string())
+            }
+            JsonRpcConnectorError::HeightOutOfRange(_) => {
+                tonic::Status::out_of_range(self.to_string())
+            }
+            JsonRpcConnectorError::TransactionRejected(
+                SendTransactionError::AlreadyInChain | SendTransactionError::AlreadyInMempool,
+            ) => tonic::Status::already_exists(self.to_string()),
+            JsonRpcConnectorError::TransactionRejected(SendTransactionError::MissingInputs) => {
+                tonic::Status::failed_precondition(self.to_string())
+            }
+            JsonRpcConnectorError::TransactionRejected(_) => {
+                tonic::Status::invalid_argument(self.to_string())
+            }
             _ => tonic::Status::internal(self.to_string()),
         }
     }
@@ -1,12 +1,18 @@
 //! Request and response types for jsonRPC client.
+//!
+//! Nodes add fields to their responses over time, so response types ignore fields they do not know about
+//! rather than rejecting the response. Hand-written `Deserialize` impls only read the keys they need.
 
 use indexmap::IndexMap;
 use serde::Deserialize;
 
 use crate::primitives::{
     address::TransparentAddress,
-    block::{BlockHash, SerializedBlock},
-    chain::{ConsensusBranchIdHex, NetworkUpgradeInfo, TipConsensusBranch},
+    block::{BlockHash, MerkleRoot, SerializedBlock},
+    chain::{
+        ConsensusBranchIdHex, NetworkKind, NetworkUpgrade, NetworkUpgradeInfo, TipConsensusBranch,
+    },
+    error::NetworkError,
     height::ChainHeight,
     transaction::{
         BlockCommitmentTreeSize, CommitmentTreestate, NoteCommitmentSubtreeIndex, OrchardTreestate,
@@ -25,6 +31,31 @@ pub struct GetInfoResponse {
     pub subversion: String,
 }
 
+/// Response to a `getnetworkinfo` RPC request.
+///
+/// This is used for the output parameter of [`JsonRpcConnector::get_network_info`].
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+pub struct GetNetworkInfoResponse {
+    /// The node version, numeric
+    pub version: u64,
+
+    /// The server sub-version identifier, used as the network protocol user-agent
+    pub subversion: String,
+
+    /// The network protocol version
+    #[serde(rename = "protocolversion")]
+    pub protocol_version: u64,
+
+    /// The number of peer connections
+    pub connections: u64,
+
+    /// The minimum relay fee for non-free transactions, in zatoshis per 1000 bytes.
+    ///
+    /// Returned by the node in ZEC/kB.
+    #[serde(rename = "relayfee", deserialize_with = "deserialize_zec_amount")]
+    pub relay_fee: u64,
+}
+
 /// Response to a `getblockchaininfo` RPC request.
 ///
 /// This is used for the output parameter of [`JsonRpcConnector::get_blockchain_info`].
@@ -51,6 +82,156 @@ pub struct GetBlockchainInfoResponse {
 
     /// Branch IDs of the current and upcoming consensus rules
     pub consensus: TipConsensusBranch,
+
+    /// Chain value of each value pool at the current tip, if reported by the node.
+    #[serde(
+        rename = "valuePools",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub value_pools: Option<Vec<ValuePoolBalance>>,
+}
+
+impl GetBlockchainInfoResponse {
+    /// Returns the network named by the response's chain name, an error if the chain name is unknown.
+    pub fn network(&self) -> Result<NetworkKind, NetworkError> {
+        NetworkKind::from_chain_name(&self.chain)
+    }
+}
+
+/// Chain value of a single value pool, as returned in the `valuePools` field of a `getblockchaininfo` or verbose
+/// `getblock` RPC request.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ValuePoolBalance {
+    /// The pool id ("transparent", "sprout", "sapling" or "orchard").
+    pub id: String,
+
+    /// Whether the node is tracking the value of this pool.
+    #[serde(default)]
+    pub monitored: bool,
+
+    /// The total value held in the pool, in zatoshis, if tracked by the node.
+    #[serde(rename = "chainValueZat", default)]
+    pub chain_value_zat: Option<u64>,
+}
+
+/// Total chain value held in each value pool at the current tip, in zatoshis.
+///
+/// Pools not reported by the node are `None` (zcashd does not report the transparent pool).
+///
+/// This is used for the output parameter of [`JsonRpcConnector::get_pool_balances`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PoolBalances {
+    /// Transparent pool chain value.
+    pub transparent: Option<u64>,
+    /// Sprout pool chain value.
+    pub sprout: Option<u64>,
+    /// Sapling pool chain value.
+    pub sapling: Option<u64>,
+    /// Orchard pool chain value.
+    pub orchard: Option<u64>,
+}
+
+impl PoolBalances {
+    /// Builds the pool balances from the `valuePools` reported by the node.
+    pub fn from_value_pools(value_pools: &[ValuePoolBalance]) -> Self {
+        let mut balances = PoolBalances::default();
+        for pool in value_pools {
+            let value = if pool.monitored {
+                pool.chain_value_zat
+            } else {
+                None
+            };
+            match pool.id.as_str() {
+                "transparent" => balances.transparent = value,
+                "sprout" => balances.sprout = value,
+                "sapling" => balances.sapling = value,
+                "orchard" => balances.orchard = value,
+                _ => {}
+            }
+        }
+        balances
+    }
+
+    /// Returns the total chain value of the pools reported by the node, None if no pool value was reported.
+    pub fn total(&self) -> Option<u64> {
+        let mut values = [self.transparent, self.sprout, self.sapling, self.orchard]
+            .into_iter()
+            .flatten()
+            .peekable();
+        values.peek()?;
+        values.try_fold(0u64, u64::checked_add)
+    }
+}
+
+/// A Zcash value pool.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValuePool {
+    /// Transparent pool.
+    Transparent,
+    /// Sprout shielded pool.
+    Sprout,
+    /// Sapling shielded pool.
+    Sapling,
+    /// Orchard shielded pool.
+    Orchard,
+}
+
+impl ValuePool {
+    /// Returns the pool with the given `valuePools` id.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "transparent" => Some(ValuePool::Transparent),
+            "sprout" => Some(ValuePool::Sprout),
+            "sapling" => Some(ValuePool::Sapling),
+            "orchard" => Some(ValuePool::Orchard),
+            _ => None,
+        }
+    }
+
+    /// Returns the network upgrade that introduced the pool, None for pools active since genesis.
+    pub fn activation_upgrade(&self) -> Option<NetworkUpgrade> {
+        match self {
+            ValuePool::Transparent | ValuePool::Sprout => None,
+            ValuePool::Sapling => Some(NetworkUpgrade::Sapling),
+            ValuePool::Orchard => Some(NetworkUpgrade::Nu5),
+        }
+    }
+}
+
+/// Chain value held in a single value pool, in zatoshis.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PoolValue {
+    /// The value pool.
+    pub pool: ValuePool,
+    /// Total value held in the pool, in zatoshis.
+    pub chain_value_zat: u64,
+}
+
+/// Chain value held in each value pool as of a block, in zatoshis.
+///
+/// Only pools active at the block's height and tracked by the node are listed.
+///
+/// This is used for the output parameter of [`JsonRpcConnector::get_pool_values`].
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PoolValueResponse {
+    /// The hash of the block.
+    pub hash: BlockHash,
+    /// The height of the block.
+    pub height: ChainHeight,
+    /// Chain value of each pool, in the order reported by the node.
+    pub pools: Vec<PoolValue>,
+}
+
+impl PoolValueResponse {
+    /// Returns the chain value held in `pool`, None if the pool is inactive at this block or not tracked by the node.
+    pub fn get(&self, pool: ValuePool) -> Option<u64> {
+        self.pools
+            .iter()
+            .find(|pool_value| pool_value.pool == pool)
+            .map(|pool_value| pool_value.chain_value_zat)
+    }
 }
 
 /// The transparent balance of a set of addresses.
@@ -86,7 +267,7 @@ impl Default for GetBlockHash {
 /// Contains the hex-encoded hash of the sent transaction.
 ///
 /// This is used for the output parameter of [`JsonRpcConnector::get_block`].
-#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(untagged)]
 pub enum GetBlockResponse {
     /// The request block, hex-encoded.
@@ -108,14 +289,114 @@ pub enum GetBlockResponse {
         #[serde(skip_serializing_if = "Option::is_none")]
         time: Option<i64>,
 
-        /// List of transaction IDs in block order, hex-encoded.
-        tx: Vec<String>,
+        /// List of transactions in block order, as hex-encoded txids (verbosity = 1) or transaction objects (verbosity = 2).
+        tx: GetBlockTransactions,
 
         /// Information about the note commitment trees.
         trees: BlockCommitmentTreeSize,
+
+        /// The block version, None if not returned by the node.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<u32>,
+
+        /// The root of the block's transaction Merkle tree, None if not returned by the node.
+        #[serde(
+            rename = "merkleroot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        merkle_root: Option<MerkleRoot>,
+
+        /// The block nonce, hex-encoded, None if not returned by the node.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        nonce: Option<String>,
+
+        /// The compact encoding of the block's target threshold, hex-encoded, None if not returned by the node.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bits: Option<String>,
+
+        /// The block difficulty, relative to the network's minimum difficulty, None if not returned by the node.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        difficulty: Option<f64>,
+
+        /// Chain value of each value pool as of this block, None if not returned by the node.
+        #[serde(
+            rename = "valuePools",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        value_pools: Option<Vec<ValuePoolBalance>>,
     },
 }
 
+impl GetBlockResponse {
+    /// Returns true if the block is in the node's best chain, false if it is on a side chain.
+    ///
+    /// The node reports `confirmations: -1` for blocks that are valid but not in the best chain.
+    /// Raw blocks carry no chain context, None is returned for [`GetBlockResponse::Raw`].
+    pub fn is_main_chain(&self) -> Option<bool> {
+        match self {
+            GetBlockResponse::Object { confirmations, .. } => Some(*confirmations >= 0),
+            GetBlockResponse::Raw(_) => None,
+        }
+    }
+}
+
+/// Transactions held in a [`GetBlockResponse::Object`].
+///
+/// The variant is picked from the contents of the `tx` array returned by the node.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+pub enum GetBlockTransactions {
+    /// List of transaction IDs in block order, hex-encoded (verbosity = 1).
+    Txids(Vec<String>),
+    /// List of transaction objects in block order (verbosity = 2).
+    Objects(Vec<BlockTxObject>),
+}
+
+impl GetBlockTransactions {
+    /// Returns the hex-encoded transaction IDs in block order.
+    pub fn txids(&self) -> Vec<String> {
+        match self {
+            GetBlockTransactions::Txids(txids) => txids.clone(),
+            GetBlockTransactions::Objects(transactions) => transactions
+                .iter()
+                .map(|transaction| transaction.txid.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A transaction object returned inline by a `getblock` RPC request with verbosity = 2.
+///
+/// Unknown fields returned by the node are ignored.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct BlockTxObject {
+    /// The transaction ID, in big-endian order, hex-encoded.
+    #[serde(with = "hex")]
+    pub txid: TransactionHash,
+
+    /// The raw transaction, encoded as hex bytes, if returned by the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hex: Option<SerializedTransaction>,
+
+    /// The transaction version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
+    /// The serialized transaction size in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+
+    /// The transaction lock time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locktime: Option<u32>,
+
+    /// The block height after which the transaction expires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiryheight: Option<u32>,
+}
+
 /// Contains the hex-encoded hash of the requested block.
 ///
 /// This is used for the output parameter of [`JsonRpcConnector::get_best_block_hash`].
@@ -126,12 +407,28 @@ pub struct BestBlockHashResponse(#[serde(with = "hex")] pub BlockHash);
 /// Vec of transaction ids, as a JSON array.
 ///
 /// This is used for the output parameter of [`JsonRpcConnector::get_raw_mempool`] and [`JsonRpcConnector::get_address_txids`].
+///
+/// Deserialization fails if the JSON is not an array of strings.
 #[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
 pub struct TxidsResponse {
     /// Vec of txids.
     pub transactions: Vec<String>,
 }
 
+impl TxidsResponse {
+    /// Returns the txids with duplicates removed, keeping the first occurrence of each so the original order is preserved.
+    pub fn deduplicated(self) -> Self {
+        let mut seen = std::collections::HashSet::new();
+        TxidsResponse {
+            transactions: self
+                .transactions
+                .into_iter()
+                .filter(|txid| seen.insert(txid.clone()))
+                .collect(),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for TxidsResponse {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -143,13 +440,111 @@ impl<'de> Deserialize<'de> for TxidsResponse {
             .as_array()
             .ok_or_else(|| serde::de::Error::custom("Expected the JSON to be an array"))?
             .iter()
-            .filter_map(|item| item.as_str().map(String::from))
-            .collect::<Vec<String>>();
+            .enumerate()
+            .map(|(index, item)| {
+                item.as_str().map(String::from).ok_or_else(|| {
+                    serde::de::Error::custom(format!(
+                        "Expected txid at index {} to be a string, found {}",
+                        index, item
+                    ))
+                })
+            })
+            .collect::<Result<Vec<String>, D::Error>>()?;
 
         Ok(TxidsResponse { transactions })
     }
 }
 
+/// A single transaction in the node's mempool, as returned by a verbose `getrawmempool` RPC request.
+///
+/// Fields only populated by some nodes are optional.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct MempoolEntry {
+    /// The transaction id, hex-encoded.
+    pub txid: String,
+
+    /// The transaction size in bytes.
+    pub size: u64,
+
+    /// The transaction fee, in zatoshis.
+    pub fee: u64,
+
+    /// Unix time the transaction entered the mempool.
+    ///
+    /// UTC seconds since the Unix 1970-01-01 epoch.
+    pub time: i64,
+
+    /// The block height when the transaction entered the mempool.
+    pub height: Option<ChainHeight>,
+
+    /// The transaction fee with fee deltas used for mining priority, in zatoshis.
+    pub modified_fee: Option<u64>,
+
+    /// Unconfirmed transactions used as inputs for this transaction, hex-encoded txids.
+    pub depends: Option<Vec<String>>,
+}
+
+/// A mempool transaction as serialized by the node, keyed by txid in the verbose `getrawmempool` response.
+#[derive(Clone, Debug, serde::Deserialize)]
+struct MempoolEntryObject {
+    size: u64,
+    /// Fee in ZEC.
+    fee: f64,
+    time: i64,
+    #[serde(default)]
+    height: Option<ChainHeight>,
+    /// Modified fee in ZEC.
+    #[serde(rename = "modifiedfee", default)]
+    modified_fee: Option<f64>,
+    #[serde(default)]
+    depends: Option<Vec<String>>,
+}
+
+/// Converts a non-negative ZEC amount, as returned by the node, to zatoshis.
+fn zec_to_zatoshis(zec: f64) -> u64 {
+    (zec * 100_000_000.0).round() as u64
+}
+
+/// Deserializes a ZEC amount, as returned by the node, to zatoshis.
+fn deserialize_zec_amount<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    f64::deserialize(deserializer).map(zec_to_zatoshis)
+}
+
+/// Vec of mempool transactions, converted from the txid keyed JSON object.
+///
+/// This is used for the output parameter of [`JsonRpcConnector::get_raw_mempool_verbose`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct GetRawMempoolVerboseResponse {
+    /// Mempool transactions, in the order returned by the node.
+    pub entries: Vec<MempoolEntry>,
+}
+
+impl<'de> Deserialize<'de> for GetRawMempoolVerboseResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let objects = IndexMap::<String, MempoolEntryObject>::deserialize(deserializer)?;
+        let entries = objects
+            .into_iter()
+            .map(|(txid, object)| MempoolEntry {
+                txid,
+                size: object.size,
+                fee: zec_to_zatoshis(object.fee),
+                time: object.time,
+                height: object.height,
+                modified_fee: object.modified_fee.map(zec_to_zatoshis),
+                depends: object.depends,
+            })
+            .collect();
+
+        Ok(GetRawMempoolVerboseResponse { entries })
+    }
+}
+
 /// Contains the hex-encoded Sapling & Orchard note commitment trees, and their
 /// corresponding [`block::Hash`], [`Height`], and block time.
 ///
@@ -190,13 +585,14 @@ impl<'de> Deserialize<'de> for GetTreestateResponse {
         let time = v["time"]
             .as_i64()
             .ok_or_else(|| serde::de::Error::missing_field("time"))? as u32;
+        // NOTE: The node returns an empty or null treestate for pools that are not yet active at the requested height (e.g. orchard before NU5).
         let sapling_final_state = v["sapling"]["commitments"]["finalState"]
             .as_str()
-            .ok_or_else(|| serde::de::Error::missing_field("sapling final state"))?
+            .unwrap_or_default()
             .to_string();
         let orchard_final_state = v["orchard"]["commitments"]["finalState"]
             .as_str()
-            .ok_or_else(|| serde::de::Error::missing_field("orchard final state"))?
+            .unwrap_or_default()
             .to_string();
         Ok(GetTreestateResponse {
             height,
@@ -234,6 +630,22 @@ pub enum GetTransactionResponse {
         /// The confirmations of the block in the best chain that contains the transaction,
         /// or 0 if the transaction is in the mempool.
         confirmations: u32,
+        /// The hash of the block in the best chain that contains the transaction,
+        /// or None if the transaction is in the mempool or the node did not return it.
+        #[serde(rename = "blockhash", skip_serializing_if = "Option::is_none")]
+        block_hash: Option<GetBlockHash>,
+        /// The time of the block in the best chain that contains the transaction, in seconds since the unix epoch,
+        /// or None if the transaction is in the mempool or the node did not return it.
+        #[serde(rename = "blocktime", skip_serializing_if = "Option::is_none")]
+        block_time: Option<i64>,
+        /// The last height at which the mempool transaction can be mined, or None if the transaction never expires,
+        /// is mined or the node did not return it.
+        #[serde(rename = "expiryheight", skip_serializing_if = "Option::is_none")]
+        expiry_height: Option<u32>,
+        /// The time the transaction entered the mempool, in seconds since the unix epoch,
+        /// or None if the transaction is mined or the node did not return it.
+        #[serde(rename = "time", skip_serializing_if = "Option::is_none")]
+        mempool_time: Option<i64>,
     },
 }
 
@@ -253,18 +665,47 @@ impl<'de> Deserialize<'de> for GetTransactionResponse {
                 .as_u64()
                 .ok_or_else(|| serde::de::Error::custom("Missing or invalid confirmations"))?
                 as u32;
+            let block_hash = match v.get("blockhash") {
+                Some(block_hash) => Some(
+                    serde_json::from_value(block_hash.clone()).map_err(serde::de::Error::custom)?,
+                ),
+                None => None,
+            };
+            let block_time = match v.get("blocktime") {
+                Some(block_time) => Some(
+                    block_time
+                        .as_i64()
+                        .ok_or_else(|| serde::de::Error::custom("Invalid blocktime"))?,
+                ),
+                None => None,
+            };
+            let (expiry_height, mempool_time) = if height < 0 {
+                mempool_fields(&v).map_err(serde::de::Error::custom)?
+            } else {
+                (None, None)
+            };
             let obj = GetTransactionResponse::Object {
                 hex,
                 height,
                 confirmations,
+                block_hash,
+                block_time,
+                expiry_height,
+                mempool_time,
             };
             Ok(obj)
         } else if v.get("hex").is_some() && v.get("txid").is_some() {
             let hex = serde_json::from_value(v["hex"].clone()).map_err(serde::de::Error::custom)?;
+            let (expiry_height, mempool_time) =
+                mempool_fields(&v).map_err(serde::de::Error::custom)?;
             let obj = GetTransactionResponse::Object {
                 hex,
                 height: -1,
                 confirmations: 0,
+                block_hash: None,
+                block_time: None,
+                expiry_height,
+                mempool_time,
             };
             Ok(obj)
         } else {
@@ -276,15 +717,31 @@ impl<'de> Deserialize<'de> for GetTransactionResponse {
     }
 }
 
-/// *** THE FOLLOWING CODE IS CURRENTLY UNUSED BY ZINGO-PROXY AND UNTESTED! ***
-/// ***                           TEST BEFORE USE                           ***
+/// Reads the expiry height and mempool entry time of a verbose mempool transaction.
+///
+/// An expiry height of 0 means the transaction never expires and is returned as None.
+fn mempool_fields(v: &serde_json::Value) -> Result<(Option<u32>, Option<i64>), &'static str> {
+    let expiry_height = match v.get("expiryheight") {
+        Some(expiry_height) => {
+            let expiry_height = expiry_height
+                .as_u64()
+                .and_then(|expiry_height| u32::try_from(expiry_height).ok())
+                .ok_or("Invalid expiryheight")?;
+            Some(expiry_height).filter(|expiry_height| *expiry_height != 0)
+        }
+        None => None,
+    };
+    let mempool_time = match v.get("time") {
+        Some(time) => Some(time.as_i64().ok_or("Invalid time")?),
+        None => None,
+    };
+    Ok((expiry_height, mempool_time))
+}
 
 /// Contains the Sapling or Orchard pool label, the index of the first subtree in the list,
 /// and a list of subtree roots and end heights.
 ///
 /// This is used for the output parameter of [`JsonRpcConnector::get_subtrees_by_index`].
-///
-/// *** UNTESTED - TEST BEFORE USE ***
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct GetSubtreesResponse {
     /// The shielded pool to which the subtrees belong.
@@ -295,11 +752,60 @@ pub struct GetSubtreesResponse {
 
     /// A sequential list of complete subtrees, in `index` order.
     ///
-    /// The generic subtree root type is a hex-encoded Sapling or Orchard subtree root string.
+    /// Roots are served hex-encoded by the node and decoded into Sapling or Orchard subtree root bytes.
     // #[serde(skip_serializing_if = "Vec::is_empty")]
     pub subtrees: Vec<SubtreeRpcData>,
 }
 
+/// A change in the balance of a transparent address, an output received or an input spent.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct AddressDelta {
+    /// The transaction id, in big-endian order, hex-encoded
+    #[serde(with = "hex")]
+    pub txid: TransactionHash,
+
+    /// The index of the output received, or of the input spent, in the transaction
+    pub index: u32,
+
+    /// The change in the address' balance in zatoshis, negative for spends
+    pub satoshis: i64,
+
+    /// The height of the block holding the transaction
+    pub height: ChainHeight,
+
+    /// The transparent address, base58check encoded
+    pub address: String,
+}
+
+/// Balance changes of a set of transparent addresses, as a JSON array.
+///
+/// This is used for the output parameter of [`JsonRpcConnector::get_address_deltas`].
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
+pub struct GetAddressDeltasResponse {
+    /// The balance changes, in height order.
+    pub deltas: Vec<AddressDelta>,
+}
+
+impl GetAddressDeltasResponse {
+    /// Returns the deltas sorted by height.
+    ///
+    /// zcashd returns the deltas of each address in turn, the sort is stable so deltas at the same height keep the
+    /// node's order.
+    pub fn height_ordered(mut self) -> Self {
+        self.deltas.sort_by_key(|delta| delta.height);
+        self
+    }
+
+    /// Returns the sum of the deltas in zatoshis, the change in the addresses' combined balance over the range.
+    pub fn balance_change(&self) -> i64 {
+        self.deltas.iter().map(|delta| delta.satoshis).sum()
+    }
+}
+
+/// *** THE FOLLOWING CODE IS CURRENTLY UNUSED BY ZINGO-PROXY AND UNTESTED! ***
+/// ***                           TEST BEFORE USE                           ***
+
 /// This is used for the output parameter of [`JsonRpcConnector::get_address_utxos`].
 ///
 /// *** UNTESTED - TEST BEFORE USE ***
@@ -326,3 +832,84 @@ pub struct GetUtxosResponse {
     /// The block height, numeric.
     pub height: ChainHeight,
 }
+
+/// Unspent outputs for a set of addresses along with their aggregate value.
+///
+/// This is used for the output parameter of [`JsonRpcConnector::get_address_utxos_summary`].
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct GetUtxosSummaryResponse {
+    /// The unspent outputs, in the order returned by the node.
+    pub utxos: Vec<GetUtxosResponse>,
+
+    /// The total value of `utxos`, in zatoshis.
+    pub total_satoshis: u64,
+}
+
+/// Position in a paginated walk over the unspent outputs of a set of addresses.
+///
+/// Outputs are walked in (height, txid, output index) order. Only outputs mined at or below `anchor_height` are walked,
+/// so outputs arriving at the tip after the first page is fetched do not shift later pages.
+///
+/// This is used for the input and output parameters of [`JsonRpcConnector::get_address_utxos_page`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct UtxoCursor {
+    /// The highest block height included in the walk, fixed when the first page is fetched.
+    pub anchor_height: ChainHeight,
+
+    /// The block height of the last output returned.
+    pub height: ChainHeight,
+
+    /// The txid of the last output returned.
+    pub txid: TransactionHash,
+
+    /// The output index of the last output returned.
+    pub output_index: u32,
+}
+
+/// A page of unspent outputs for a set of addresses.
+///
+/// This is used for the output parameter of [`JsonRpcConnector::get_address_utxos_page`].
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct GetUtxosPage {
+    /// The unspent outputs, in (height, txid, output index) order.
+    pub utxos: Vec<GetUtxosResponse>,
+
+    /// The cursor to fetch the next page from, None once all outputs have been returned.
+    pub next: Option<UtxoCursor>,
+}
+
+impl GetUtxosPage {
+    /// Returns the page of at most `max_entries` outputs following `cursor`, or starting at `start_height` if `cursor` is None.
+    ///
+    /// `utxos` is the full set of unspent outputs returned by the node, outputs above `anchor_height` are skipped.
+    /// `max_entries` must be non-zero.
+    pub fn from_utxos(
+        mut utxos: Vec<GetUtxosResponse>,
+        start_height: ChainHeight,
+        max_entries: usize,
+        anchor_height: ChainHeight,
+        cursor: Option<&UtxoCursor>,
+    ) -> Self {
+        utxos.retain(|utxo| {
+            utxo.height >= start_height
+                && utxo.height <= anchor_height
+                && cursor.map_or(true, |cursor| {
+                    (utxo.height, utxo.txid, utxo.output_index)
+                        > (cursor.height, cursor.txid, cursor.output_index)
+                })
+        });
+        utxos.sort_by_key(|utxo| (utxo.height, utxo.txid, utxo.output_index));
+        let next = if utxos.len() > max_entries {
+            utxos.truncate(max_entries);
+            utxos.last().map(|last| UtxoCursor {
+                anchor_height,
+                height: last.height,
+                txid: last.txid,
+                output_index: last.output_index,
+            })
+        } else {
+            None
+        };
+        GetUtxosPage { utxos, next }
+    }
+}
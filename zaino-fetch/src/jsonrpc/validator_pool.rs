@@ -0,0 +1,271 @@
+//! Failover across a prioritized list of validator JsonRPC endpoints.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use http::Uri;
+
+use crate::{
+    jsonrpc::{
+        connector::{test_node_connection, Auth, JsonRpcConnector},
+        error::JsonRpcConnectorError,
+    },
+    task::spawn_named,
+};
+
+/// Ejection and recovery settings of a [`ValidatorPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailoverConfig {
+    /// Time an endpoint is ejected for after its first failure, doubled for each further consecutive failure.
+    pub ejection_period: Duration,
+    /// Maximum time an endpoint is ejected for.
+    pub max_ejection_period: Duration,
+    /// Interval at which endpoints with a higher priority than the active endpoint are rechecked for recovery.
+    pub recheck_interval: Duration,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        FailoverConfig {
+            ejection_period: Duration::from_secs(5),
+            max_ejection_period: Duration::from_secs(300),
+            recheck_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Failure tracking of a single endpoint.
+#[derive(Debug, Clone, Copy, Default)]
+struct EndpointHealth {
+    /// Number of failures since the endpoint last answered a request.
+    consecutive_failures: u32,
+    /// Time until which the endpoint is skipped, None if the endpoint is not ejected.
+    ejected_until: Option<Instant>,
+}
+
+impl EndpointHealth {
+    /// Returns true if the endpoint is ejected at time `now`.
+    fn is_ejected(&self, now: Instant) -> bool {
+        self.ejected_until.is_some_and(|until| until > now)
+    }
+}
+
+/// Holds the active endpoint and failover count of a [`ValidatorPool`].
+#[derive(Debug, Clone)]
+pub struct ValidatorPoolStatus {
+    /// Endpoints in priority order.
+    endpoints: Arc<Vec<Uri>>,
+    /// Index of the active endpoint.
+    active: Arc<AtomicUsize>,
+    /// Number of times the active endpoint has changed.
+    failovers: Arc<AtomicU64>,
+}
+
+impl ValidatorPoolStatus {
+    /// Returns the endpoint requests are currently sent to first.
+    pub fn active_endpoint(&self) -> &Uri {
+        &self.endpoints[self.active.load(Ordering::SeqCst)]
+    }
+
+    /// Returns the priority of the active endpoint, 0 being the primary.
+    pub fn active_index(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of times the active endpoint has changed.
+    pub fn failovers(&self) -> u64 {
+        self.failovers.load(Ordering::Relaxed)
+    }
+}
+
+/// A prioritized list of validator endpoints, shared by the JsonRpcConnectors routing requests through it.
+///
+/// Requests are sent to the active endpoint first, then to the remaining endpoints in priority order.
+/// An endpoint that fails to connect or times out is ejected for [`FailoverConfig::ejection_period`],
+/// doubling with each consecutive failure, so a flapping node is not retried on every call.
+/// Endpoints with a higher priority than the active endpoint are rechecked by [`ValidatorPool::spawn_recheck`].
+#[derive(Debug, Clone)]
+pub struct ValidatorPool {
+    /// Failure tracking, by endpoint priority.
+    health: Arc<Mutex<Vec<EndpointHealth>>>,
+    /// Ejection and recovery settings.
+    config: FailoverConfig,
+    /// Active endpoint and failover count.
+    status: ValidatorPoolStatus,
+}
+
+impl ValidatorPool {
+    /// Returns a new pool of `endpoints`, in priority order, with the first endpoint active.
+    ///
+    /// Returns an InvalidArgument error if `endpoints` is empty.
+    pub fn new(endpoints: Vec<Uri>, config: FailoverConfig) -> Result<Self, JsonRpcConnectorError> {
+        if endpoints.is_empty() {
+            return Err(JsonRpcConnectorError::InvalidArgument(
+                "A validator pool requires at least one endpoint".to_string(),
+            ));
+        }
+        Ok(ValidatorPool {
+            health: Arc::new(Mutex::new(vec![EndpointHealth::default(); endpoints.len()])),
+            config,
+            status: ValidatorPoolStatus {
+                endpoints: Arc::new(endpoints),
+                active: Arc::new(AtomicUsize::new(0)),
+                failovers: Arc::new(AtomicU64::new(0)),
+            },
+        })
+    }
+
+    /// Returns the endpoints in priority order.
+    pub fn endpoints(&self) -> &[Uri] {
+        &self.status.endpoints
+    }
+
+    /// Returns true if `uri` is one of the pool's endpoints.
+    pub fn contains(&self, uri: &Uri) -> bool {
+        self.status.endpoints.contains(uri)
+    }
+
+    /// Returns the pool's active endpoint and failover count.
+    pub fn status(&self) -> ValidatorPoolStatus {
+        self.status.clone()
+    }
+
+    /// Returns the indexes of the endpoints to try, the active endpoint first then the rest by priority.
+    ///
+    /// Ejected endpoints are skipped, unless all endpoints are ejected.
+    pub(crate) fn candidates(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let active = self.status.active_index();
+        let order: Vec<usize> = std::iter::once(active)
+            .chain((0..self.endpoints().len()).filter(|index| *index != active))
+            .collect();
+        let available: Vec<usize> = match self.health.lock() {
+            Ok(health) => order
+                .iter()
+                .copied()
+                .filter(|index| !health[*index].is_ejected(now))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        if available.is_empty() {
+            order
+        } else {
+            available
+        }
+    }
+
+    /// Returns the endpoint at `index`.
+    pub(crate) fn endpoint(&self, index: usize) -> &Uri {
+        &self.status.endpoints[index]
+    }
+
+    /// Records a response from the endpoint at `index`, making it the active endpoint.
+    pub(crate) fn record_success(&self, index: usize) {
+        if let Ok(mut health) = self.health.lock() {
+            health[index] = EndpointHealth::default();
+        }
+        self.set_active(index);
+    }
+
+    /// Records a connection failure or timeout of the endpoint at `index`, ejecting it.
+    pub(crate) fn record_failure(&self, index: usize) {
+        if let Ok(mut health) = self.health.lock() {
+            let endpoint = &mut health[index];
+            endpoint.consecutive_failures = endpoint.consecutive_failures.saturating_add(1);
+            let ejection = self
+                .config
+                .ejection_period
+                .saturating_mul(2u32.saturating_pow(endpoint.consecutive_failures - 1))
+                .min(self.config.max_ejection_period);
+            endpoint.ejected_until = Some(Instant::now() + ejection);
+        }
+        eprintln!(
+            "Validator endpoint {} unreachable, failing over.",
+            self.endpoint(index)
+        );
+    }
+
+    /// Sets the active endpoint, counting a failover if it changed.
+    fn set_active(&self, index: usize) {
+        let previous = self.status.active.swap(index, Ordering::SeqCst);
+        if previous != index {
+            self.status.failovers.fetch_add(1, Ordering::Relaxed);
+            println!(
+                "Active validator endpoint changed from {} to {}.",
+                self.endpoint(previous),
+                self.endpoint(index)
+            );
+        }
+    }
+
+    /// Sends a `getinfo` request to each candidate endpoint, in the order requests are sent, returns the first endpoint to
+    /// answer and makes it the active endpoint.
+    ///
+    /// Endpoints that do not answer are ejected. The candidates are tried up to 3 times, 3 seconds apart, before the
+    /// last connection error is returned.
+    pub async fn probe(&self, auth: Option<&Auth>) -> Result<Uri, JsonRpcConnectorError> {
+        let mut last_error = None;
+        for attempt in 0..3 {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            }
+            for index in self.candidates() {
+                match test_node_connection(self.endpoint(index).clone(), auth).await {
+                    Ok(()) => {
+                        self.record_success(index);
+                        return Ok(self.endpoint(index).clone());
+                    }
+                    Err(e) => {
+                        self.record_failure(index);
+                        last_error = Some(e);
+                    }
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            JsonRpcConnectorError::CustomError("No validator endpoint answered".to_string())
+        }))
+    }
+
+    /// Spawns the recovery check.
+    ///
+    /// Every [`FailoverConfig::recheck_interval`] the endpoints with a higher priority than the active endpoint,
+    /// that are not ejected, are sent a `getblockcount` request; the first to answer becomes active. Runs while `online` is true.
+    pub fn spawn_recheck(
+        &self,
+        auth: Option<Auth>,
+        online: Arc<AtomicBool>,
+    ) -> tokio::task::JoinHandle<()> {
+        let pool = self.clone();
+        spawn_named("validator_pool_recheck", async move {
+            let mut interval = tokio::time::interval(pool.config.recheck_interval);
+            while online.load(Ordering::SeqCst) {
+                interval.tick().await;
+                let now = Instant::now();
+                let higher_priority: Vec<usize> = match pool.health.lock() {
+                    Ok(health) => (0..pool.status.active_index())
+                        .filter(|index| !health[*index].is_ejected(now))
+                        .collect(),
+                    Err(_) => continue,
+                };
+                for index in higher_priority {
+                    let connector =
+                        JsonRpcConnector::new_with_auth(pool.endpoint(index).clone(), auth.clone());
+                    match connector.get_block_count().await {
+                        Err(e) if e.is_unreachable() => pool.record_failure(index),
+                        // The node answered, even if with an error.
+                        _ => {
+                            pool.record_success(index);
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
@@ -6,5 +6,10 @@
 #![forbid(unsafe_code)]
 
 pub mod chain;
+pub mod error;
+pub mod health;
 pub mod jsonrpc;
+pub mod metrics;
 pub mod primitives;
+pub mod spans;
+pub mod task;
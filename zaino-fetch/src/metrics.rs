@@ -0,0 +1,322 @@
+//! Zingo-Indexer Prometheus metrics.
+//!
+//! Metrics are only recorded once [`enable`] has been called, while disabled each instrumentation point costs a single atomic load.
+
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response,
+};
+
+use crate::{jsonrpc::connector::JsonRpcConnector, task::spawn_named};
+
+/// Upper bounds, in seconds, of the request latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Set once the metrics endpoint is enabled.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Global metrics registry.
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// Kind of method being instrumented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodKind {
+    /// gRPC method served by Zaino.
+    Grpc,
+    /// JsonRPC method sent to the full node / validator.
+    JsonRpc,
+}
+
+impl MethodKind {
+    /// Returns the metric name prefix for the method kind.
+    fn prefix(&self) -> &'static str {
+        match self {
+            MethodKind::Grpc => "zaino_grpc",
+            MethodKind::JsonRpc => "zaino_jsonrpc",
+        }
+    }
+}
+
+/// Request count and latency histogram of a single method.
+#[derive(Debug, Clone, Default)]
+struct MethodMetrics {
+    /// Number of requests in each latency bucket, plus a final "+Inf" bucket.
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    /// Sum of all request latencies in seconds.
+    sum: f64,
+    /// Number of requests.
+    count: u64,
+}
+
+impl MethodMetrics {
+    /// Records a single request.
+    fn observe(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket] += 1;
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Holds all metrics recorded by Zaino.
+#[derive(Debug, Default)]
+struct Registry {
+    grpc: Mutex<BTreeMap<String, MethodMetrics>>,
+    jsonrpc: Mutex<BTreeMap<String, MethodMetrics>>,
+    mempool_size: AtomicU64,
+    best_block_height: AtomicU64,
+    /// Bits of the sync progress fraction, stored as an `f64`.
+    sync_progress: AtomicU64,
+}
+
+impl Registry {
+    /// Returns the method metrics of the given kind.
+    fn methods(&self, kind: MethodKind) -> &Mutex<BTreeMap<String, MethodMetrics>> {
+        match kind {
+            MethodKind::Grpc => &self.grpc,
+            MethodKind::JsonRpc => &self.jsonrpc,
+        }
+    }
+}
+
+/// Returns the global registry.
+fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(Registry::default)
+}
+
+/// Enables metrics recording.
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Returns true if metrics recording is enabled.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Records the latency of a single request, called on drop.
+///
+/// Holds nothing if metrics are disabled.
+#[derive(Debug)]
+pub struct MethodTimer {
+    inner: Option<(MethodKind, String, Instant)>,
+}
+
+impl MethodTimer {
+    /// Starts timing a request to the given method.
+    pub fn start(kind: MethodKind, method: &str) -> Self {
+        let inner = if is_enabled() {
+            Some((kind, method.to_string(), Instant::now()))
+        } else {
+            None
+        };
+        MethodTimer { inner }
+    }
+}
+
+impl Drop for MethodTimer {
+    fn drop(&mut self) {
+        if let Some((kind, method, start)) = self.inner.take() {
+            observe(kind, method, start.elapsed());
+        }
+    }
+}
+
+/// Records a single request to the given method.
+pub fn observe(kind: MethodKind, method: impl Into<String>, elapsed: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(mut methods) = registry().methods(kind).lock() {
+        methods.entry(method.into()).or_default().observe(elapsed);
+    }
+}
+
+/// Sets the mempool size gauge.
+pub fn set_mempool_size(size: u64) {
+    if is_enabled() {
+        registry().mempool_size.store(size, Ordering::Relaxed);
+    }
+}
+
+/// Sets the best block height gauge.
+pub fn set_best_block_height(height: u64) {
+    if is_enabled() {
+        registry()
+            .best_block_height
+            .store(height, Ordering::Relaxed);
+    }
+}
+
+/// Sets the sync progress gauge, the fraction of the estimated chain synced by the indexer.
+pub fn set_sync_progress(fraction: f64) {
+    if is_enabled() {
+        registry()
+            .sync_progress
+            .store(fraction.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Returns all metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+    let registry = registry();
+    let mut output = String::new();
+    for kind in [MethodKind::Grpc, MethodKind::JsonRpc] {
+        let prefix = kind.prefix();
+        let methods = registry
+            .methods(kind)
+            .lock()
+            .map(|methods| methods.clone())
+            .unwrap_or_default();
+
+        let _ = writeln!(
+            output,
+            "# HELP {prefix}_requests_total Total number of requests by method."
+        );
+        let _ = writeln!(output, "# TYPE {prefix}_requests_total counter");
+        for (method, metrics) in methods.iter() {
+            let _ = writeln!(
+                output,
+                "{prefix}_requests_total{{method=\"{method}\"}} {}",
+                metrics.count
+            );
+        }
+
+        let _ = writeln!(
+            output,
+            "# HELP {prefix}_request_duration_seconds Request latency in seconds by method."
+        );
+        let _ = writeln!(output, "# TYPE {prefix}_request_duration_seconds histogram");
+        for (method, metrics) in methods.iter() {
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(metrics.buckets.iter()) {
+                cumulative += count;
+                let _ = writeln!(
+                    output,
+                    "{prefix}_request_duration_seconds_bucket{{method=\"{method}\",le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                output,
+                "{prefix}_request_duration_seconds_bucket{{method=\"{method}\",le=\"+Inf\"}} {}",
+                metrics.count
+            );
+            let _ = writeln!(
+                output,
+                "{prefix}_request_duration_seconds_sum{{method=\"{method}\"}} {}",
+                metrics.sum
+            );
+            let _ = writeln!(
+                output,
+                "{prefix}_request_duration_seconds_count{{method=\"{method}\"}} {}",
+                metrics.count
+            );
+        }
+    }
+
+    let _ = writeln!(
+        output,
+        "# HELP zaino_mempool_size Number of transactions in the node's mempool."
+    );
+    let _ = writeln!(output, "# TYPE zaino_mempool_size gauge");
+    let _ = writeln!(
+        output,
+        "zaino_mempool_size {}",
+        registry.mempool_size.load(Ordering::Relaxed)
+    );
+    let _ = writeln!(
+        output,
+        "# HELP zaino_best_block_height Height of the tip of the node's best chain."
+    );
+    let _ = writeln!(output, "# TYPE zaino_best_block_height gauge");
+    let _ = writeln!(
+        output,
+        "zaino_best_block_height {}",
+        registry.best_block_height.load(Ordering::Relaxed)
+    );
+    let _ = writeln!(
+        output,
+        "# HELP zaino_sync_progress Fraction of the validator's estimated chain height synced by the indexer."
+    );
+    let _ = writeln!(output, "# TYPE zaino_sync_progress gauge");
+    let _ = writeln!(
+        output,
+        "zaino_sync_progress {}",
+        f64::from_bits(registry.sync_progress.load(Ordering::Relaxed))
+    );
+    output
+}
+
+/// Updates the mempool size and best block height gauges from the node.
+///
+/// Failures are logged and leave the gauges at their last value.
+async fn refresh_gauges(connector: &JsonRpcConnector) {
+    match connector.get_blockchain_info().await {
+        Ok(blockchain_info) => set_best_block_height(blockchain_info.blocks.0 as u64),
+        Err(e) => eprintln!("Metrics: failed to fetch blockchain info: {}.", e),
+    }
+    match connector.get_raw_mempool().await {
+        Ok(mempool) => set_mempool_size(mempool.transactions.len() as u64),
+        Err(e) => eprintln!("Metrics: failed to fetch mempool: {}.", e),
+    }
+}
+
+/// Enables metrics and spawns an HTTP server exporting them at `listen_addr`.
+///
+/// Gauges are refreshed from the node through `zebrad_client` on each scrape. Runs while `online` is true.
+pub fn spawn_metrics_server(
+    listen_addr: SocketAddr,
+    zebrad_client: JsonRpcConnector,
+    online: Arc<AtomicBool>,
+) -> Result<tokio::task::JoinHandle<()>, hyper::Error> {
+    enable();
+    let zebrad_client = Arc::new(zebrad_client);
+    let make_service = make_service_fn(move |_conn| {
+        let zebrad_client = zebrad_client.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |_request: Request<Body>| {
+                let zebrad_client = zebrad_client.clone();
+                async move {
+                    refresh_gauges(&zebrad_client).await;
+                    Ok::<_, Infallible>(
+                        Response::builder()
+                            .header("Content-Type", "text/plain; version=0.0.4")
+                            .body(Body::from(render()))
+                            .unwrap_or_default(),
+                    )
+                }
+            }))
+        }
+    });
+    let server = hyper::Server::try_bind(&listen_addr)?
+        .serve(make_service)
+        .with_graceful_shutdown(async move {
+            while online.load(Ordering::SeqCst) {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
+    println!("Metrics server listening at {}.", listen_addr);
+    Ok(spawn_named("metrics_server", async move {
+        if let Err(e) = server.await {
+            eprintln!("Metrics server error: {}.", e);
+        }
+    }))
+}
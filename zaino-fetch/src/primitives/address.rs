@@ -1,10 +1,7 @@
 //! Hold primitives relating to zcash addresses.
 
-use crate::primitives::chain::NetworkKind;
-use std::fmt;
-
-/// *** THE FOLLOWING CODE IS CURRENTLY UNUSED BY ZINGO-PROXY AND UNTESTED! ***
-/// ***                           TEST BEFORE USE                           ***
+use crate::primitives::{chain::NetworkKind, error::AddressError};
+use std::{fmt, str::FromStr};
 
 /// Transparent Zcash Addresses
 ///
@@ -17,8 +14,6 @@ use std::fmt;
 /// to a Bitcoin address just by removing the "t".)
 ///
 /// <https://zips.z.cash/protocol/protocol.pdf#transparentaddrencoding>
-///
-/// *** UNTESTED - TEST BEFORE USE ***
 #[derive(Clone, Eq, PartialEq, Hash, serde::Deserialize, serde::Serialize)]
 pub enum TransparentAddress {
     /// P2SH (Pay to Script Hash) addresses
@@ -61,3 +56,150 @@ impl fmt::Debug for TransparentAddress {
         }
     }
 }
+
+impl TransparentAddress {
+    /// Returns the address paid to by a standard P2PKH or P2SH output script, None for any other script.
+    pub fn from_script_pubkey(script_pubkey: &[u8], network_kind: NetworkKind) -> Option<Self> {
+        let mut hash = [0u8; 20];
+        match script_pubkey {
+            // OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG
+            [0x76, 0xa9, 0x14, pub_key_hash @ .., 0x88, 0xac] if pub_key_hash.len() == 20 => {
+                hash.copy_from_slice(pub_key_hash);
+                Some(TransparentAddress::PayToPublicKeyHash {
+                    network_kind,
+                    pub_key_hash: hash,
+                })
+            }
+            // OP_HASH160 <20 bytes> OP_EQUAL
+            [0xa9, 0x14, script_hash @ .., 0x87] if script_hash.len() == 20 => {
+                hash.copy_from_slice(script_hash);
+                Some(TransparentAddress::PayToScriptHash {
+                    network_kind,
+                    script_hash: hash,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a Base58Check encoded address, rejecting addresses that do not belong to `network_kind`.
+    ///
+    /// Testnet and Regtest addresses share the same prefixes, so either is accepted for both and the returned
+    /// address belongs to `network_kind`.
+    pub fn parse_for_network(s: &str, network_kind: NetworkKind) -> Result<Self, AddressError> {
+        let address = TransparentAddress::from_str(s)?;
+        let found = address.network();
+        let same_prefixes = matches!(
+            (found, network_kind),
+            (NetworkKind::Mainnet, NetworkKind::Mainnet)
+                | (
+                    NetworkKind::Testnet | NetworkKind::Regtest,
+                    NetworkKind::Testnet | NetworkKind::Regtest
+                )
+        );
+        if !same_prefixes {
+            return Err(AddressError::WrongNetwork {
+                expected: network_kind,
+                found,
+            });
+        }
+        Ok(match address {
+            TransparentAddress::PayToScriptHash { script_hash, .. } => {
+                TransparentAddress::PayToScriptHash {
+                    network_kind,
+                    script_hash,
+                }
+            }
+            TransparentAddress::PayToPublicKeyHash { pub_key_hash, .. } => {
+                TransparentAddress::PayToPublicKeyHash {
+                    network_kind,
+                    pub_key_hash,
+                }
+            }
+        })
+    }
+
+    /// Returns the network the address belongs to.
+    pub fn network(&self) -> NetworkKind {
+        match self {
+            TransparentAddress::PayToScriptHash { network_kind, .. }
+            | TransparentAddress::PayToPublicKeyHash { network_kind, .. } => *network_kind,
+        }
+    }
+
+    /// Returns the two byte Base58Check version prefix of the address.
+    ///
+    /// Testnet and Regtest share the same prefixes.
+    fn version_prefix(&self) -> [u8; 2] {
+        match self {
+            TransparentAddress::PayToScriptHash { network_kind, .. } => match network_kind {
+                NetworkKind::Mainnet => [0x1C, 0xBD],
+                NetworkKind::Testnet | NetworkKind::Regtest => [0x1C, 0xBA],
+            },
+            TransparentAddress::PayToPublicKeyHash { network_kind, .. } => match network_kind {
+                NetworkKind::Mainnet => [0x1C, 0xB8],
+                NetworkKind::Testnet | NetworkKind::Regtest => [0x1D, 0x25],
+            },
+        }
+    }
+
+    /// Returns the 20 byte script or public key hash of the address.
+    fn hash_bytes(&self) -> &[u8; 20] {
+        match self {
+            TransparentAddress::PayToScriptHash { script_hash, .. } => script_hash,
+            TransparentAddress::PayToPublicKeyHash { pub_key_hash, .. } => pub_key_hash,
+        }
+    }
+}
+
+impl fmt::Display for TransparentAddress {
+    /// Formats the address in its Base58Check encoding, as accepted by the node's address RPCs.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut payload = Vec::with_capacity(22);
+        payload.extend_from_slice(&self.version_prefix());
+        payload.extend_from_slice(self.hash_bytes());
+        f.write_str(&bs58::encode(payload).with_check().into_string())
+    }
+}
+
+impl FromStr for TransparentAddress {
+    type Err = AddressError;
+
+    /// Parses a Base58Check encoded address.
+    ///
+    /// Testnet and Regtest addresses share the same prefixes and are parsed as Testnet, use
+    /// [`TransparentAddress::parse_for_network`] to parse Regtest addresses.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let payload = bs58::decode(s)
+            .with_check(None)
+            .into_vec()
+            .map_err(|e| match e {
+                bs58::decode::Error::InvalidChecksum { .. } => AddressError::InvalidChecksum,
+                e => AddressError::InvalidBase58(e.to_string()),
+            })?;
+        if payload.len() != 22 {
+            return Err(AddressError::InvalidLength(payload.len()));
+        }
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(&payload[2..]);
+        match [payload[0], payload[1]] {
+            [0x1C, 0xBD] => Ok(TransparentAddress::PayToScriptHash {
+                network_kind: NetworkKind::Mainnet,
+                script_hash: hash,
+            }),
+            [0x1C, 0xBA] => Ok(TransparentAddress::PayToScriptHash {
+                network_kind: NetworkKind::Testnet,
+                script_hash: hash,
+            }),
+            [0x1C, 0xB8] => Ok(TransparentAddress::PayToPublicKeyHash {
+                network_kind: NetworkKind::Mainnet,
+                pub_key_hash: hash,
+            }),
+            [0x1D, 0x25] => Ok(TransparentAddress::PayToPublicKeyHash {
+                network_kind: NetworkKind::Testnet,
+                pub_key_hash: hash,
+            }),
+            prefix => Err(AddressError::UnknownPrefix(hex::encode(prefix))),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Hold primitives relating to zcash blocks.
 
-use crate::primitives::error::SerializationError;
+use crate::primitives::{error::SerializationError, height::ChainHeight};
 use hex::{FromHex, ToHex};
 use std::fmt;
 
@@ -106,6 +106,7 @@ impl BlockHash {
     }
 }
 
+/// Formats the hash as big-endian (display) hex, matching the RPC convention.
 impl fmt::Display for BlockHash {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(&self.encode_hex::<String>())
@@ -156,9 +157,131 @@ impl From<[u8; 32]> for BlockHash {
     }
 }
 
+/// Parses a hash in big-endian (display) hex, as returned by [`BlockHash`]'s `Display` implementation.
 impl std::str::FromStr for BlockHash {
     type Err = SerializationError;
+
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self::from_hex(s)?)
+        if s.len() != 64 {
+            return Err(SerializationError::InvalidHashLength(s.len()));
+        }
+        Self::from_hex(s).map_err(SerializationError::InvalidHashHex)
+    }
+}
+
+impl TryFrom<&str> for BlockHash {
+    type Error = SerializationError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Root of the transaction Merkle tree of a block, as held in the block header.
+///
+/// Like [`BlockHash`], the root is displayed and returned by the node in big-endian (display) byte order
+/// and held in internal byte order.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MerkleRoot(pub [u8; 32]);
+
+impl MerkleRoot {
+    /// Return the root bytes in big-endian byte-order suitable for printing out byte by byte.
+    pub fn bytes_in_display_order(&self) -> [u8; 32] {
+        let mut reversed_bytes = self.0;
+        reversed_bytes.reverse();
+        reversed_bytes
+    }
+}
+
+/// Formats the root as big-endian (display) hex, matching the RPC convention.
+impl fmt::Display for MerkleRoot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.bytes_in_display_order().encode_hex::<String>())
+    }
+}
+
+impl fmt::Debug for MerkleRoot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("MerkleRoot")
+            .field(&self.bytes_in_display_order().encode_hex::<String>())
+            .finish()
+    }
+}
+
+impl FromHex for MerkleRoot {
+    type Error = <[u8; 32] as FromHex>::Error;
+
+    fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+        let mut root = <[u8; 32]>::from_hex(hex)?;
+        root.reverse();
+
+        Ok(MerkleRoot(root))
+    }
+}
+
+impl From<[u8; 32]> for MerkleRoot {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl serde::Serialize for MerkleRoot {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MerkleRoot {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let hex_string = String::deserialize(deserializer)?;
+        MerkleRoot::from_hex(hex_string).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Identifies a block by either hash or height, as accepted by the node's block RPCs.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum BlockIdentifier {
+    /// Block hash, sent as big-endian hex.
+    Hash(BlockHash),
+    /// Block height, sent as a decimal string.
+    Height(ChainHeight),
+}
+
+impl fmt::Display for BlockIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockIdentifier::Hash(hash) => write!(f, "{}", hash),
+            BlockIdentifier::Height(height) => write!(f, "{}", height.0),
+        }
+    }
+}
+
+impl serde::Serialize for BlockIdentifier {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl From<BlockHash> for BlockIdentifier {
+    fn from(hash: BlockHash) -> Self {
+        BlockIdentifier::Hash(hash)
+    }
+}
+
+impl From<ChainHeight> for BlockIdentifier {
+    fn from(height: ChainHeight) -> Self {
+        BlockIdentifier::Height(height)
+    }
+}
+
+impl From<u32> for BlockIdentifier {
+    fn from(height: u32) -> Self {
+        BlockIdentifier::Height(ChainHeight(height))
     }
 }
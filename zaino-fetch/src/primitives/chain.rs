@@ -1,6 +1,6 @@
 //! Hold primitives relating to chain and network.
 
-use crate::primitives::height::ChainHeight;
+use crate::primitives::{error::NetworkError, height::ChainHeight};
 use hex::ToHex;
 use std::fmt;
 
@@ -22,6 +22,61 @@ pub enum NetworkKind {
     Regtest,
 }
 
+impl NetworkKind {
+    /// Returns the network named by a `getblockchaininfo` chain name, "main", "test" or "regtest".
+    pub fn from_chain_name(chain_name: &str) -> Result<Self, NetworkError> {
+        match chain_name {
+            "main" => Ok(NetworkKind::Mainnet),
+            "test" => Ok(NetworkKind::Testnet),
+            "regtest" => Ok(NetworkKind::Regtest),
+            _ => Err(NetworkError::UnknownChain(chain_name.to_string())),
+        }
+    }
+
+    /// Returns the network's BIP70 chain name, as reported by `getblockchaininfo`.
+    pub fn chain_name(&self) -> &'static str {
+        match self {
+            NetworkKind::Mainnet => "main",
+            NetworkKind::Testnet => "test",
+            NetworkKind::Regtest => "regtest",
+        }
+    }
+
+    /// Returns the default zcashd / zebrad JsonRPC port for the network.
+    pub fn default_rpc_port(&self) -> u16 {
+        match self {
+            NetworkKind::Mainnet => 8232,
+            NetworkKind::Testnet | NetworkKind::Regtest => 18232,
+        }
+    }
+
+    /// Returns the expected activation height of `upgrade`, None for upgrades unknown to this version of Zaino.
+    ///
+    /// Regtest heights are those set by the `nuparams` of Zaino's regtest validators, which activate every upgrade
+    /// at height 1.
+    pub fn activation_height(&self, upgrade: NetworkUpgrade) -> Option<ChainHeight> {
+        let height = match (self, upgrade) {
+            (_, NetworkUpgrade::Genesis) => 0,
+            (_, NetworkUpgrade::BeforeOverwinter) => 1,
+            (_, NetworkUpgrade::Unknown) => return None,
+            (NetworkKind::Regtest, _) => 1,
+            (NetworkKind::Mainnet, NetworkUpgrade::Overwinter) => 347_500,
+            (NetworkKind::Mainnet, NetworkUpgrade::Sapling) => 419_200,
+            (NetworkKind::Mainnet, NetworkUpgrade::Blossom) => 653_600,
+            (NetworkKind::Mainnet, NetworkUpgrade::Heartwood) => 903_000,
+            (NetworkKind::Mainnet, NetworkUpgrade::Canopy) => 1_046_400,
+            (NetworkKind::Mainnet, NetworkUpgrade::Nu5) => 1_687_104,
+            (NetworkKind::Testnet, NetworkUpgrade::Overwinter) => 207_500,
+            (NetworkKind::Testnet, NetworkUpgrade::Sapling) => 280_000,
+            (NetworkKind::Testnet, NetworkUpgrade::Blossom) => 584_000,
+            (NetworkKind::Testnet, NetworkUpgrade::Heartwood) => 903_800,
+            (NetworkKind::Testnet, NetworkUpgrade::Canopy) => 1_028_500,
+            (NetworkKind::Testnet, NetworkUpgrade::Nu5) => 1_842_420,
+        };
+        Some(ChainHeight(height))
+    }
+}
+
 /// The Consensus Branch Id, used to bind transactions and blocks to a
 /// particular network upgrade.
 #[derive(
@@ -136,6 +191,39 @@ pub enum NetworkUpgrade {
     /// IDs, and other changes. There is no special code name for Nu5.
     #[serde(rename = "NU5")]
     Nu5,
+    /// A network upgrade newer than those known to this version of Zaino.
+    ///
+    /// Lets responses from newer nodes listing upcoming upgrades still be parsed.
+    #[serde(other)]
+    Unknown,
+}
+
+impl NetworkUpgrade {
+    /// Network upgrades activated by a consensus branch id, in activation order.
+    pub const ACTIVATED_UPGRADES: [NetworkUpgrade; 6] = [
+        NetworkUpgrade::Overwinter,
+        NetworkUpgrade::Sapling,
+        NetworkUpgrade::Blossom,
+        NetworkUpgrade::Heartwood,
+        NetworkUpgrade::Canopy,
+        NetworkUpgrade::Nu5,
+    ];
+
+    /// Returns the consensus branch id of the upgrade, None for upgrades without a branch id.
+    pub fn branch_id(&self) -> Option<ConsensusBranchId> {
+        let branch_id = match self {
+            NetworkUpgrade::Genesis
+            | NetworkUpgrade::BeforeOverwinter
+            | NetworkUpgrade::Unknown => return None,
+            NetworkUpgrade::Overwinter => 0x5ba8_1b19,
+            NetworkUpgrade::Sapling => 0x76b8_09bb,
+            NetworkUpgrade::Blossom => 0x2bb4_0e60,
+            NetworkUpgrade::Heartwood => 0xf5b9_230b,
+            NetworkUpgrade::Canopy => 0xe9ff_75a6,
+            NetworkUpgrade::Nu5 => 0xc2d6_d0b4,
+        };
+        Some(ConsensusBranchId(branch_id))
+    }
 }
 
 impl fmt::Display for NetworkUpgrade {
@@ -1,5 +1,7 @@
 //! Hold error types for Zingo-Indexer primitives and related functionality.
 
+use crate::primitives::chain::NetworkKind;
+
 /// A serialization error.
 #[derive(thiserror::Error, Debug)]
 pub enum SerializationError {
@@ -29,6 +31,51 @@ pub enum SerializationError {
     /// A string was not valid hexadecimal.
     #[error("string was not hex: {0}")]
     FromHexError(#[from] hex::FromHexError),
+
+    /// A hex encoded hash was not 64 characters long.
+    #[error("hash was the wrong length: expected 64 hex characters, found {0}")]
+    InvalidHashLength(usize),
+
+    /// A hex encoded hash held characters that are not hexadecimal.
+    #[error("hash was not hex: {0}")]
+    InvalidHashHex(hex::FromHexError),
+}
+
+/// An error parsing a Base58Check encoded transparent address.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    /// The address was not valid Base58.
+    #[error("address was not base58: {0}")]
+    InvalidBase58(String),
+
+    /// The address checksum did not match its payload.
+    #[error("address checksum was invalid")]
+    InvalidChecksum,
+
+    /// The decoded address was not 22 bytes long.
+    #[error("address was the wrong length: expected 22 bytes, found {0}")]
+    InvalidLength(usize),
+
+    /// The address version prefix is not a known transparent address prefix.
+    #[error("address prefix was unknown: {0}")]
+    UnknownPrefix(String),
+
+    /// The address belongs to a different network than expected.
+    #[error("address is for {found:?}, expected {expected:?}")]
+    WrongNetwork {
+        /// Network the address was expected to belong to.
+        expected: NetworkKind,
+        /// Network of the address prefix.
+        found: NetworkKind,
+    },
+}
+
+/// An error identifying the network served by a validator.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    /// The `getblockchaininfo` chain name is not "main", "test" or "regtest".
+    #[error("chain name was unknown: {0}")]
+    UnknownChain(String),
 }
 
 /// Error type alias to make working with generic errors easier.
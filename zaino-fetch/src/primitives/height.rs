@@ -207,6 +207,24 @@ impl ChainHeight {
         (self - 1).ok_or(ChainHeightError::Underflow)
     }
 
+    /// Adds `v` to this height, returning `None` if the result would be above [`ChainHeight::MAX`].
+    pub fn checked_add(self, v: u32) -> Option<ChainHeight> {
+        self.0
+            .checked_add(v)
+            .filter(|height| *height <= Self::MAX.0)
+            .map(ChainHeight)
+    }
+
+    /// Subtracts `v` from this height, returning `None` if the result would be below genesis.
+    pub fn checked_sub(self, v: u32) -> Option<ChainHeight> {
+        self.0.checked_sub(v).map(ChainHeight)
+    }
+
+    /// Subtracts `v` from this height, returning [`ChainHeight::MIN`] if the result would be below genesis.
+    pub fn saturating_sub(self, v: u32) -> ChainHeight {
+        ChainHeight(self.0.saturating_sub(v))
+    }
+
     /// Returns `true` if the [`Height`] is at its minimum.
     pub fn is_min(self) -> bool {
         self == Self::MIN
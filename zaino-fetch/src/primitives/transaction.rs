@@ -233,23 +233,20 @@ impl std::str::FromStr for TransactionHash {
     }
 }
 
-/// *** THE FOLLOWING CODE IS CURRENTLY UNUSED BY ZINGO-PROXY AND UNTESTED! ***
-/// ***                           TEST BEFORE USE                           ***
-
 /// Wrapper type that can hold Sapling or Orchard subtree roots with hex encoding.
 ///
-/// *** UNTESTED - TEST BEFORE USE ***
+/// Serialized as `{"root": <hex>, "end_height": <height>}`, matching `z_getsubtreesbyindex`.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SubtreeRpcData {
-    /// Merkle root of the 2^16-leaf subtree.
-    pub root: String,
+    /// Merkle root of the 2^16-leaf subtree, in the byte order served by the node.
+    pub root: [u8; 32],
     /// Height of the block containing the note that completed this subtree.
     pub height: ChainHeight,
 }
 
 impl SubtreeRpcData {
     /// Returns new instance of SubtreeRpcData
-    pub fn new(root: String, height: ChainHeight) -> Self {
+    pub fn new(root: [u8; 32], height: ChainHeight) -> Self {
         Self { root, height }
     }
 }
@@ -260,8 +257,8 @@ impl serde::Serialize for SubtreeRpcData {
         S: serde::Serializer,
     {
         let mut state = serializer.serialize_struct("SubtreeRpcData", 2)?;
-        state.serialize_field("root", &self.root)?;
-        state.serialize_field("height", &self.height)?;
+        state.serialize_field("root", &hex::encode(self.root))?;
+        state.serialize_field("end_height", &self.height)?;
         state.end()
     }
 }
@@ -273,14 +270,16 @@ impl<'de> serde::Deserialize<'de> for SubtreeRpcData {
     {
         #[derive(serde::Deserialize)]
         struct Inner {
-            root: String,
-            height: ChainHeight,
+            #[serde(with = "hex")]
+            root: [u8; 32],
+            #[serde(alias = "height")]
+            end_height: ChainHeight,
         }
 
         let inner = Inner::deserialize(deserializer)?;
         Ok(SubtreeRpcData {
             root: inner.root,
-            height: inner.height,
+            height: inner.end_height,
         })
     }
 }
@@ -288,20 +287,15 @@ impl<'de> serde::Deserialize<'de> for SubtreeRpcData {
 impl hex::FromHex for SubtreeRpcData {
     type Error = hex::FromHexError;
 
+    /// Parses a hex-encoded 32 byte root followed by the hex-encoded big-endian u32 end height.
     fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
-        let hex_str = std::str::from_utf8(hex.as_ref())
-            .map_err(|_| hex::FromHexError::InvalidHexCharacter { c: '�', index: 0 })?;
-
-        if hex_str.len() < 8 {
-            return Err(hex::FromHexError::OddLength);
+        let hex = hex.as_ref();
+        if hex.len() != 72 {
+            return Err(hex::FromHexError::InvalidStringLength);
         }
-
-        let root_end_index = hex_str.len() - 8;
-        let (root_hex, height_hex) = hex_str.split_at(root_end_index);
-
-        let root = root_hex.to_string();
-        let height = u32::from_str_radix(height_hex, 16)
-            .map_err(|_| hex::FromHexError::InvalidHexCharacter { c: '�', index: 0 })?;
+        let (root_hex, height_hex) = hex.split_at(64);
+        let root = <[u8; 32] as hex::FromHex>::from_hex(root_hex)?;
+        let height = u32::from_be_bytes(<[u8; 4] as hex::FromHex>::from_hex(height_hex)?);
 
         Ok(SubtreeRpcData {
             root,
@@ -310,6 +304,9 @@ impl hex::FromHex for SubtreeRpcData {
     }
 }
 
+/// *** THE FOLLOWING CODE IS CURRENTLY UNUSED BY ZINGO-PROXY AND UNTESTED! ***
+/// ***                           TEST BEFORE USE                           ***
+
 /// Zingo-Indexer encoding of a Bitcoin script.
 ///
 /// *** UNTESTED - TEST BEFORE USE ***
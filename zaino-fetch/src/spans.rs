@@ -0,0 +1,88 @@
+//! Tracing spans correlating incoming gRPC requests with the JsonRPC calls they trigger.
+//!
+//! Each gRPC request runs in a `grpc` span carrying a request id, JsonRPC calls made while serving it are recorded in
+//! child `jsonrpc` spans. Per-call detail is logged at debug level and failures at warn level.
+//! Spans are inert when no tracing subscriber is installed.
+
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use tracing::{field::Empty, Instrument};
+
+/// Id given to the next gRPC request.
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Returns a new, process-unique, request id.
+pub fn next_request_id() -> u64 {
+    NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Runs a gRPC request handler within a new `grpc` span for `method`, tagged with a new request id.
+///
+/// JsonRPC calls, and tasks spawned with [`crate::task::spawn_named`], made while `handler` runs are recorded as
+/// children of the span. Failed requests are logged at warn level.
+pub async fn instrument_grpc<T, F>(method: &'static str, handler: F) -> Result<T, tonic::Status>
+where
+    F: Future<Output = Result<T, tonic::Status>>,
+{
+    let span = tracing::debug_span!(
+        "grpc",
+        request_id = next_request_id(),
+        method,
+        height = Empty,
+        hash = Empty
+    );
+    async move {
+        tracing::debug!("gRPC request received");
+        let result = handler.await;
+        match &result {
+            Ok(_) => tracing::debug!("gRPC request served"),
+            Err(status) => tracing::warn!(
+                code = ?status.code(),
+                message = status.message(),
+                "gRPC request failed"
+            ),
+        }
+        result
+    }
+    .instrument(span)
+    .await
+}
+
+/// Records the block height a request resolved to on the current `grpc` span.
+pub fn record_height(height: u64) {
+    tracing::Span::current().record("height", height);
+}
+
+/// Records the block hash a request resolved to on the current `grpc` span, hex encoded.
+pub fn record_hash(hash: &str) {
+    tracing::Span::current().record("hash", hash);
+}
+
+/// Runs a JsonRPC call within a new `jsonrpc` span for `method`, a child of the current span.
+///
+/// Failed calls are logged at warn level.
+pub(crate) async fn instrument_jsonrpc<T, E, F>(method: &str, call: F) -> Result<T, E>
+where
+    E: std::fmt::Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let span = tracing::debug_span!("jsonrpc", method, endpoint = Empty);
+    async move {
+        let result = call.await;
+        match &result {
+            Ok(_) => tracing::debug!("JsonRPC call completed"),
+            Err(e) => tracing::warn!(error = %e, "JsonRPC call failed"),
+        }
+        result
+    }
+    .instrument(span)
+    .await
+}
+
+/// Records the endpoint a JsonRPC call was sent to on the current `jsonrpc` span.
+pub(crate) fn record_endpoint(endpoint: &http::Uri) {
+    tracing::Span::current().record("endpoint", tracing::field::display(endpoint));
+}
@@ -0,0 +1,31 @@
+//! Zingo-Indexer task spawning.
+
+use std::future::Future;
+
+use tracing::Instrument;
+
+/// Spawns a new task, registering it under `name` for inspection in tokio-console.
+///
+/// Task names are only recorded when built with the `tokio-console` feature and `RUSTFLAGS="--cfg tokio_unstable"`,
+/// otherwise this is equivalent to [`tokio::task::spawn`].
+///
+/// The task runs within the caller's current tracing span, so work spawned while serving a request is attributed to it.
+pub fn spawn_named<F>(name: &str, future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let future = future.in_current_span();
+    #[cfg(all(feature = "tokio-console", tokio_unstable))]
+    {
+        tokio::task::Builder::new()
+            .name(name)
+            .spawn(future)
+            .expect("Failed to spawn task")
+    }
+    #[cfg(not(all(feature = "tokio-console", tokio_unstable)))]
+    {
+        let _ = name;
+        tokio::task::spawn(future)
+    }
+}
@@ -4,10 +4,13 @@ use nym_sdk::mixnet::{
     MixnetClient, MixnetClientBuilder, MixnetMessageSender, Recipient, ReconstructedMessage,
     StoragePaths,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::error::NymError;
 
+/// Name of the file, within a Nym client's storage directory, holding the client's nym address.
+pub const NYM_ADDRESS_FILE: &str = "nym_address";
+
 /// Wrapper struct for a Nym client.
 pub struct NymClient {
     /// Nym SDK Client.
@@ -18,8 +21,14 @@ pub struct NymClient {
 
 impl NymClient {
     /// Spawns a nym client and connects to the mixnet.
+    ///
+    /// The client's keys and gateway registration are stored in `str_path`, which is created if missing, and reused
+    /// by later spawns so the client's nym address is stable across restarts. A new identity is generated and stored
+    /// if none is held. The client's nym address is written to [`NYM_ADDRESS_FILE`] in `str_path`.
     pub async fn spawn(str_path: &str) -> Result<Self, NymError> {
         //nym_bin_common::logging::setup_logging();
+        std::fs::create_dir_all(str_path)?;
+        let stored_addr = stored_nym_address(str_path)?;
         let client = MixnetClientBuilder::new_with_default_storage(StoragePaths::new_from_dir(
             PathBuf::from(str_path),
         )?)
@@ -28,6 +37,13 @@ impl NymClient {
         .connect_to_mixnet()
         .await?;
         let addr = client.nym_address().to_string();
+        if let Some(stored_addr) = stored_addr.filter(|stored_addr| *stored_addr != addr) {
+            eprintln!(
+                "Nym address changed from {} to {}, the stored identity was not reused.",
+                stored_addr, addr
+            );
+        }
+        store_nym_address(str_path, &addr)?;
         Ok(Self { client, addr })
     }
 
@@ -66,3 +82,18 @@ impl NymClient {
         self.client.disconnect().await;
     }
 }
+
+/// Returns the nym address stored in `str_path` by a previous [`NymClient::spawn`], None if no address is stored.
+pub fn stored_nym_address(str_path: &str) -> Result<Option<String>, NymError> {
+    match std::fs::read_to_string(Path::new(str_path).join(NYM_ADDRESS_FILE)) {
+        Ok(addr) => Ok(Some(addr.trim().to_string()).filter(|addr| !addr.is_empty())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Writes `addr` to [`NYM_ADDRESS_FILE`] in `str_path`.
+pub fn store_nym_address(str_path: &str, addr: &str) -> Result<(), NymError> {
+    std::fs::write(Path::new(str_path).join(NYM_ADDRESS_FILE), addr)?;
+    Ok(())
+}
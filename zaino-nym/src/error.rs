@@ -25,6 +25,12 @@ pub enum NymError {
     /// Custom error for receiveing not AnonSenderTag (surb) from the Nym network.
     #[error("No AnonSenderTag received from the mixnet")]
     EmptyRecipientTagError,
+    /// Errors reading or writing the Nym client's storage directory.
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Request method is not in the Nym method allowlist.
+    #[error("Method not allowed over the mixnet: {0}")]
+    MethodNotAllowed(String),
 }
 
 impl From<NymError> for tonic::Status {
@@ -44,6 +50,10 @@ impl From<NymError> for tonic::Status {
             NymError::EmptyRecipientTagError => {
                 tonic::Status::internal(format!("No AnonSenderTag received from nym mixnet"))
             }
+            NymError::Io(e) => tonic::Status::internal(format!("IO error: {}", e)),
+            NymError::MethodNotAllowed(method) => {
+                tonic::Status::permission_denied(format!("Method not allowed over nym: {}", method))
+            }
         }
     }
 }
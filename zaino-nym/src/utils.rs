@@ -1,40 +1,129 @@
 //! Utility functions for Nym-Mixnet
 
 use crate::error::NymError;
-use std::io::Cursor;
+use std::{collections::HashSet, io::Cursor};
 use zaino_fetch::chain::{
     error::ParseError,
-    utils::{read_bytes, CompactSize},
+    utils::{read_bytes, read_length_prefix, CompactSize},
 };
 
+/// Nym request methods reachable by default, restricted to methods that only read chain and mempool data.
+pub const DEFAULT_NYM_ALLOWED_METHODS: [&str; 18] = [
+    "GetLightdInfo",
+    "get_latest_block",
+    "get_block",
+    "get_block_nullifiers",
+    "get_block_range",
+    "get_block_range_nullifiers",
+    "get_transaction",
+    "get_taddress_txids",
+    "get_taddress_balance",
+    "get_taddress_balance_stream",
+    "get_mempool_tx",
+    "get_mempool_stream",
+    "get_tree_state",
+    "get_latest_tree_state",
+    "get_subtree_roots",
+    "get_address_utxos",
+    "get_address_utxos_stream",
+    "ping",
+];
+
+/// Nym request methods that act on the node, these must be explicitly added to the allowlist.
+pub const SENSITIVE_NYM_METHODS: [&str; 2] = ["SendTransaction", "send_transaction"];
+
+/// Longest method name accepted in a Nym request by default, in bytes.
+pub const DEFAULT_MAX_NYM_METHOD_LEN: usize = 64;
+
+/// Largest request body accepted in a Nym request by default, in bytes, enough for a transaction filling a block.
+pub const DEFAULT_MAX_NYM_BODY_LEN: usize = 2_000_000;
+
+/// Bounds on the length prefixes of a Nym request.
+///
+/// The mixnet delivers untrusted input, lengths above these bounds are rejected with [`ParseError::InvalidData`]
+/// before the data they describe is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NymRequestLimits {
+    /// Longest method name accepted, in bytes.
+    pub max_method_len: usize,
+    /// Largest request body accepted, in bytes.
+    pub max_body_len: usize,
+}
+
+impl Default for NymRequestLimits {
+    fn default() -> Self {
+        NymRequestLimits {
+            max_method_len: DEFAULT_MAX_NYM_METHOD_LEN,
+            max_body_len: DEFAULT_MAX_NYM_BODY_LEN,
+        }
+    }
+}
+
+/// Returns the default Nym request method allowlist, see [`DEFAULT_NYM_ALLOWED_METHODS`].
+pub fn default_nym_allowed_methods() -> HashSet<String> {
+    DEFAULT_NYM_ALLOWED_METHODS
+        .iter()
+        .map(|method| method.to_string())
+        .collect()
+}
+
 /// Reads a RPC method name from a Vec<u8> and returns this as a string along with the remaining data in the input.
-fn read_nym_method(data: &[u8]) -> Result<(String, &[u8]), NymError> {
+///
+/// Returns an InvalidData error if the method name is longer than `max_len`.
+fn read_nym_method(data: &[u8], max_len: usize) -> Result<(String, &[u8]), NymError> {
     let mut cursor = Cursor::new(data);
-    let method_len = CompactSize::read(&mut cursor).map_err(ParseError::Io)? as usize;
+    let method_len = read_length_prefix(&mut cursor, max_len, "Nym request method too long")?;
     let method = String::from_utf8(read_bytes(&mut cursor, method_len, "failed to read")?)
         .map_err(ParseError::FromUtf8Error)?;
     Ok((method, &data[cursor.position() as usize..]))
 }
 
 /// Check the body of the request is the correct length.
-fn check_nym_body(data: &[u8]) -> Result<&[u8], NymError> {
+///
+/// Returns an InvalidData error if the body is longer than `max_len`, and a BodyLengthMismatch error if the body is
+/// truncated or padded.
+fn check_nym_body(data: &[u8], max_len: usize) -> Result<&[u8], NymError> {
     let mut cursor = Cursor::new(data);
-    let body_len = CompactSize::read(&mut cursor).map_err(ParseError::Io)? as usize;
-    if &body_len != &data[cursor.position() as usize..].len() {
-        return Err(NymError::ParseError(ParseError::InvalidData(
-            "Incorrect request body size read.".to_string(),
-        )));
+    let expected = read_length_prefix(&mut cursor, max_len, "Nym request body too long")?;
+    let body = data.get(cursor.position() as usize..).unwrap_or_default();
+    if expected != body.len() {
+        return Err(NymError::ParseError(ParseError::BodyLengthMismatch {
+            expected,
+            actual: body.len(),
+        }));
     };
-    Ok(&data[cursor.position() as usize..])
+    Ok(body)
 }
 
-/// Extracts metadata from a NymRequest.
+/// Extracts metadata from a NymRequest, with the default [`NymRequestLimits`].
+///
+/// Returns a MethodNotAllowed error, before the request body is read, if the method is not in `allowed_methods`.
+///
+/// Returns [ID, Method, RequestData].
+pub fn read_nym_request_data<'a>(
+    data: &'a [u8],
+    allowed_methods: &HashSet<String>,
+) -> Result<(u64, String, &'a [u8]), NymError> {
+    read_nym_request_data_with_limits(data, allowed_methods, &NymRequestLimits::default())
+}
+
+/// Extracts metadata from a NymRequest, rejecting method names and bodies longer than `limits`.
+///
+/// Returns a MethodNotAllowed error, before the request body is read, if the method is not in `allowed_methods`.
 ///
 /// Returns [ID, Method, RequestData].
-pub fn read_nym_request_data(data: &[u8]) -> Result<(u64, String, &[u8]), NymError> {
+pub fn read_nym_request_data_with_limits<'a>(
+    data: &'a [u8],
+    allowed_methods: &HashSet<String>,
+    limits: &NymRequestLimits,
+) -> Result<(u64, String, &'a [u8]), NymError> {
     let mut cursor = Cursor::new(data);
     let id = CompactSize::read(&mut cursor).map_err(ParseError::Io)?;
-    let (method, data) = read_nym_method(&data[cursor.position() as usize..])?;
-    let body = check_nym_body(data)?;
+    let (method, data) =
+        read_nym_method(&data[cursor.position() as usize..], limits.max_method_len)?;
+    if !allowed_methods.contains(&method) {
+        return Err(NymError::MethodNotAllowed(method));
+    }
+    let body = check_nym_body(data, limits.max_body_len)?;
     Ok((id, method, body))
 }
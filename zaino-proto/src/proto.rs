@@ -3,3 +3,6 @@
 pub mod compact_formats;
 pub mod proposal;
 pub mod service;
+
+/// Encoded `FileDescriptorSet` of the lightwallet service and compact formats, used to serve gRPC reflection.
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("proto/descriptor.bin");
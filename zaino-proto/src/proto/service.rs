@@ -123,15 +123,22 @@ pub struct TransparentAddressBlockFilter {
 /// Duration is currently used only for testing, so that the Ping rpc
 /// can simulate a delay, to create many simultaneous connections. Units
 /// are microseconds.
+/// The nonce is echoed back in the PingResponse, so clients can match
+/// responses to requests when measuring round-trip latency.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Duration {
     #[prost(int64, tag = "1")]
     pub interval_us: i64,
+    #[prost(uint64, tag = "2")]
+    pub nonce: u64,
 }
 /// PingResponse is used to indicate concurrency, how many Ping rpcs
 /// are executing upon entry and upon exit (after the delay).
 /// This rpc is used for testing only.
+/// Zingo-Indexer also echoes the request nonce and returns its wall-clock
+/// time in milliseconds since the unix epoch and its version, for latency
+/// and clock skew probes.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PingResponse {
@@ -139,6 +146,42 @@ pub struct PingResponse {
     pub entry: i64,
     #[prost(int64, tag = "2")]
     pub exit: i64,
+    #[prost(uint64, tag = "3")]
+    pub nonce: u64,
+    #[prost(int64, tag = "4")]
+    pub server_time_ms: i64,
+    #[prost(string, tag = "5")]
+    pub version: ::prost::alloc::string::String,
+}
+/// EstimateFeeArg is the number of blocks within which a transaction
+/// should be mined.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EstimateFeeArg {
+    #[prost(uint32, tag = "1")]
+    pub target_blocks: u32,
+}
+/// FeeRate is a recommended fee rate in zatoshis per 1000 bytes.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FeeRate {
+    #[prost(uint64, tag = "1")]
+    pub zats_per_kb: u64,
+}
+/// ServerCapabilities describes this Zingo-Indexer instance in one call: its
+/// version, the version of the node it serves, the optional features enabled
+/// and the methods it serves, so clients can avoid calling disabled methods.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ServerCapabilities {
+    #[prost(string, tag = "1")]
+    pub version: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub node_subversion: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "3")]
+    pub features: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "4")]
+    pub enabled_methods: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -729,6 +772,39 @@ pub mod compact_tx_streamer_client {
                 );
             self.inner.server_streaming(req, path, codec).await
         }
+        /// Return a stream of the Mempool transactions spending from or paying to any of the given
+        /// transparent addresses. This will keep the output stream open, streaming matching transactions
+        /// as they enter the mempool. If the address list is empty no transactions are returned.
+        pub async fn get_taddress_mempool_stream(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AddressList>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::RawTransaction>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetTaddressMempoolStream",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "cash.z.wallet.sdk.rpc.CompactTxStreamer",
+                        "GetTaddressMempoolStream",
+                    ),
+                );
+            self.inner.server_streaming(req, path, codec).await
+        }
         /// GetTreeState returns the note commitment tree state corresponding to the given block.
         /// See section 3.7 of the Zcash protocol specification. It returns several other useful
         /// values also (even though they can be obtained using GetBlock).
@@ -907,6 +983,62 @@ pub mod compact_tx_streamer_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Return the recommended fee rate to be mined within the given number of blocks
+        pub async fn estimate_fee(
+            &mut self,
+            request: impl tonic::IntoRequest<super::EstimateFeeArg>,
+        ) -> std::result::Result<tonic::Response<super::FeeRate>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/cash.z.wallet.sdk.rpc.CompactTxStreamer/EstimateFee",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "cash.z.wallet.sdk.rpc.CompactTxStreamer",
+                        "EstimateFee",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Return the version, enabled features and served methods of this Zingo-Indexer instance
+        pub async fn get_server_capabilities(
+            &mut self,
+            request: impl tonic::IntoRequest<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::ServerCapabilities>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetServerCapabilities",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "cash.z.wallet.sdk.rpc.CompactTxStreamer",
+                        "GetServerCapabilities",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         /// Testing-only, requires lightwalletd --ping-very-insecure (do not enable in production)
         pub async fn ping(
             &mut self,
@@ -1068,6 +1200,22 @@ pub mod compact_tx_streamer_server {
             tonic::Response<Self::GetMempoolStreamStream>,
             tonic::Status,
         >;
+        /// Server streaming response type for the GetTaddressMempoolStream method.
+        type GetTaddressMempoolStreamStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::RawTransaction, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        /// Return a stream of the Mempool transactions spending from or paying to any of the given
+        /// transparent addresses. This will keep the output stream open, streaming matching transactions
+        /// as they enter the mempool. If the address list is empty no transactions are returned.
+        async fn get_taddress_mempool_stream(
+            &self,
+            request: tonic::Request<super::AddressList>,
+        ) -> std::result::Result<
+            tonic::Response<Self::GetTaddressMempoolStreamStream>,
+            tonic::Status,
+        >;
         /// GetTreeState returns the note commitment tree state corresponding to the given block.
         /// See section 3.7 of the Zcash protocol specification. It returns several other useful
         /// values also (even though they can be obtained using GetBlock).
@@ -1120,6 +1268,16 @@ pub mod compact_tx_streamer_server {
             &self,
             request: tonic::Request<super::Empty>,
         ) -> std::result::Result<tonic::Response<super::LightdInfo>, tonic::Status>;
+        /// Return the recommended fee rate to be mined within the given number of blocks
+        async fn estimate_fee(
+            &self,
+            request: tonic::Request<super::EstimateFeeArg>,
+        ) -> std::result::Result<tonic::Response<super::FeeRate>, tonic::Status>;
+        /// Return the version, enabled features and served methods of this Zingo-Indexer instance
+        async fn get_server_capabilities(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::ServerCapabilities>, tonic::Status>;
         /// Testing-only, requires lightwalletd --ping-very-insecure (do not enable in production)
         async fn ping(
             &self,
@@ -1791,6 +1949,57 @@ pub mod compact_tx_streamer_server {
                     };
                     Box::pin(fut)
                 }
+                "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetTaddressMempoolStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTaddressMempoolStreamSvc<T: CompactTxStreamer>(pub Arc<T>);
+                    impl<
+                        T: CompactTxStreamer,
+                    > tonic::server::ServerStreamingService<super::AddressList>
+                    for GetTaddressMempoolStreamSvc<T> {
+                        type Response = super::RawTransaction;
+                        type ResponseStream = T::GetTaddressMempoolStreamStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AddressList>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CompactTxStreamer>::get_taddress_mempool_stream(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTaddressMempoolStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetTreeState" => {
                     #[allow(non_camel_case_types)]
                     struct GetTreeStateSvc<T: CompactTxStreamer>(pub Arc<T>);
@@ -2077,6 +2286,100 @@ pub mod compact_tx_streamer_server {
                     };
                     Box::pin(fut)
                 }
+                "/cash.z.wallet.sdk.rpc.CompactTxStreamer/EstimateFee" => {
+                    #[allow(non_camel_case_types)]
+                    struct EstimateFeeSvc<T: CompactTxStreamer>(pub Arc<T>);
+                    impl<
+                        T: CompactTxStreamer,
+                    > tonic::server::UnaryService<super::EstimateFeeArg>
+                    for EstimateFeeSvc<T> {
+                        type Response = super::FeeRate;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::EstimateFeeArg>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CompactTxStreamer>::estimate_fee(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = EstimateFeeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/cash.z.wallet.sdk.rpc.CompactTxStreamer/GetServerCapabilities" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetServerCapabilitiesSvc<T: CompactTxStreamer>(pub Arc<T>);
+                    impl<
+                        T: CompactTxStreamer,
+                    > tonic::server::UnaryService<super::Empty>
+                    for GetServerCapabilitiesSvc<T> {
+                        type Response = super::ServerCapabilities;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::Empty>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CompactTxStreamer>::get_server_capabilities(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetServerCapabilitiesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/cash.z.wallet.sdk.rpc.CompactTxStreamer/Ping" => {
                     #[allow(non_camel_case_types)]
                     struct PingSvc<T: CompactTxStreamer>(pub Arc<T>);
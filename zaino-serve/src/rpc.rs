@@ -1,6 +1,21 @@
 //! Lightwallet service RPC implementations and Nym functionality.
 
 use std::sync::{atomic::AtomicBool, Arc};
+use zaino_fetch::{
+    chain::blockcache::BlockCache,
+    jsonrpc::{
+        connection_pool::ConnectionPool,
+        connector::{Auth, JsonRpcConnector},
+        validator_pool::ValidatorPool,
+    },
+    primitives::chain::NetworkKind,
+};
+use zaino_state::{
+    fetch::{FetchService, NoLocalState},
+    queue::QueueSlot,
+};
+
+use crate::server::{capabilities::ServerFeatures, method_filter::MethodFilter};
 
 #[cfg(feature = "nym_poc")]
 pub mod nymwalletservice;
@@ -17,6 +32,48 @@ pub struct GrpcClient {
     pub lightwalletd_uri: http::Uri,
     /// Zebrad uri.
     pub zebrad_uri: http::Uri,
+    /// Authentication sent with requests to zebrad, e.g. a cookie file.
+    pub auth: Option<Auth>,
+    /// Validator endpoints requests to zebrad fail over to, requests are only sent to zebrad_uri if None.
+    pub validator_pool: Option<ValidatorPool>,
+    /// Connections to zebrad, shared between workers.
+    pub connection_pool: ConnectionPool,
+    /// Network served, addresses for other networks are rejected.
+    pub network: NetworkKind,
+    /// Compact block cache, shared between workers.
+    pub block_cache: BlockCache,
+    /// Serves transaction, mempool and node info requests, with its caches shared between workers.
+    pub fetch_service: Arc<FetchService<NoLocalState>>,
+    /// Number of blocks fetched concurrently ahead of the client in a GetBlockRange stream.
+    pub block_range_prefetch: usize,
+    /// Optional features enabled on the server, reported by GetServerCapabilities.
+    pub features: ServerFeatures,
+    /// Enabled gRPC methods, reported by GetServerCapabilities.
+    pub method_filter: MethodFilter,
     /// Represents the Online status of the gRPC server.
     pub online: Arc<AtomicBool>,
 }
+
+impl GrpcClient {
+    /// Returns a JsonRpcConnector to zebrad, authenticating with the client's auth.
+    ///
+    /// Requests are routed through the client's validator pool, if set, over connections from its connection pool.
+    pub fn node_connector(&self) -> JsonRpcConnector {
+        match &self.validator_pool {
+            Some(validator_pool) => {
+                JsonRpcConnector::new_with_pool(validator_pool.clone(), self.auth.clone())
+            }
+            None => JsonRpcConnector::new_with_auth(self.zebrad_uri.clone(), self.auth.clone()),
+        }
+        .with_connection_pool(self.connection_pool.clone())
+    }
+
+    /// Takes a slot of the fetch service's upstream queue for requests sent to zebrad, held until the slot is dropped.
+    ///
+    /// Fails with a resource exhausted status while every slot is held, see [`FetchService::with_upstream_queue`].
+    pub fn enter_upstream(&self) -> Result<Option<QueueSlot>, tonic::Status> {
+        self.fetch_service
+            .enter_upstream()
+            .map_err(|e| e.to_grpc_status())
+    }
+}
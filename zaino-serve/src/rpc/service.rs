@@ -1,28 +1,306 @@
 //! Lightwallet service RPC implementations.
 
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicI64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::StreamExt;
 use hex::FromHex;
 use tokio::time::timeout;
 use tokio_stream::wrappers::ReceiverStream;
 
-use crate::{rpc::GrpcClient, utils::get_build_info};
+use crate::{
+    rpc::GrpcClient,
+    server::{
+        capabilities::ServerFeatures, method_filter::MethodFilter, request_log::LoggedPayload,
+    },
+    utils::get_build_info,
+};
 use zaino_fetch::{
-    chain::{block::get_block_from_node, mempool::Mempool},
-    jsonrpc::{connector::JsonRpcConnector, response::GetTransactionResponse},
+    chain::{
+        block::get_block_from_connector,
+        blockcache::BlockCache,
+        error::{BlockCacheError, MempoolError},
+        mempool::{Mempool, MempoolAddressFilter, MempoolExcludeFilter, MempoolUpdate},
+        transaction::FullTransaction,
+        utils::ParseFromSlice,
+    },
+    jsonrpc::{
+        connector::JsonRpcConnector,
+        error::JsonRpcConnectorError,
+        response::{
+            GetBlockResponse, GetBlockchainInfoResponse, GetInfoResponse, GetTransactionResponse,
+            GetUtxosResponse,
+        },
+    },
+    metrics::{MethodKind, MethodTimer},
     primitives::{
-        chain::{ConsensusBranchId, ConsensusBranchIdHex},
+        address::TransparentAddress,
+        chain::{NetworkKind, NetworkUpgrade, NetworkUpgradeStatus},
         height::ChainHeight,
+        transaction::TransactionHash,
     },
+    spans::{instrument_grpc, record_hash, record_height},
+    task::spawn_named,
 };
 use zaino_proto::proto::{
     compact_formats::{CompactBlock, CompactTx},
     service::{
         compact_tx_streamer_server::CompactTxStreamer, Address, AddressList, Balance, BlockId,
-        BlockRange, ChainSpec, Duration, Empty, Exclude, GetAddressUtxosArg, GetAddressUtxosReply,
-        GetAddressUtxosReplyList, GetSubtreeRootsArg, LightdInfo, PingResponse, RawTransaction,
-        SendResponse, SubtreeRoot, TransparentAddressBlockFilter, TreeState, TxFilter,
+        BlockRange, ChainSpec, Duration, Empty, EstimateFeeArg, Exclude, FeeRate,
+        GetAddressUtxosArg, GetAddressUtxosReply, GetAddressUtxosReplyList, GetSubtreeRootsArg,
+        LightdInfo, PingResponse, RawTransaction, SendResponse, ServerCapabilities,
+        ShieldedProtocol, SubtreeRoot, TransparentAddressBlockFilter, TreeState, TxFilter,
     },
 };
 
+/// Maximum number of transactions fetched ahead of the client in a GetTaddressTxids stream.
+const TADDRESS_TXIDS_LOOKAHEAD: usize = 16;
+
+/// Interval between checks of the server's online flag in a GetTaddressMempoolStream stream, and the initial delay
+/// before re-fetching the mempool after a failed fetch.
+const TADDRESS_MEMPOOL_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Maximum delay between retries of a failed mempool fetch in a GetTaddressMempoolStream stream.
+const TADDRESS_MEMPOOL_MAX_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(8);
+
+/// Maximum number of outputs fetched per page in a GetAddressUtxosStream stream.
+const ADDRESS_UTXOS_PAGE_SIZE: usize = 100;
+
+/// Number of Ping requests currently being served.
+static PINGS_IN_FLIGHT: AtomicI64 = AtomicI64::new(0);
+
+/// Builds a [`PingResponse`] echoing the request nonce, with the server's wall-clock time and version.
+///
+/// No chain work is done and the node is not contacted, so the response time reflects the indexer alone.
+/// The request delay (`interval_us`) is ignored.
+pub fn ping_response(request: Duration) -> PingResponse {
+    let entry = PINGS_IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+    let server_time_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_millis() as i64);
+    let exit = PINGS_IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    PingResponse {
+        entry,
+        exit,
+        nonce: request.nonce,
+        server_time_ms,
+        version: get_build_info().version,
+    }
+}
+
+/// Builds the [`ServerCapabilities`] of a server with `features` enabled, serving the methods enabled by
+/// `method_filter` for a node reporting `node_subversion`.
+pub fn server_capabilities(
+    features: &ServerFeatures,
+    method_filter: &MethodFilter,
+    node_subversion: String,
+) -> ServerCapabilities {
+    ServerCapabilities {
+        version: get_build_info().version,
+        node_subversion,
+        features: features.names(),
+        enabled_methods: method_filter.enabled_methods(),
+    }
+}
+
+/// Builds a [`BlockId`] for the node's best chain tip from its `getblockchaininfo` response.
+///
+/// The hash is returned in big-endian (display) byte order, matching lightwalletd.
+pub fn tip_block_id(blockchain_info: &GetBlockchainInfoResponse) -> BlockId {
+    BlockId {
+        height: blockchain_info.blocks.0 as u64,
+        hash: blockchain_info
+            .best_block_hash
+            .bytes_in_display_order()
+            .to_vec(),
+    }
+}
+
+/// Builds a [`GetAddressUtxosReply`] from an unspent output returned by the node's `getaddressutxos`.
+///
+/// The txid is returned in little-endian (internal) byte order, matching lightwalletd.
+pub fn address_utxo_reply(utxo: GetUtxosResponse) -> GetAddressUtxosReply {
+    GetAddressUtxosReply {
+        address: utxo.address.to_string(),
+        txid: utxo.txid.0.to_vec(),
+        index: i32::try_from(utxo.output_index).unwrap_or(i32::MAX),
+        script: utxo.script.as_raw_bytes().to_vec(),
+        value_zat: i64::try_from(utxo.satoshis).unwrap_or(i64::MAX),
+        height: utxo.height.0 as u64,
+    }
+}
+
+/// Checks that each of `addresses` is a valid transparent address for `network`.
+///
+/// Returns an invalid argument status naming the first invalid address so requests for the wrong network are rejected
+/// before the node is queried.
+pub fn check_transparent_addresses(
+    addresses: &[String],
+    network: NetworkKind,
+) -> Result<(), tonic::Status> {
+    for address in addresses {
+        if let Err(e) = TransparentAddress::parse_for_network(address, network) {
+            return Err(tonic::Status::invalid_argument(format!(
+                "Invalid transparent address {}: {}",
+                address, e
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Builds a [`LightdInfo`] response for `network` from the node's `getinfo` and `getblockchaininfo` responses.
+///
+/// The chain name is that of `network`. The sapling activation height and consensus branch id are taken from the
+/// `upgrades` map, the sapling activation height falls back to the network's expected activation height and the
+/// consensus branch id is that of the latest network upgrade active at the chain tip.
+pub fn lightd_info_from_node(
+    zebra_info: GetInfoResponse,
+    blockchain_info: GetBlockchainInfoResponse,
+    network: NetworkKind,
+) -> LightdInfo {
+    let sapling_height = blockchain_info
+        .upgrades
+        .values()
+        .find(|upgrade| upgrade.name == NetworkUpgrade::Sapling)
+        .map(|upgrade| upgrade.activation_height)
+        .or_else(|| network.activation_height(NetworkUpgrade::Sapling))
+        .unwrap_or(ChainHeight(1));
+    let consensus_branch_id = blockchain_info
+        .upgrades
+        .iter()
+        .filter(|(_, upgrade)| {
+            upgrade.status == NetworkUpgradeStatus::Active
+                && upgrade.activation_height.0 <= blockchain_info.blocks.0
+        })
+        .max_by_key(|(_, upgrade)| upgrade.activation_height.0)
+        .map_or(blockchain_info.consensus.chain_tip, |(branch_id, _)| {
+            *branch_id
+        });
+
+    let build_info = get_build_info();
+
+    LightdInfo {
+        version: build_info.version,
+        vendor: "ZingoLabs ZingoIndexerD".to_string(),
+        taddr_support: true,
+        chain_name: network.chain_name().to_string(),
+        sapling_activation_height: sapling_height.0 as u64,
+        consensus_branch_id: consensus_branch_id.0.to_string(),
+        block_height: blockchain_info.blocks.0 as u64,
+        git_commit: build_info.commit_hash,
+        branch: build_info.branch,
+        build_date: build_info.build_date,
+        build_user: build_info.build_user,
+        estimated_height: blockchain_info.estimated_height.0 as u64,
+        zcashd_build: zebra_info.build,
+        zcashd_subversion: zebra_info.subversion,
+    }
+}
+
+/// Returns the mempool transaction with the given txid if it spends from or pays to an address in `address_filter`.
+async fn matching_mempool_transaction(
+    zebrad_client: &JsonRpcConnector,
+    address_filter: &MempoolAddressFilter,
+    txid: &TransactionHash,
+) -> Result<Option<RawTransaction>, MempoolError> {
+    let (hex, height) = match zebrad_client
+        .get_raw_transaction(txid.to_string(), Some(1))
+        .await?
+    {
+        GetTransactionResponse::Object { hex, height, .. } => (hex, height),
+        GetTransactionResponse::Raw(_) => {
+            return Err(MempoolError::JsonRpcError(JsonRpcConnectorError::new(
+                "Received raw transaction type, this should not be possible here.",
+            )))
+        }
+    };
+    let (_, transaction) =
+        FullTransaction::parse_from_slice(&hex.bytes, Some(vec![txid.0.to_vec()]), None)?;
+    if !address_filter.matches(&transaction, zebrad_client).await? {
+        return Ok(None);
+    }
+    Ok(Some(RawTransaction {
+        data: hex.bytes,
+        height: height as u64,
+    }))
+}
+
+/// Runs `fetch` unless the client drops the stream fed by `channel_tx` first, returns None if the stream was dropped.
+///
+/// An in-flight fetch is cancelled as soon as the client disconnects, rather than once its result fails to send.
+async fn unless_closed<T, F: std::future::Future>(
+    channel_tx: &tokio::sync::mpsc::Sender<T>,
+    fetch: F,
+) -> Option<F::Output> {
+    tokio::select! {
+        biased;
+        _ = channel_tx.closed() => None,
+        output = fetch => Some(output),
+    }
+}
+
+/// Streams the compact blocks from `start` to `end`, fetched through `zebrad_client`, to `channel_tx`, in descending
+/// order if `start` is above `end`.
+///
+/// Up to `block_range_prefetch` blocks are fetched concurrently ahead of the client, blocks completing out of order
+/// are held back so the stream stays in height order. Blocks are served from `block_cache` if present and added to
+/// it when fetched, a block that fails to fetch is sent as its error status and the stream continues.
+///
+/// Returns [`tonic::Status::cancelled`] if the client drops the stream before the last block is sent, outstanding
+/// fetches are cancelled.
+pub async fn stream_block_range(
+    zebrad_client: &JsonRpcConnector,
+    block_cache: &BlockCache,
+    start: u32,
+    end: u32,
+    block_range_prefetch: usize,
+    channel_tx: &tokio::sync::mpsc::Sender<Result<CompactBlock, tonic::Status>>,
+) -> Result<(), tonic::Status> {
+    let (low, high) = (start.min(end), start.max(end));
+    let heights = (low..=high).map(move |height| {
+        if start > end {
+            high - (height - low)
+        } else {
+            height
+        }
+    });
+    let mut blocks = futures::stream::iter(heights)
+        .map(|height| async move {
+            if let Some(block) = block_cache.get(height) {
+                return Ok(block);
+            }
+            println!("[TEST] Fetching block at height: {}.", height);
+            let block = get_block_from_connector(zebrad_client, &height).await?;
+            block_cache.insert(block.clone());
+            Ok::<_, BlockCacheError>(block)
+        })
+        // NOTE: `buffered` yields results in input order regardless of completion order.
+        .buffered(block_range_prefetch.max(1));
+    let cancelled = || tonic::Status::cancelled("get_block_range cancelled by client");
+    loop {
+        tokio::select! {
+            biased;
+            _ = channel_tx.closed() => return Err(cancelled()),
+            next = blocks.next() => match next {
+                Some(block) => {
+                    if channel_tx
+                        .send(block.map_err(|e| e.to_grpc_status()))
+                        .await
+                        .is_err()
+                    {
+                        return Err(cancelled());
+                    }
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
 /// Stream of RawTransactions, output type of get_taddress_txids.
 pub struct RawTransactionStream {
     inner: ReceiverStream<Result<RawTransaction, tonic::Status>>,
@@ -85,8 +363,105 @@ impl futures::Stream for CompactBlockStream {
     }
 }
 
+/// Stream of CompactTransactions, output type of get_mempool_tx.
+pub struct CompactTransactionStream {
+    inner: ReceiverStream<Result<CompactTx, tonic::Status>>,
+}
+
+impl CompactTransactionStream {
+    /// Returns new instanse of CompactTransactionStream.
+    pub fn new(rx: tokio::sync::mpsc::Receiver<Result<CompactTx, tonic::Status>>) -> Self {
+        CompactTransactionStream {
+            inner: ReceiverStream::new(rx),
+        }
+    }
+}
+
+impl futures::Stream for CompactTransactionStream {
+    type Item = Result<CompactTx, tonic::Status>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let poll = std::pin::Pin::new(&mut self.inner).poll_next(cx);
+        match poll {
+            std::task::Poll::Ready(Some(Ok(raw_tx))) => std::task::Poll::Ready(Some(Ok(raw_tx))),
+            std::task::Poll::Ready(Some(Err(e))) => std::task::Poll::Ready(Some(Err(e))),
+            std::task::Poll::Ready(None) => std::task::Poll::Ready(None),
+            std::task::Poll::Pending => std::task::Poll::Pending,
+        }
+    }
+}
+
+/// Stream of SubtreeRoots, output type of get_subtree_roots.
+pub struct SubtreeRootStream {
+    inner: ReceiverStream<Result<SubtreeRoot, tonic::Status>>,
+}
+
+impl SubtreeRootStream {
+    /// Returns new instanse of SubtreeRootStream.
+    pub fn new(rx: tokio::sync::mpsc::Receiver<Result<SubtreeRoot, tonic::Status>>) -> Self {
+        SubtreeRootStream {
+            inner: ReceiverStream::new(rx),
+        }
+    }
+}
+
+/// Stream of GetAddressUtxosReplys, output type of get_address_utxos_stream.
+pub struct AddressUtxoStream {
+    inner: ReceiverStream<Result<GetAddressUtxosReply, tonic::Status>>,
+}
+
+impl AddressUtxoStream {
+    /// Returns new instanse of AddressUtxoStream.
+    pub fn new(
+        rx: tokio::sync::mpsc::Receiver<Result<GetAddressUtxosReply, tonic::Status>>,
+    ) -> Self {
+        AddressUtxoStream {
+            inner: ReceiverStream::new(rx),
+        }
+    }
+}
+
+impl futures::Stream for AddressUtxoStream {
+    type Item = Result<GetAddressUtxosReply, tonic::Status>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let poll = std::pin::Pin::new(&mut self.inner).poll_next(cx);
+        match poll {
+            std::task::Poll::Ready(Some(Ok(utxo))) => std::task::Poll::Ready(Some(Ok(utxo))),
+            std::task::Poll::Ready(Some(Err(e))) => std::task::Poll::Ready(Some(Err(e))),
+            std::task::Poll::Ready(None) => std::task::Poll::Ready(None),
+            std::task::Poll::Pending => std::task::Poll::Pending,
+        }
+    }
+}
+
+impl futures::Stream for SubtreeRootStream {
+    type Item = Result<SubtreeRoot, tonic::Status>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let poll = std::pin::Pin::new(&mut self.inner).poll_next(cx);
+        match poll {
+            std::task::Poll::Ready(Some(Ok(raw_tx))) => std::task::Poll::Ready(Some(Ok(raw_tx))),
+            std::task::Poll::Ready(Some(Err(e))) => std::task::Poll::Ready(Some(Err(e))),
+            std::task::Poll::Ready(None) => std::task::Poll::Ready(None),
+            std::task::Poll::Pending => std::task::Poll::Pending,
+        }
+    }
+}
+
 impl CompactTxStreamer for GrpcClient {
-    /// Return the height of the tip of the best chain.
+    /// Return the height and hash of the tip of the best chain.
+    ///
+    /// The tip is fetched from the node on each call so reorgs are reflected as soon as the node has switched chains.
     fn get_latest_block<'life0, 'async_trait>(
         &'life0 self,
         _request: tonic::Request<ChainSpec>,
@@ -103,24 +478,22 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_latest_block.");
-        Box::pin(async {
-            let blockchain_info = JsonRpcConnector::new(
-                self.zebrad_uri.clone(),
-                Some("xxxxxx".to_string()),
-                Some("xxxxxx".to_string()),
-            )
-            .await
-            .get_blockchain_info()
-            .await
-            .map_err(|e| e.to_grpc_status())?;
-
-            let block_id = BlockId {
-                height: blockchain_info.blocks.0 as u64,
-                hash: blockchain_info.best_block_hash.0.to_vec(),
-            };
+        Box::pin(instrument_grpc("get_latest_block", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_latest_block");
+            let blockchain_info = self
+                .node_connector()
+                .get_blockchain_info()
+                .await
+                .map_err(|e| e.to_grpc_status())?;
+            self.block_cache
+                .update_tip(blockchain_info.blocks.0, &blockchain_info.best_block_hash.0);
+
+            let block_id = tip_block_id(&blockchain_info);
+            record_height(block_id.height);
+            record_hash(&blockchain_info.best_block_hash.to_string());
 
             Ok(tonic::Response::new(block_id))
-        })
+        }))
     }
 
     /// Return the compact block corresponding to the given block identifier.
@@ -145,9 +518,10 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_block.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_block", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_block");
             Err(tonic::Status::unimplemented("get_block not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        }))
     }
 
     /// Same as GetBlock except actions contain only nullifiers.
@@ -170,9 +544,10 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_block_nullifiers.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_block_nullifiers", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_block_nullifiers");
             Err(tonic::Status::unimplemented("get_block_nullifiers not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        }))
     }
 
     /// Server streaming response type for the GetBlockRange method.
@@ -181,8 +556,13 @@ impl CompactTxStreamer for GrpcClient {
 
     /// Return a list of consecutive compact blocks.
     ///
-    /// TODO: This implementation is slow. An internal block cache should be implemented that this rpc, along with the get_block rpc, can rely on.
-    ///       - add get_block function that queries the block cache for block and calls get_block_from_node to fetch block if not present.
+    /// Blocks are streamed from start to end, in descending order if start is above end.
+    /// Up to [`GrpcClient::block_range_prefetch`] blocks are fetched concurrently ahead of the client, blocks completing
+    /// out of order are held back so the stream stays in height order, see [`stream_block_range`].
+    /// Fetching stops once the client drops the stream, the stream task then ends with `Status::cancelled`.
+    ///
+    /// Blocks are served from the block cache if present and added to the cache when fetched,
+    /// the cache is first checked against the node's best chain so blocks from a reorganised chain are not served.
     fn get_block_range<'life0, 'async_trait>(
         &'life0 self,
         request: tonic::Request<BlockRange>,
@@ -202,49 +582,48 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_block_range.");
-        let zebrad_uri = self.zebrad_uri.clone();
-        Box::pin(async move {
+        let zebrad_client = self.node_connector();
+        let block_cache = self.block_cache.clone();
+        let block_range_prefetch = self.block_range_prefetch.max(1);
+        Box::pin(instrument_grpc("get_block_range", async move {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_block_range");
             let blockrange = request.into_inner();
-            let mut start = blockrange
+            let start = blockrange
                 .start
                 .map(|s| s.height as u32)
                 .ok_or(tonic::Status::invalid_argument("Start block not specified"))?;
-            let mut end = blockrange
+            let end = blockrange
                 .end
                 .map(|e| e.height as u32)
                 .ok_or(tonic::Status::invalid_argument("End block not specified"))?;
-            if start > end {
-                (start, end) = (end, start);
-            }
             println!("[TEST] Fetching blocks in range: {}-{}.", start, end);
+            let upstream_slot = self.enter_upstream()?;
+            let blockchain_info = zebrad_client
+                .get_blockchain_info()
+                .await
+                .map_err(|e| e.to_grpc_status())?;
+            block_cache.update_tip(blockchain_info.blocks.0, &blockchain_info.best_block_hash.0);
             let (channel_tx, channel_rx) = tokio::sync::mpsc::channel(32);
-            tokio::spawn(async move {
+            spawn_named("get_block_range_stream", async move {
+                let _upstream_slot = upstream_slot;
                 // NOTE: This timeout is so slow due to the blockcache not being implemented. This should be reduced to 30s once functionality is in place.
-                let timeout = timeout(std::time::Duration::from_secs(120), async {
-                    for height in (start..=end).rev() {
-                        println!("[TEST] Fetching block at height: {}.", height);
-                        let compact_block = get_block_from_node(&zebrad_uri, &height).await;
-                        match compact_block {
-                            Ok(block) => {
-                                if channel_tx.send(Ok(block)).await.is_err() {
-                                    break;
-                                }
-                            }
-                            Err(e) => {
-                                if channel_tx
-                                    .send(Err(tonic::Status::internal(e.to_string())))
-                                    .await
-                                    .is_err()
-                                {
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                })
+                let timeout = timeout(
+                    std::time::Duration::from_secs(120),
+                    stream_block_range(
+                        &zebrad_client,
+                        &block_cache,
+                        start,
+                        end,
+                        block_range_prefetch,
+                        &channel_tx,
+                    ),
+                )
                 .await;
                 match timeout {
-                    Ok(_) => {}
+                    Ok(Ok(())) => {}
+                    Ok(Err(status)) => {
+                        println!("[TEST] get_block_range ended: {}.", status.message());
+                    }
                     Err(_) => {
                         channel_tx
                             .send(Err(tonic::Status::internal(
@@ -258,7 +637,7 @@ impl CompactTxStreamer for GrpcClient {
             let output_stream = CompactBlockStream::new(channel_rx);
             let stream_boxed = Box::pin(output_stream);
             Ok(tonic::Response::new(stream_boxed))
-        })
+        }))
     }
 
     /// Server streaming response type for the GetBlockRangeNullifiers method.
@@ -288,12 +667,15 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_block_range_nullifiers.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_block_range_nullifiers", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_block_range_nullifiers");
             Err(tonic::Status::unimplemented("get_block_range_nullifiers not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        }))
     }
 
     /// Return the requested full (not compact) transaction (as from zcashd).
+    ///
+    /// The transaction is fetched through [`GrpcClient::fetch_service`].
     fn get_transaction<'life0, 'async_trait>(
         &'life0 self,
         request: tonic::Request<TxFilter>,
@@ -310,20 +692,17 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_transaction.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_transaction", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_transaction");
             let hash = request.into_inner().hash;
             if hash.len() == 32 {
                 let reversed_hash = hash.iter().rev().copied().collect::<Vec<u8>>();
                 let hash_hex = hex::encode(reversed_hash);
-                let tx = JsonRpcConnector::new(
-                    self.zebrad_uri.clone(),
-                    Some("xxxxxx".to_string()),
-                    Some("xxxxxx".to_string()),
-                )
-                .await
-                .get_raw_transaction(hash_hex, Some(1))
-                .await
-                .map_err(|e| e.to_grpc_status())?;
+                let tx = self
+                    .fetch_service
+                    .get_raw_transaction(hash_hex, Some(1))
+                    .await
+                    .map_err(|e| e.to_grpc_status())?;
 
                 let (hex, height) = if let GetTransactionResponse::Object { hex, height, .. } = tx {
                     (hex, height)
@@ -335,6 +714,7 @@ impl CompactTxStreamer for GrpcClient {
                         "Invalid response from server - Height conversion failed",
                     )
                 })?;
+                record_height(height);
 
                 Ok(tonic::Response::new(RawTransaction {
                     data: hex.bytes,
@@ -345,7 +725,7 @@ impl CompactTxStreamer for GrpcClient {
                     "Transaction hash incorrect",
                 ))
             }
-        })
+        }))
     }
 
     /// Submit the given transaction to the Zcash network.
@@ -365,23 +745,36 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of send_transaction.");
-        Box::pin(async {
-            let hex_tx = hex::encode(request.into_inner().data);
-            let tx_output = JsonRpcConnector::new(
-                self.zebrad_uri.clone(),
-                Some("xxxxxx".to_string()),
-                Some("xxxxxx".to_string()),
-            )
-            .await
-            .send_raw_transaction(hex_tx)
-            .await
-            .map_err(|e| e.to_grpc_status())?;
-
-            Ok(tonic::Response::new(SendResponse {
+        Box::pin(instrument_grpc("send_transaction", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "send_transaction");
+            let raw_transaction = request.into_inner().data;
+            let _upstream_slot = self.enter_upstream()?;
+            let zebrad_client = self.node_connector();
+            let consensus = zebrad_client
+                .get_blockchain_info()
+                .await
+                .map_err(|e| e.to_grpc_status())?
+                .consensus;
+            FullTransaction::parse_for_relay(&raw_transaction, &consensus).map_err(|e| {
+                tonic::Status::invalid_argument(format!("Invalid transaction: {}", e))
+            })?;
+            let hex_tx = hex::encode(&raw_transaction);
+            let tx_output = zebrad_client
+                .send_raw_transaction(hex_tx)
+                .await
+                .map_err(|e| e.to_grpc_status())?;
+            let txid = tx_output.0.to_string();
+
+            // NOTE: The raw transaction is never logged, only its txid and length.
+            let mut response = tonic::Response::new(SendResponse {
                 error_code: 0,
-                error_message: tx_output.0.to_string(),
-            }))
-        })
+                error_message: txid.clone(),
+            });
+            response
+                .extensions_mut()
+                .insert(LoggedPayload::transaction(&txid, raw_transaction.len()));
+            Ok(response)
+        }))
     }
 
     /// Server streaming response type for the GetTaddressTxids method.
@@ -389,6 +782,10 @@ impl CompactTxStreamer for GrpcClient {
     type GetTaddressTxidsStream = std::pin::Pin<Box<RawTransactionStream>>;
 
     /// This name is misleading, returns the full transactions that have either inputs or outputs connected to the given transparent address.
+    ///
+    /// Transactions within the filter's height range are streamed in the order returned by the node, an address without
+    /// transactions in the range yields an empty stream. Up to [`TADDRESS_TXIDS_LOOKAHEAD`] transactions are fetched
+    /// concurrently ahead of the client, fetching stops once the client drops the stream.
     fn get_taddress_txids<'life0, 'async_trait>(
         &'life0 self,
         request: tonic::Request<TransparentAddressBlockFilter>,
@@ -408,9 +805,14 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_taddress_txids.");
-        Box::pin(async move {
+        Box::pin(instrument_grpc("get_taddress_txids", async move {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_taddress_txids");
             let block_filter = request.into_inner();
             let address = block_filter.address;
+            if address.is_empty() {
+                return Err(tonic::Status::invalid_argument("Address not specified"));
+            }
+            check_transparent_addresses(std::slice::from_ref(&address), self.network)?;
             let start = block_filter
                 .range
                 .clone()
@@ -423,54 +825,51 @@ impl CompactTxStreamer for GrpcClient {
                 .map(|e| e.height as u32)
                 .ok_or(tonic::Status::invalid_argument("End block not specified"))?;
 
-            let zebrad_client = JsonRpcConnector::new(
-                self.zebrad_uri.clone(),
-                Some("xxxxxx".to_string()),
-                Some("xxxxxx".to_string()),
-            )
-            .await;
+            let upstream_slot = self.enter_upstream()?;
+            let zebrad_client = self.node_connector();
             let txids = zebrad_client
-                .get_address_txids(vec![address], start, end)
+                .get_address_txids(vec![address], Some(start), Some(end))
                 .await
                 .map_err(|e| e.to_grpc_status())?;
 
             let (channel_tx, channel_rx) = tokio::sync::mpsc::channel(32);
-            tokio::spawn(async move {
+            spawn_named("get_taddress_txids_stream", async move {
+                let _upstream_slot = upstream_slot;
                 let timeout = timeout(std::time::Duration::from_secs(30), async {
-                    for txid in txids.transactions {
-                        let transaction = zebrad_client.get_raw_transaction(txid, Some(1)).await;
-                        match transaction {
-                            Ok(GetTransactionResponse::Object { hex, height, .. }) => {
-                                if channel_tx
-                                    .send(Ok(RawTransaction {
-                                        data: hex.bytes,
-                                        height: height as u64,
-                                    }))
-                                    .await
-                                    .is_err()
-                                {
-                                    break;
+                    let mut transactions = futures::stream::iter(txids.transactions)
+                        .map(|txid| {
+                            let zebrad_client = &zebrad_client;
+                            async move {
+                                match zebrad_client.get_raw_transaction(txid, Some(1)).await {
+                                    Ok(GetTransactionResponse::Object { hex, height, .. }) => {
+                                        Ok(RawTransaction {
+                                            data: hex.bytes,
+                                            height: height as u64,
+                                        })
+                                    }
+                                    Ok(GetTransactionResponse::Raw(_)) => Err(tonic::Status::internal(
+                                        "Received raw transaction type, this should not be impossible.",
+                                    )),
+                                    Err(e) => Err(tonic::Status::internal(e.to_string())),
                                 }
                             }
-                            Ok(GetTransactionResponse::Raw(_)) => {
-                                if channel_tx
-                                .send(Err(tonic::Status::internal(
-                                    "Received raw transaction type, this should not be impossible.",
-                                )))
-                                .await
-                                .is_err()
-                            {
+                        })
+                        .buffered(TADDRESS_TXIDS_LOOKAHEAD);
+                    loop {
+                        tokio::select! {
+                            biased;
+                            _ = channel_tx.closed() => {
+                                // The client has dropped the stream, outstanding fetches are cancelled.
+                                println!("[TEST] get_taddress_txids cancelled by client.");
                                 break;
                             }
-                            }
-                            Err(e) => {
-                                if channel_tx
-                                    .send(Err(tonic::Status::internal(e.to_string())))
-                                    .await
-                                    .is_err()
-                                {
-                                    break;
+                            next = transactions.next() => match next {
+                                Some(transaction) => {
+                                    if channel_tx.send(transaction).await.is_err() {
+                                        break;
+                                    }
                                 }
+                                None => break,
                             }
                         }
                     }
@@ -491,7 +890,7 @@ impl CompactTxStreamer for GrpcClient {
             let output_stream = RawTransactionStream::new(channel_rx);
             let stream_boxed = Box::pin(output_stream);
             Ok(tonic::Response::new(stream_boxed))
-        })
+        }))
     }
 
     /// This RPC has not been implemented as it is not currently used by zingolib.
@@ -512,9 +911,10 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_taddress_balance.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_taddress_balance", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_taddress_balance");
             Err(tonic::Status::unimplemented("get_taddress_balance not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        }))
     }
 
     /// This RPC has not been implemented as it is not currently used by zingolib.
@@ -536,14 +936,15 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_taddress_balance_stream.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_taddress_balance_stream", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_taddress_balance_stream");
             Err(tonic::Status::unimplemented("get_taddress_balance_stream not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        }))
     }
 
     /// Server streaming response type for the GetMempoolTx method.
     #[doc = "Server streaming response type for the GetMempoolTx method."]
-    type GetMempoolTxStream = tonic::Streaming<CompactTx>;
+    type GetMempoolTxStream = std::pin::Pin<Box<CompactTransactionStream>>;
 
     /// Return the compact transactions currently in the mempool; the results
     /// can be a few seconds out of date. If the Exclude list is empty, return
@@ -555,11 +956,14 @@ impl CompactTxStreamer for GrpcClient {
     /// match a shortened txid, they are all sent (none is excluded). Transactions
     /// in the exclude list that don't exist in the mempool are ignored.
     ///
-    /// This RPC has not been implemented as it is not currently used by zingolib.
-    /// If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer).
+    /// Fetching stops once the client drops the stream, cancelling the in-flight transaction fetch.
+    ///
+    /// The mempool and its transactions are fetched through [`GrpcClient::fetch_service`].
+    ///
+    /// TODO: This RPC should query Zingo-Indexer's internal mempool state rather than directly querying zebrad.
     fn get_mempool_tx<'life0, 'async_trait>(
         &'life0 self,
-        _request: tonic::Request<Exclude>,
+        request: tonic::Request<Exclude>,
     ) -> core::pin::Pin<
         Box<
             dyn core::future::Future<
@@ -576,9 +980,77 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_mempool_tx.");
-        Box::pin(async {
-            Err(tonic::Status::unimplemented("get_mempool_tx not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        Box::pin(instrument_grpc("get_mempool_tx", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_mempool_tx");
+            let exclude_filter = MempoolExcludeFilter::new(request.into_inner().txid);
+
+            let fetch_service = self.fetch_service.clone();
+            let mempool_txids = fetch_service
+                .get_raw_mempool()
+                .await
+                .map_err(|e| e.to_grpc_status())?
+                .transactions
+                .iter()
+                .map(TransactionHash::from_hex)
+                .collect::<Result<Vec<TransactionHash>, _>>()
+                .map_err(|_e| {
+                    tonic::Status::internal("Invalid response from server - Txid decoding failed")
+                })?;
+            let txids = exclude_filter.filter(mempool_txids);
+
+            let (channel_tx, channel_rx) = tokio::sync::mpsc::channel(32);
+            spawn_named("get_mempool_tx_stream", async move {
+                let timeout = timeout(std::time::Duration::from_secs(30), async {
+                    for txid in txids {
+                        let transaction = match unless_closed(
+                            &channel_tx,
+                            fetch_service.get_raw_transaction(txid.to_string(), Some(1)),
+                        )
+                        .await
+                        {
+                            Some(transaction) => transaction,
+                            None => {
+                                println!("[TEST] get_mempool_tx cancelled by client.");
+                                break;
+                            }
+                        };
+                        let compact_tx = match transaction {
+                            Ok(GetTransactionResponse::Object { hex, .. }) => {
+                                FullTransaction::parse_from_slice(
+                                    hex.as_ref(),
+                                    Some(vec![txid.0.to_vec()]),
+                                    None,
+                                )
+                                .and_then(|(_, transaction)| transaction.to_compact(0))
+                                .map_err(|e| e.to_grpc_status())
+                            }
+                            Ok(GetTransactionResponse::Raw(_)) => Err(tonic::Status::internal(
+                                "Received raw transaction type, this should not be impossible.",
+                            )),
+                            Err(e) => Err(e.to_grpc_status()),
+                        };
+                        if channel_tx.send(compact_tx).await.is_err() {
+                            break;
+                        }
+                    }
+                })
+                .await;
+                match timeout {
+                    Ok(_) => {}
+                    Err(_) => {
+                        channel_tx
+                            .send(Err(tonic::Status::internal(
+                                "get_mempool_tx gRPC request timed out",
+                            )))
+                            .await
+                            .ok();
+                    }
+                }
+            });
+            let output_stream = CompactTransactionStream::new(channel_rx);
+            let stream_boxed = Box::pin(output_stream);
+            Ok(tonic::Response::new(stream_boxed))
+        }))
     }
 
     /// Server streaming response type for the GetMempoolStream method.
@@ -588,6 +1060,8 @@ impl CompactTxStreamer for GrpcClient {
     /// Return a stream of current Mempool transactions. This will keep the output stream open while
     /// there are mempool transactions. It will close the returned stream when a new block is mined.
     ///
+    /// The node is no longer polled once the client drops the stream.
+    ///
     /// TODO: This implementation is slow. Zingo-Indexer's blockcache state engine should keep its own internal mempool state.
     ///     - This RPC should query Zingo-Indexer's internal mempool state rather than creating its own mempool and directly querying zebrad.
     fn get_mempool_stream<'life0, 'async_trait>(
@@ -609,20 +1083,15 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_mempool_stream.");
-        Box::pin(async {
-            let zebrad_client = JsonRpcConnector::new(
-                self.zebrad_uri.clone(),
-                Some("xxxxxx".to_string()),
-                Some("xxxxxx".to_string()),
-            )
-            .await;
-
-            let zebrad_uri = self.zebrad_uri.clone();
+        Box::pin(instrument_grpc("get_mempool_stream", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_mempool_stream");
+            let zebrad_client = self.node_connector();
+
             let (channel_tx, channel_rx) = tokio::sync::mpsc::channel(32);
-            tokio::spawn(async move {
+            spawn_named("get_mempool_stream", async move {
                 let timeout = timeout(std::time::Duration::from_secs(30), async {
                     let mempool = Mempool::new();
-                    if let Err(e) = mempool.update(&zebrad_uri).await {
+                    if let Err(e) = mempool.update(&zebrad_client).await {
                         channel_tx.send(Err(tonic::Status::internal(e.to_string())))
                             .await
                             .ok();
@@ -630,13 +1099,23 @@ impl CompactTxStreamer for GrpcClient {
                     }
                     let mut mined = false;
                     let mut txid_index: usize = 0;
-                    while !mined {
+                    'stream: while !mined {
                         match mempool.get_mempool_txids().await {
                             Ok(mempool_txids) => {
                                 for txid in &mempool_txids[txid_index..] {
-                                    match zebrad_client
-                                        .get_raw_transaction(txid.clone(), Some(1))
-                                        .await {
+                                    let transaction = match unless_closed(
+                                        &channel_tx,
+                                        zebrad_client.get_raw_transaction(txid.clone(), Some(1)),
+                                    )
+                                    .await
+                                    {
+                                        Some(transaction) => transaction,
+                                        None => {
+                                            println!("[TEST] get_mempool_stream cancelled by client.");
+                                            break 'stream;
+                                        }
+                                    };
+                                    match transaction {
                                         Ok(GetTransactionResponse::Object { hex, height, .. }) => {
                                             txid_index += 1;
                                             if channel_tx
@@ -647,19 +1126,19 @@ impl CompactTxStreamer for GrpcClient {
                                                 .await
                                                 .is_err()
                                             {
-                                                break;
+                                                break 'stream;
                                             }
                                         }
                                         Ok(GetTransactionResponse::Raw(_)) => {
                                             if channel_tx
-                                            .send(Err(tonic::Status::internal(
-                                                "Received raw transaction type, this should not be impossible.",
-                                            )))
-                                            .await
-                                            .is_err()
-                                        {
-                                            break;
-                                        }
+                                                .send(Err(tonic::Status::internal(
+                                                    "Received raw transaction type, this should not be impossible.",
+                                                )))
+                                                .await
+                                                .is_err()
+                                            {
+                                                break 'stream;
+                                            }
                                         }
                                         Err(e) => {
                                             if channel_tx
@@ -667,7 +1146,7 @@ impl CompactTxStreamer for GrpcClient {
                                                 .await
                                                 .is_err()
                                             {
-                                        break;
+                                                break 'stream;
                                             }
                                         }
                                     }
@@ -683,15 +1162,24 @@ impl CompactTxStreamer for GrpcClient {
                                 }
                             }
                         }
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                        mined = match mempool.update(&zebrad_uri).await {
-                            Ok(mined) => mined,
-                            Err(e) => {
+                        // The mempool is not polled again once the client has dropped the stream.
+                        mined = match unless_closed(&channel_tx, async {
+                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                            mempool.update(&zebrad_client).await
+                        })
+                        .await
+                        {
+                            Some(Ok(mined)) => mined,
+                            Some(Err(e)) => {
                                 channel_tx.send(Err(tonic::Status::internal(e.to_string())))
                                     .await
                                     .ok();
                                 break;
                             }
+                            None => {
+                                println!("[TEST] get_mempool_stream cancelled by client.");
+                                break;
+                            }
                         };
                     }
                 })
@@ -711,7 +1199,138 @@ impl CompactTxStreamer for GrpcClient {
             let output_stream = RawTransactionStream::new(channel_rx);
             let stream_boxed = Box::pin(output_stream);
             Ok(tonic::Response::new(stream_boxed))
-        })
+        }))
+    }
+
+    #[doc = "Server streaming response type for the GetTaddressMempoolStream method."]
+    type GetTaddressMempoolStreamStream = std::pin::Pin<Box<RawTransactionStream>>;
+
+    /// Return a stream of the Mempool transactions spending from or paying to any of the given
+    /// transparent addresses. This will keep the output stream open, streaming matching transactions
+    /// as they enter the mempool. If the address list is empty no transactions are returned.
+    ///
+    /// Each transaction entering the mempool is decoded and its transparent inputs and outputs checked against the
+    /// address list, transactions that do not match are not sent. The stream is closed when the client drops it or
+    /// the server goes offline.
+    ///
+    /// Mempool updates are followed through [`GrpcClient::fetch_service`]'s mempool poller, the call fails with
+    /// `Unavailable` if the service does not follow one.
+    fn get_taddress_mempool_stream<'life0, 'async_trait>(
+        &'life0 self,
+        request: tonic::Request<AddressList>,
+    ) -> core::pin::Pin<
+        Box<
+            dyn core::future::Future<
+                    Output = std::result::Result<
+                        tonic::Response<Self::GetTaddressMempoolStreamStream>,
+                        tonic::Status,
+                    >,
+                > + core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        println!("[TEST] Received call of get_taddress_mempool_stream.");
+        Box::pin(instrument_grpc("get_taddress_mempool_stream", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_taddress_mempool_stream");
+            let addresses = request.into_inner().addresses;
+            check_transparent_addresses(&addresses, self.network)?;
+            let address_filter = MempoolAddressFilter::new(addresses);
+            let (channel_tx, channel_rx) = tokio::sync::mpsc::channel(32);
+            if address_filter.is_empty() {
+                // NOTE: Dropping the sender closes the stream without sending any transactions.
+                return Ok(tonic::Response::new(Box::pin(RawTransactionStream::new(
+                    channel_rx,
+                ))));
+            }
+            let Some(updates) = self.fetch_service.subscriber().subscribe_mempool() else {
+                return Err(tonic::Status::unavailable(
+                    "Mempool updates are not available from this server",
+                ));
+            };
+            let zebrad_client = self.node_connector();
+            let mut updates = Box::pin(updates);
+            let online = self.online.clone();
+            spawn_named("get_taddress_mempool_stream", async move {
+                // Txids already checked, the mempool is re-fetched on start and when updates are dropped so transactions
+                // added before the subscription are not missed.
+                let mut checked: HashSet<TransactionHash> = HashSet::new();
+                let mut resync = true;
+                let mut retry_delay = TADDRESS_MEMPOOL_CHECK_INTERVAL;
+                let mut online_check = tokio::time::interval(TADDRESS_MEMPOOL_CHECK_INTERVAL);
+                'stream: while online.load(Ordering::SeqCst) {
+                    let added: Vec<TransactionHash> = if resync {
+                        match zebrad_client.get_raw_mempool().await {
+                            Ok(mempool) => {
+                                resync = false;
+                                retry_delay = TADDRESS_MEMPOOL_CHECK_INTERVAL;
+                                mempool
+                                    .transactions
+                                    .iter()
+                                    .filter_map(|txid| TransactionHash::from_hex(txid).ok())
+                                    .collect()
+                            }
+                            Err(e) => {
+                                eprintln!(
+                                    "Failed to fetch mempool, retrying in {:?}: {}.",
+                                    retry_delay, e
+                                );
+                                tokio::select! {
+                                    _ = tokio::time::sleep(retry_delay) => {}
+                                    _ = channel_tx.closed() => break 'stream,
+                                }
+                                retry_delay =
+                                    (retry_delay * 2).min(TADDRESS_MEMPOOL_MAX_RETRY_DELAY);
+                                continue;
+                            }
+                        }
+                    } else {
+                        tokio::select! {
+                            update = updates.next() => match update {
+                                Some(MempoolUpdate::Added(txid)) => vec![txid],
+                                Some(MempoolUpdate::Removed(txid)) => {
+                                    checked.remove(&txid);
+                                    continue;
+                                }
+                                Some(MempoolUpdate::Lagged(_)) => {
+                                    resync = true;
+                                    continue;
+                                }
+                                None => break 'stream,
+                            },
+                            _ = channel_tx.closed() => break 'stream,
+                            _ = online_check.tick() => continue,
+                        }
+                    };
+                    for txid in added {
+                        if !checked.insert(txid) {
+                            continue;
+                        }
+                        match matching_mempool_transaction(&zebrad_client, &address_filter, &txid)
+                            .await
+                        {
+                            Ok(Some(transaction)) => {
+                                if channel_tx.send(Ok(transaction)).await.is_err() {
+                                    break 'stream;
+                                }
+                            }
+                            Ok(None) => {}
+                            // NOTE: Transactions can leave the mempool before they are checked, errors for a single
+                            // transaction do not close the stream.
+                            Err(e) => {
+                                eprintln!("Failed to check mempool transaction {}: {}.", txid, e);
+                            }
+                        }
+                    }
+                }
+            });
+            let output_stream = RawTransactionStream::new(channel_rx);
+            let stream_boxed = Box::pin(output_stream);
+            Ok(tonic::Response::new(stream_boxed))
+        }))
     }
 
     /// GetTreeState returns the note commitment tree state corresponding to the given block.
@@ -734,20 +1353,29 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_tree_state.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_tree_state", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_tree_state");
             let block_id = request.into_inner();
             let hash_or_height = if block_id.height != 0 {
                 block_id.height.to_string()
             } else {
                 hex::encode(block_id.hash)
             };
+            let cached = if block_id.height != 0 {
+                u32::try_from(block_id.height)
+                    .ok()
+                    .and_then(|height| self.block_cache.get_treestate_at(height))
+            } else {
+                self.block_cache.get_treestate(&hash_or_height)
+            };
+            if let Some(tree_state) = cached {
+                record_height(tree_state.height);
+                record_hash(&tree_state.hash);
+                return Ok(tonic::Response::new(tree_state));
+            }
 
-            let zebrad_client = JsonRpcConnector::new(
-                self.zebrad_uri.clone(),
-                Some("xxxxxx".to_string()),
-                Some("xxxxxx".to_string()),
-            )
-            .await;
+            let _upstream_slot = self.enter_upstream()?;
+            let zebrad_client = self.node_connector();
 
             // TODO: This is slow. Chain, along with other blockchain info should be saved on startup and used here [blockcache?].
             let chain = zebrad_client
@@ -756,18 +1384,22 @@ impl CompactTxStreamer for GrpcClient {
                 .map_err(|e| e.to_grpc_status())?
                 .chain;
             let treestate = zebrad_client
-                .get_treestate(hash_or_height)
+                .get_treestate_with_retry(hash_or_height, 3, std::time::Duration::from_millis(500))
                 .await
                 .map_err(|e| e.to_grpc_status())?;
-            Ok(tonic::Response::new(TreeState {
+            let tree_state = TreeState {
                 network: chain,
                 height: treestate.height as u64,
                 hash: treestate.hash.to_string(),
                 time: treestate.time,
                 sapling_tree: treestate.sapling.commitments.final_state.to_string(),
                 orchard_tree: treestate.orchard.commitments.final_state.to_string(),
-            }))
-        })
+            };
+            record_height(tree_state.height);
+            record_hash(&tree_state.hash);
+            self.block_cache.insert_treestate(&tree_state);
+            Ok(tonic::Response::new(tree_state))
+        }))
     }
 
     /// This RPC has not been implemented as it is not currently used by zingolib.
@@ -788,23 +1420,23 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_latest_tree_state.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_latest_tree_state", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_latest_tree_state");
             Err(tonic::Status::unimplemented("get_latest_tree_state not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        }))
     }
 
     /// Server streaming response type for the GetSubtreeRoots method.
     #[doc = " Server streaming response type for the GetSubtreeRoots method."]
-    type GetSubtreeRootsStream = tonic::Streaming<SubtreeRoot>;
+    type GetSubtreeRootsStream = std::pin::Pin<Box<SubtreeRootStream>>;
 
     /// Returns a stream of information about roots of subtrees of the Sapling and Orchard
     /// note commitment trees.
     ///
-    /// This RPC has not been implemented as it is not currently used by zingolib.
-    /// If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer).
+    /// Fetching stops once the client drops the stream, cancelling the in-flight block fetch.
     fn get_subtree_roots<'life0, 'async_trait>(
         &'life0 self,
-        _request: tonic::Request<GetSubtreeRootsArg>,
+        request: tonic::Request<GetSubtreeRootsArg>,
     ) -> core::pin::Pin<
         Box<
             dyn core::future::Future<
@@ -821,9 +1453,88 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_subtree_roots.");
-        Box::pin(async {
-            Err(tonic::Status::unimplemented("get_subtree_roots not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        Box::pin(instrument_grpc("get_subtree_roots", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_subtree_roots");
+            let subtree_roots_args = request.into_inner();
+            let pool = match ShieldedProtocol::try_from(subtree_roots_args.shielded_protocol) {
+                Ok(ShieldedProtocol::Sapling) => "sapling".to_string(),
+                Ok(ShieldedProtocol::Orchard) => "orchard".to_string(),
+                Err(_) => {
+                    return Err(tonic::Status::invalid_argument(
+                        "Invalid shielded protocol value",
+                    ))
+                }
+            };
+            let start_index = u16::try_from(subtree_roots_args.start_index)
+                .map_err(|_e| tonic::Status::invalid_argument("Start index out of range"))?;
+            let limit =
+                if subtree_roots_args.max_entries == 0 {
+                    None
+                } else {
+                    Some(u16::try_from(subtree_roots_args.max_entries).map_err(|_e| {
+                        tonic::Status::invalid_argument("Max entries out of range")
+                    })?)
+                };
+
+            let upstream_slot = self.enter_upstream()?;
+            let zebrad_client = self.node_connector();
+            let subtrees = zebrad_client
+                .get_subtrees_by_index(pool, start_index, limit)
+                .await
+                .map_err(|e| e.to_grpc_status())?;
+
+            let (channel_tx, channel_rx) = tokio::sync::mpsc::channel(32);
+            spawn_named("get_subtree_roots_stream", async move {
+                let _upstream_slot = upstream_slot;
+                let timeout = timeout(std::time::Duration::from_secs(30), async {
+                    for subtree in subtrees.subtrees {
+                        let block = match unless_closed(
+                            &channel_tx,
+                            zebrad_client.get_block(subtree.height, Some(1)),
+                        )
+                        .await
+                        {
+                            Some(block) => block,
+                            None => {
+                                println!("[TEST] get_subtree_roots cancelled by client.");
+                                break;
+                            }
+                        };
+                        let subtree_root = match block {
+                            Ok(GetBlockResponse::Object { hash, height, .. }) => Ok(SubtreeRoot {
+                                root_hash: subtree.root.to_vec(),
+                                completing_block_hash: hash.0 .0.to_vec(),
+                                completing_block_height: height
+                                    .map(|h| h.0 as u64)
+                                    .unwrap_or(subtree.height.0 as u64),
+                            }),
+                            Ok(GetBlockResponse::Raw(_)) => Err(tonic::Status::internal(
+                                "Received raw block type, this should not be impossible.",
+                            )),
+                            Err(e) => Err(e.to_grpc_status()),
+                        };
+                        if channel_tx.send(subtree_root).await.is_err() {
+                            break;
+                        }
+                    }
+                })
+                .await;
+                match timeout {
+                    Ok(_) => {}
+                    Err(_) => {
+                        channel_tx
+                            .send(Err(tonic::Status::internal(
+                                "get_subtree_roots gRPC request timed out",
+                            )))
+                            .await
+                            .ok();
+                    }
+                }
+            });
+            let output_stream = SubtreeRootStream::new(channel_rx);
+            let stream_boxed = Box::pin(output_stream);
+            Ok(tonic::Response::new(stream_boxed))
+        }))
     }
 
     /// This RPC has not been implemented as it is not currently used by zingolib.
@@ -847,20 +1558,25 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_address_utxos.");
-        Box::pin(async {
+        Box::pin(instrument_grpc("get_address_utxos", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_address_utxos");
             Err(tonic::Status::unimplemented("get_address_utxos not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        }))
     }
 
     /// Server streaming response type for the GetAddressUtxosStream method.
     #[doc = "Server streaming response type for the GetAddressUtxosStream method."]
-    type GetAddressUtxosStreamStream = tonic::Streaming<GetAddressUtxosReply>;
+    type GetAddressUtxosStreamStream = std::pin::Pin<Box<AddressUtxoStream>>;
 
-    /// This RPC has not been implemented as it is not currently used by zingolib.
-    /// If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer).
+    /// Returns a stream of the unspent transparent outputs of the given addresses, one reply per output.
+    ///
+    /// Outputs mined at or above `start_height` are streamed in (height, txid, output index) order, stopping after
+    /// `max_entries` outputs if non-zero. Outputs are fetched in pages of at most [`ADDRESS_UTXOS_PAGE_SIZE`], anchored
+    /// to the chain tip when the first page is fetched, so outputs spent or mined during the stream are never
+    /// returned twice. Fetching stops once the client drops the stream.
     fn get_address_utxos_stream<'life0, 'async_trait>(
         &'life0 self,
-        _request: tonic::Request<GetAddressUtxosArg>,
+        request: tonic::Request<GetAddressUtxosArg>,
     ) -> core::pin::Pin<
         Box<
             dyn core::future::Future<
@@ -877,12 +1593,99 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_address_utxos_stream.");
-        Box::pin(async {
-            Err(tonic::Status::unimplemented("get_address_utxos_stream not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        Box::pin(instrument_grpc("get_address_utxos_stream", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_address_utxos_stream");
+            let utxos_args = request.into_inner();
+            check_transparent_addresses(&utxos_args.addresses, self.network)?;
+            let start_height = u32::try_from(utxos_args.start_height)
+                .map_err(|_e| tonic::Status::invalid_argument("Start height out of range"))?;
+            let max_entries = match utxos_args.max_entries {
+                0 => None,
+                max_entries => Some(max_entries as usize),
+            };
+            let page_size = max_entries.map_or(ADDRESS_UTXOS_PAGE_SIZE, |max_entries| {
+                max_entries.min(ADDRESS_UTXOS_PAGE_SIZE)
+            });
+
+            let upstream_slot = self.enter_upstream()?;
+            let zebrad_client = self.node_connector();
+            // NOTE: The first page is fetched before the stream is returned so invalid requests fail the call.
+            let first_page = zebrad_client
+                .get_address_utxos_page(
+                    utxos_args.addresses.clone(),
+                    ChainHeight(start_height),
+                    page_size,
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_grpc_status())?;
+
+            let (channel_tx, channel_rx) = tokio::sync::mpsc::channel(32);
+            spawn_named("get_address_utxos_stream", async move {
+                let _upstream_slot = upstream_slot;
+                let timeout = timeout(std::time::Duration::from_secs(120), async {
+                    let mut page = first_page;
+                    let mut sent = 0;
+                    'stream: loop {
+                        for utxo in page.utxos {
+                            if max_entries.is_some_and(|max_entries| sent >= max_entries) {
+                                break 'stream;
+                            }
+                            if channel_tx.send(Ok(address_utxo_reply(utxo))).await.is_err() {
+                                break 'stream;
+                            }
+                            sent += 1;
+                        }
+                        let cursor = match page.next {
+                            Some(cursor) => cursor,
+                            None => break,
+                        };
+                        page = match unless_closed(
+                            &channel_tx,
+                            zebrad_client.get_address_utxos_page(
+                                utxos_args.addresses.clone(),
+                                ChainHeight(start_height),
+                                page_size,
+                                Some(cursor),
+                            ),
+                        )
+                        .await
+                        {
+                            Some(Ok(page)) => page,
+                            Some(Err(e)) => {
+                                channel_tx.send(Err(e.to_grpc_status())).await.ok();
+                                break;
+                            }
+                            None => {
+                                println!("[TEST] get_address_utxos_stream cancelled by client.");
+                                break;
+                            }
+                        };
+                    }
+                })
+                .await;
+                match timeout {
+                    Ok(_) => {}
+                    Err(_) => {
+                        channel_tx
+                            .send(Err(tonic::Status::internal(
+                                "get_address_utxos_stream gRPC request timed out",
+                            )))
+                            .await
+                            .ok();
+                    }
+                }
+            });
+            let output_stream = AddressUtxoStream::new(channel_rx);
+            let stream_boxed = Box::pin(output_stream);
+            Ok(tonic::Response::new(stream_boxed))
+        }))
     }
 
     /// Return information about this lightwalletd instance and the blockchain
+    ///
+    /// The node's version information is fetched through [`GrpcClient::fetch_service`], so it is served from the
+    /// info cache within its TTL.
     fn get_lightd_info<'life0, 'async_trait>(
         &'life0 self,
         _request: tonic::Request<Empty>,
@@ -899,17 +1702,13 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of get_lightd_info.");
-        // TODO: Add user and password as fields of GrpcClient and use here.
         // TODO: Return Nym_Address in get_lightd_info response, for use by wallets.
-        Box::pin(async {
-            let zebrad_client = JsonRpcConnector::new(
-                self.zebrad_uri.clone(),
-                Some("xxxxxx".to_string()),
-                Some("xxxxxx".to_string()),
-            )
-            .await;
-
-            let zebra_info = zebrad_client
+        Box::pin(instrument_grpc("get_lightd_info", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_lightd_info");
+            let zebrad_client = self.node_connector();
+
+            let zebra_info = self
+                .fetch_service
                 .get_info()
                 .await
                 .map_err(|e| e.to_grpc_status())?;
@@ -918,50 +1717,83 @@ impl CompactTxStreamer for GrpcClient {
                 .await
                 .map_err(|e| e.to_grpc_status())?;
 
-            let sapling_id_str = "76b809bb";
-            let sapling_id = ConsensusBranchIdHex(
-                ConsensusBranchId::from_hex(sapling_id_str).map_err(|_e| {
-                    tonic::Status::internal(
-                        "Internal Error - Consesnsus Branch ID hex conversion failed",
-                    )
-                })?,
-            );
-            let sapling_height = blockchain_info
-                .upgrades
-                .get(&sapling_id)
-                .map_or(ChainHeight(1), |sapling_json| {
-                    sapling_json.activation_height
-                });
-
-            let build_info = get_build_info();
-
-            let lightd_info = LightdInfo {
-                version: build_info.version,
-                vendor: "ZingoLabs ZingoIndexerD".to_string(),
-                taddr_support: true,
-                chain_name: blockchain_info.chain,
-                sapling_activation_height: sapling_height.0 as u64,
-                consensus_branch_id: blockchain_info.consensus.chain_tip.0.to_string(),
-                block_height: blockchain_info.blocks.0 as u64,
-                git_commit: build_info.commit_hash,
-                branch: build_info.branch,
-                build_date: build_info.build_date,
-                build_user: build_info.build_user,
-                estimated_height: blockchain_info.estimated_height.0 as u64,
-                zcashd_build: zebra_info.build,
-                zcashd_subversion: zebra_info.subversion,
-            };
+            let lightd_info = lightd_info_from_node(zebra_info, blockchain_info, self.network);
 
             Ok(tonic::Response::new(lightd_info))
-        })
+        }))
     }
 
-    // /// Testing-only, requires lightwalletd --ping-very-insecure (do not enable in production) [from zebrad]
-    /// This RPC has not been implemented as it is not currently used by zingolib.
-    /// If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer).
+    /// Return the recommended fee rate to be mined within the given number of blocks
+    fn estimate_fee<'life0, 'async_trait>(
+        &'life0 self,
+        request: tonic::Request<EstimateFeeArg>,
+    ) -> core::pin::Pin<
+        Box<
+            dyn core::future::Future<
+                    Output = std::result::Result<tonic::Response<FeeRate>, tonic::Status>,
+                > + core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        println!("[TEST] Received call of estimate_fee.");
+        Box::pin(instrument_grpc("estimate_fee", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "estimate_fee");
+            let zats_per_kb = self
+                .node_connector()
+                .estimate_fee(request.into_inner().target_blocks)
+                .await
+                .map_err(|e| e.to_grpc_status())?;
+            Ok(tonic::Response::new(FeeRate { zats_per_kb }))
+        }))
+    }
+
+    /// Return the version, enabled features and served methods of this Zingo-Indexer instance.
+    ///
+    /// The node's subversion is fetched through [`GrpcClient::fetch_service`], see [`server_capabilities`].
+    fn get_server_capabilities<'life0, 'async_trait>(
+        &'life0 self,
+        _request: tonic::Request<Empty>,
+    ) -> core::pin::Pin<
+        Box<
+            dyn core::future::Future<
+                    Output = std::result::Result<
+                        tonic::Response<ServerCapabilities>,
+                        tonic::Status,
+                    >,
+                > + core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        println!("[TEST] Received call of get_server_capabilities.");
+        Box::pin(instrument_grpc("get_server_capabilities", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "get_server_capabilities");
+            let node_info = self
+                .fetch_service
+                .get_info()
+                .await
+                .map_err(|e| e.to_grpc_status())?;
+            Ok(tonic::Response::new(server_capabilities(
+                &self.features,
+                &self.method_filter,
+                node_info.subversion,
+            )))
+        }))
+    }
+
+    /// Latency probe, echoes the request nonce with the server's wall-clock time and version.
+    ///
+    /// Does no chain work and does not contact the node, see [`ping_response`].
     fn ping<'life0, 'async_trait>(
         &'life0 self,
-        _request: tonic::Request<Duration>,
+        request: tonic::Request<Duration>,
     ) -> core::pin::Pin<
         Box<
             dyn core::future::Future<
@@ -975,8 +1807,9 @@ impl CompactTxStreamer for GrpcClient {
         Self: 'async_trait,
     {
         println!("[TEST] Received call of ping.");
-        Box::pin(async {
-            Err(tonic::Status::unimplemented("ping not yet implemented. If you require this RPC please open an issue or PR at the Zingo-Indexer github (https://github.com/zingolabs/zingo-indexer)."))
-        })
+        Box::pin(instrument_grpc("ping", async {
+            let _timer = MethodTimer::start(MethodKind::Grpc, "ping");
+            Ok(tonic::Response::new(ping_response(request.into_inner())))
+        }))
     }
 }
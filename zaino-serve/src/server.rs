@@ -5,11 +5,16 @@ use std::sync::{
     Arc,
 };
 
+pub mod capabilities;
 pub mod director;
 pub mod error;
 pub(crate) mod ingestor;
+pub mod limit;
+pub mod method_filter;
 pub(crate) mod queue;
 pub mod request;
+pub mod request_log;
+pub mod tls;
 pub(crate) mod worker;
 
 /// Holds a thread safe reperesentation of a StatusType.
@@ -0,0 +1,108 @@
+//! Optional features of the server, reported by the GetServerCapabilities gRPC method.
+//!
+//! Features are set from the indexer's config and compile-time features when the server is spawned, so a client can
+//! learn in one call which optional services are running instead of probing each endpoint.
+
+use std::collections::BTreeSet;
+
+/// An optional feature of a Zingo-Indexer server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Feature {
+    /// gRPC served over TCP.
+    Tcp,
+    /// gRPC served over a Unix domain socket.
+    UnixSocket,
+    /// Requests received over the Nym mixnet.
+    Nym,
+    /// gRPC served over TLS.
+    Tls,
+    /// Prometheus metrics endpoint.
+    Metrics,
+    /// Readiness endpoint.
+    Health,
+    /// gRPC reflection service.
+    GrpcReflection,
+    /// gRPC request logging.
+    RequestLog,
+    /// Failover between several validators.
+    ValidatorFailover,
+    /// Block cache persisted to disk.
+    DiskBlockCache,
+    /// Caches warmed up before serving.
+    Warmup,
+    /// Mempool transactions streamed by GetMempoolStream.
+    MempoolStream,
+    /// Built with the deprecated `nym_poc` feature.
+    NymPoc,
+    /// Built with the `tokio-console` feature.
+    TokioConsole,
+}
+
+impl Feature {
+    /// Every feature, in the order features are reported.
+    pub const ALL: [Feature; 14] = [
+        Feature::Tcp,
+        Feature::UnixSocket,
+        Feature::Nym,
+        Feature::Tls,
+        Feature::Metrics,
+        Feature::Health,
+        Feature::GrpcReflection,
+        Feature::RequestLog,
+        Feature::ValidatorFailover,
+        Feature::DiskBlockCache,
+        Feature::Warmup,
+        Feature::MempoolStream,
+        Feature::NymPoc,
+        Feature::TokioConsole,
+    ];
+
+    /// Returns the name the feature is reported as, e.g. `"mempool_stream"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::Tcp => "tcp",
+            Feature::UnixSocket => "unix_socket",
+            Feature::Nym => "nym",
+            Feature::Tls => "tls",
+            Feature::Metrics => "metrics",
+            Feature::Health => "health",
+            Feature::GrpcReflection => "grpc_reflection",
+            Feature::RequestLog => "request_log",
+            Feature::ValidatorFailover => "validator_failover",
+            Feature::DiskBlockCache => "disk_block_cache",
+            Feature::Warmup => "warmup",
+            Feature::MempoolStream => "mempool_stream",
+            Feature::NymPoc => "nym_poc",
+            Feature::TokioConsole => "tokio_console",
+        }
+    }
+}
+
+/// Features enabled on a Zingo-Indexer server, none by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerFeatures {
+    /// Enabled features.
+    enabled: BTreeSet<Feature>,
+}
+
+impl ServerFeatures {
+    /// Returns the set of `features`.
+    pub fn new(features: impl IntoIterator<Item = Feature>) -> Self {
+        ServerFeatures {
+            enabled: features.into_iter().collect(),
+        }
+    }
+
+    /// Returns true if `feature` is enabled.
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.enabled.contains(&feature)
+    }
+
+    /// Returns the names of the enabled features, in [`Feature::ALL`] order.
+    pub fn names(&self) -> Vec<String> {
+        self.enabled
+            .iter()
+            .map(|feature| feature.name().to_string())
+            .collect()
+    }
+}
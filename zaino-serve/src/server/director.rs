@@ -3,18 +3,33 @@
 use http::Uri;
 use nym_sphinx_anonymous_replies::requests::AnonymousSenderTag;
 use std::{
+    collections::HashSet,
     net::SocketAddr,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
-        Arc,
+        Arc, RwLock,
     },
 };
+use tonic::transport::ServerTlsConfig;
+use zaino_fetch::{
+    chain::blockcache::BlockCache,
+    jsonrpc::{connection_pool::ConnectionPool, connector::Auth, validator_pool::ValidatorPool},
+    primitives::chain::NetworkKind,
+    task::spawn_named,
+};
+use zaino_nym::utils::{default_nym_allowed_methods, NymRequestLimits};
+use zaino_state::fetch::{FetchService, NoLocalState};
 
 use crate::server::{
+    capabilities::ServerFeatures,
     error::{IngestorError, ServerError, WorkerError},
-    ingestor::{NymIngestor, TcpIngestor},
+    ingestor::{NymIngestor, TcpIngestor, UdsIngestor},
+    limit::{RequestLimiter, RequestLimits},
+    method_filter::MethodFilter,
     queue::Queue,
     request::ZingoIndexerRequest,
+    request_log::RequestLogger,
     worker::{WorkerPool, WorkerPoolStatus},
     AtomicStatus, StatusType,
 };
@@ -25,11 +40,14 @@ pub struct ServerStatus {
     /// Status of the Server.
     pub server_status: AtomicStatus,
     tcp_ingestor_status: AtomicStatus,
+    uds_ingestor_status: AtomicStatus,
     nym_ingestor_status: AtomicStatus,
     nym_dispatcher_status: AtomicStatus,
     workerpool_status: WorkerPoolStatus,
     request_queue_status: Arc<AtomicUsize>,
     nym_response_queue_status: Arc<AtomicUsize>,
+    /// Nym address the NymIngestor listens at, None if the NymIngestor is not active.
+    nym_address: Arc<RwLock<Option<String>>>,
 }
 
 impl ServerStatus {
@@ -38,11 +56,28 @@ impl ServerStatus {
         ServerStatus {
             server_status: AtomicStatus::new(5),
             tcp_ingestor_status: AtomicStatus::new(5),
+            uds_ingestor_status: AtomicStatus::new(5),
             nym_ingestor_status: AtomicStatus::new(5),
             nym_dispatcher_status: AtomicStatus::new(5),
             workerpool_status: WorkerPoolStatus::new(max_workers),
             request_queue_status: Arc::new(AtomicUsize::new(0)),
             nym_response_queue_status: Arc::new(AtomicUsize::new(0)),
+            nym_address: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Returns the nym address the NymIngestor listens at, None if the NymIngestor is not active.
+    pub fn nym_address(&self) -> Option<String> {
+        self.nym_address
+            .read()
+            .ok()
+            .and_then(|nym_address| nym_address.clone())
+    }
+
+    /// Records the nym address the NymIngestor listens at.
+    fn record_nym_address(&self, addr: &str) {
+        if let Ok(mut nym_address) = self.nym_address.write() {
+            *nym_address = Some(addr.to_string());
         }
     }
 
@@ -50,6 +85,7 @@ impl ServerStatus {
     pub fn load(&self) -> ServerStatus {
         self.server_status.load();
         self.tcp_ingestor_status.load();
+        self.uds_ingestor_status.load();
         self.nym_ingestor_status.load();
         self.nym_dispatcher_status.load();
         self.workerpool_status.load();
@@ -63,6 +99,8 @@ impl ServerStatus {
 pub struct Server {
     /// Listens for incoming gRPC requests over HTTP.
     tcp_ingestor: Option<TcpIngestor>,
+    /// Listens for incoming gRPC requests over a Unix domain socket.
+    uds_ingestor: Option<UdsIngestor>,
     /// Listens for incoming gRPC requests over Nym Mixnet, also sends responses back to clients.
     nym_ingestor: Option<NymIngestor>,
     /// Dynamically sized pool of workers.
@@ -82,19 +120,35 @@ impl Server {
     pub async fn spawn(
         tcp_active: bool,
         tcp_ingestor_listen_addr: Option<SocketAddr>,
+        uds_listen_path: Option<PathBuf>,
         nym_active: bool,
         nym_conf_path: Option<String>,
+        nym_allowed_methods: Option<HashSet<String>>,
+        nym_request_limits: NymRequestLimits,
         lightwalletd_uri: Uri,
         zebrad_uri: Uri,
+        auth: Option<Auth>,
+        validator_pool: Option<ValidatorPool>,
+        connection_pool: ConnectionPool,
+        network: NetworkKind,
+        block_cache: BlockCache,
+        fetch_service: Arc<FetchService<NoLocalState>>,
+        block_range_prefetch: usize,
         max_queue_size: u16,
         max_worker_pool_size: u16,
         idle_worker_pool_size: u16,
+        tls_config: Option<ServerTlsConfig>,
+        reflection: bool,
+        request_limits: RequestLimits,
+        request_logger: RequestLogger,
+        method_filter: MethodFilter,
+        features: ServerFeatures,
         status: ServerStatus,
         online: Arc<AtomicBool>,
     ) -> Result<Self, ServerError> {
-        if (!tcp_active) && (!nym_active) {
+        if (!tcp_active) && (!nym_active) && uds_listen_path.is_none() {
             return Err(ServerError::ServerConfigError(
-                "Cannot start server with no ingestors selected, at least one of either nym or tcp must be set to active in conf.".to_string(),
+                "Cannot start server with no ingestors selected, at least one of nym, tcp or a unix domain socket must be set to active in conf.".to_string(),
             ));
         }
         if tcp_active && tcp_ingestor_listen_addr.is_none() {
@@ -132,6 +186,21 @@ impl Server {
         } else {
             None
         };
+        let uds_ingestor = match uds_listen_path {
+            Some(socket_path) => {
+                println!("Launching UdsIngestor..");
+                Some(
+                    UdsIngestor::spawn(
+                        socket_path,
+                        request_queue.tx().clone(),
+                        status.uds_ingestor_status.clone(),
+                        online.clone(),
+                    )
+                    .await?,
+                )
+            }
+            None => None,
+        };
         let nym_ingestor = if nym_active {
             println!("Launching NymIngestor..");
             let nym_conf_path_string =
@@ -139,6 +208,8 @@ impl Server {
             Some(
                 NymIngestor::spawn(
                     nym_conf_path_string.clone().as_str(),
+                    nym_allowed_methods.unwrap_or_else(default_nym_allowed_methods),
+                    nym_request_limits,
                     request_queue.tx().clone(),
                     nym_response_queue.rx().clone(),
                     nym_response_queue.tx().clone(),
@@ -150,6 +221,9 @@ impl Server {
         } else {
             None
         };
+        if let Some(ingestor) = &nym_ingestor {
+            status.record_nym_address(ingestor.nym_address());
+        }
 
         println!("Launching WorkerPool..");
         let worker_pool = WorkerPool::spawn(
@@ -160,12 +234,26 @@ impl Server {
             nym_response_queue.tx().clone(),
             lightwalletd_uri,
             zebrad_uri,
+            auth,
+            validator_pool,
+            connection_pool,
+            network,
+            block_cache,
+            fetch_service,
+            block_range_prefetch,
+            tls_config,
+            reflection,
+            RequestLimiter::new(request_limits),
+            request_logger,
+            method_filter,
+            features,
             status.workerpool_status.clone(),
             online.clone(),
         )
         .await;
         Ok(Server {
             tcp_ingestor,
+            uds_ingestor,
             nym_ingestor,
             worker_pool,
             request_queue,
@@ -182,11 +270,12 @@ impl Server {
     /// - Updates the ServerStatus.
     /// - Checks for shutdown signal, shutting down server if received.
     pub async fn serve(mut self) -> tokio::task::JoinHandle<Result<(), ServerError>> {
-        tokio::task::spawn(async move {
+        spawn_named("server", async move {
             // NOTE: This interval may need to be reduced or removed / moved once scale testing begins.
             let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(50));
             let mut nym_ingestor_handle = None;
             let mut tcp_ingestor_handle = None;
+            let mut uds_ingestor_handle = None;
             let mut worker_handles;
             if let Some(ingestor) = self.nym_ingestor.take() {
                 nym_ingestor_handle = Some(ingestor.serve().await);
@@ -194,6 +283,9 @@ impl Server {
             if let Some(ingestor) = self.tcp_ingestor.take() {
                 tcp_ingestor_handle = Some(ingestor.serve().await);
             }
+            if let Some(ingestor) = self.uds_ingestor.take() {
+                uds_ingestor_handle = Some(ingestor.serve().await);
+            }
             worker_handles = self.worker_pool.clone().serve().await;
             self.status.server_status.store(1);
             loop {
@@ -230,6 +322,7 @@ impl Server {
                     > = worker_handles.into_iter().map(Some).collect();
                     self.shutdown_components(
                         tcp_ingestor_handle,
+                        uds_ingestor_handle,
                         nym_ingestor_handle,
                         worker_handle_options,
                     )
@@ -262,6 +355,7 @@ impl Server {
     async fn shutdown_components(
         &mut self,
         tcp_ingestor_handle: Option<tokio::task::JoinHandle<Result<(), IngestorError>>>,
+        uds_ingestor_handle: Option<tokio::task::JoinHandle<Result<(), IngestorError>>>,
         nym_ingestor_handle: Option<tokio::task::JoinHandle<Result<(), IngestorError>>>,
         mut worker_handles: Vec<Option<tokio::task::JoinHandle<Result<(), WorkerError>>>>,
     ) {
@@ -269,6 +363,10 @@ impl Server {
             self.status.tcp_ingestor_status.store(4);
             handle.await.ok();
         }
+        if let Some(handle) = uds_ingestor_handle {
+            self.status.uds_ingestor_status.store(4);
+            handle.await.ok();
+        }
         if let Some(handle) = nym_ingestor_handle {
             self.status.nym_ingestor_status.store(4);
             handle.await.ok();
@@ -290,6 +388,7 @@ impl Server {
     pub fn statuses(&mut self) -> ServerStatus {
         self.status.server_status.load();
         self.status.tcp_ingestor_status.load();
+        self.status.uds_ingestor_status.load();
         self.status.nym_ingestor_status.load();
         self.status.nym_dispatcher_status.load();
         self.status
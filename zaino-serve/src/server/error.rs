@@ -60,6 +60,9 @@ pub enum WorkerError {
     /// Tokio join error.
     #[error("Tokio join error: {0}")]
     TokioJoinError(#[from] tokio::task::JoinError),
+    /// gRPC reflection service error.
+    #[error("gRPC reflection error: {0}")]
+    ReflectionError(#[from] tonic_reflection::server::Error),
     /// Worker Pool Full.
     #[error("Worker Pool Full")]
     WorkerPoolFull,
@@ -86,4 +89,7 @@ pub enum ServerError {
     /// Server configuration errors.
     #[error("Server configuration error: {0}")]
     ServerConfigError(String),
+    /// Errors loading or parsing TLS certificates and keys.
+    #[error("TLS configuration error: {0}")]
+    TlsConfigError(String),
 }
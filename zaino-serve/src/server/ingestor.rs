@@ -3,21 +3,26 @@
 use nym_sdk::mixnet::MixnetMessageSender;
 use nym_sphinx_anonymous_replies::requests::AnonymousSenderTag;
 use std::{
+    collections::HashSet,
+    io,
     net::SocketAddr,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
 };
-use tokio::net::TcpListener;
+use tokio::net::{TcpListener, UnixListener, UnixStream};
+use zaino_fetch::task::spawn_named;
 
 use crate::server::{
-    error::{IngestorError, QueueError},
+    error::{IngestorError, QueueError, RequestError},
     queue::{QueueReceiver, QueueSender},
     request::ZingoIndexerRequest,
     AtomicStatus, StatusType,
 };
-use zaino_nym::{client::NymClient, error::NymError};
+use zaino_nym::{client::NymClient, error::NymError, utils::NymRequestLimits};
 
 /// Listens for incoming gRPC requests over HTTP.
 pub(crate) struct TcpIngestor {
@@ -52,7 +57,7 @@ impl TcpIngestor {
 
     /// Starts Tcp service.
     pub(crate) async fn serve(self) -> tokio::task::JoinHandle<Result<(), IngestorError>> {
-        tokio::task::spawn(async move {
+        spawn_named("tcp_ingestor", async move {
             // NOTE: This interval may need to be changed or removed / moved once scale testing begins.
             let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(50));
             // TODO Check blockcache sync status and wait on server / node if on hold.
@@ -129,10 +134,152 @@ impl TcpIngestor {
     }
 }
 
+/// Listens for incoming gRPC requests over a Unix domain socket.
+pub(crate) struct UdsIngestor {
+    /// Unix domain socket listener.
+    ingestor: UnixListener,
+    /// Path of the socket file.
+    socket_path: PathBuf,
+    /// Used to send requests to the queue.
+    queue: QueueSender<ZingoIndexerRequest>,
+    /// Current status of the ingestor.
+    status: AtomicStatus,
+    /// Represents the Online status of the gRPC server.
+    online: Arc<AtomicBool>,
+}
+
+impl UdsIngestor {
+    /// Permissions of the socket file, only the owner may connect.
+    const SOCKET_MODE: u32 = 0o600;
+
+    /// Creates a Unix domain socket Ingestor.
+    ///
+    /// A stale socket file left at `socket_path` by a previous run is removed. Returns an error if the
+    /// path is held by a socket another process is listening on, or by a file that is not a socket.
+    pub(crate) async fn spawn(
+        socket_path: PathBuf,
+        queue: QueueSender<ZingoIndexerRequest>,
+        status: AtomicStatus,
+        online: Arc<AtomicBool>,
+    ) -> Result<Self, IngestorError> {
+        status.store(0);
+        remove_stale_socket(&socket_path).await?;
+        let listener = UnixListener::bind(&socket_path)?;
+        std::fs::set_permissions(
+            &socket_path,
+            std::fs::Permissions::from_mode(Self::SOCKET_MODE),
+        )?;
+        println!("UdsIngestor listening at: {}.", socket_path.display());
+        Ok(UdsIngestor {
+            ingestor: listener,
+            socket_path,
+            queue,
+            online,
+            status,
+        })
+    }
+
+    /// Starts Unix domain socket service, the socket file is removed when the service closes.
+    pub(crate) async fn serve(self) -> tokio::task::JoinHandle<Result<(), IngestorError>> {
+        spawn_named("uds_ingestor", async move {
+            // NOTE: This interval may need to be changed or removed / moved once scale testing begins.
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(50));
+            self.status.store(1);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if self.check_for_shutdown().await {
+                            return self.close();
+                        }
+                    }
+                    incoming = self.ingestor.accept() => {
+                        if self.check_for_shutdown().await {
+                            return self.close();
+                        }
+                        match incoming {
+                            Ok((stream, _)) => {
+                                match self.queue.try_send(ZingoIndexerRequest::new_from_uds(stream)) {
+                                    Ok(_) => {
+                                        println!("[TEST] Requests in Queue: {}", self.queue.queue_length());
+                                    }
+                                    Err(QueueError::QueueFull(_request)) => {
+                                        eprintln!("Queue Full.");
+                                    }
+                                    Err(e) => {
+                                        eprintln!("Queue Closed. Failed to send request to queue: {}", e);
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("Failed to accept connection with client: {}", e);
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Removes the socket file and sets the ingestor status to closed.
+    fn close(&self) -> Result<(), IngestorError> {
+        self.status.store(5);
+        match std::fs::remove_file(&self.socket_path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks indexers online status and ingestors internal status for closure signal.
+    pub(crate) async fn check_for_shutdown(&self) -> bool {
+        if self.status() >= 4 {
+            return true;
+        }
+        if !self.check_online() {
+            return true;
+        }
+        false
+    }
+
+    /// Returns the ingestor current status usize.
+    pub(crate) fn status(&self) -> usize {
+        self.status.load()
+    }
+
+    fn check_online(&self) -> bool {
+        self.online.load(Ordering::SeqCst)
+    }
+}
+
+/// Removes the socket file at `socket_path` if no process is listening on it.
+async fn remove_stale_socket(socket_path: &Path) -> Result<(), IngestorError> {
+    match std::fs::symlink_metadata(socket_path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            if UnixStream::connect(socket_path).await.is_ok() {
+                return Err(IngestorError::ClientConnectionError(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another process", socket_path.display()),
+                )));
+            }
+            std::fs::remove_file(socket_path)?;
+            Ok(())
+        }
+        Ok(_) => Err(IngestorError::ClientConnectionError(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", socket_path.display()),
+        ))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Listens for incoming gRPC requests over Nym Mixnet.
 pub(crate) struct NymIngestor {
     /// Nym Client
     ingestor: NymClient,
+    /// Request methods reachable over the mixnet.
+    allowed_methods: HashSet<String>,
+    /// Bounds on the length prefixes of requests received over the mixnet.
+    request_limits: NymRequestLimits,
     /// Used to send requests to the queue.
     queue: QueueSender<ZingoIndexerRequest>,
     /// Used to send requests to the queue.
@@ -149,6 +296,8 @@ impl NymIngestor {
     /// Creates a Nym Ingestor
     pub(crate) async fn spawn(
         nym_conf_path: &str,
+        allowed_methods: HashSet<String>,
+        request_limits: NymRequestLimits,
         queue: QueueSender<ZingoIndexerRequest>,
         response_queue: QueueReceiver<(Vec<u8>, AnonymousSenderTag)>,
         response_requeue: QueueSender<(Vec<u8>, AnonymousSenderTag)>,
@@ -161,6 +310,8 @@ impl NymIngestor {
         println!("NymIngestor listening at: {}.", listener.addr);
         Ok(NymIngestor {
             ingestor: listener,
+            allowed_methods,
+            request_limits,
             queue,
             response_queue,
             response_requeue,
@@ -169,9 +320,14 @@ impl NymIngestor {
         })
     }
 
+    /// Returns the nym address the ingestor listens at.
+    pub(crate) fn nym_address(&self) -> &str {
+        &self.ingestor.addr
+    }
+
     /// Starts Nym service.
     pub(crate) async fn serve(mut self) -> tokio::task::JoinHandle<Result<(), IngestorError>> {
-        tokio::task::spawn(async move {
+        spawn_named("nym_ingestor", async move {
             // NOTE: This interval may need to be reduced or removed / moved once scale testing begins.
             let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(50));
             // TODO Check blockcache sync status and wait on server / node if on hold.
@@ -202,8 +358,19 @@ impl NymIngestor {
                                     .sender_tag
                                     .ok_or_else(|| IngestorError::NymError(NymError::EmptyRecipientTagError))?;
                                 // TODO: Handle RequestError here.
-                                let zingo_proxy_request =
-                                    ZingoIndexerRequest::new_from_nym(return_recipient, request_vu8.as_ref())?;
+                                let zingo_proxy_request = match ZingoIndexerRequest::new_from_nym(
+                                    return_recipient,
+                                    request_vu8.as_ref(),
+                                    &self.allowed_methods,
+                                    &self.request_limits,
+                                ) {
+                                    Ok(request) => request,
+                                    Err(RequestError::NymError(NymError::MethodNotAllowed(method))) => {
+                                        eprintln!("Rejected nym request for disallowed method: {}.", method);
+                                        continue;
+                                    }
+                                    Err(e) => return Err(e.into()),
+                                };
                                 match self.queue.try_send(zingo_proxy_request) {
                                     Ok(_) => {}
                                     Err(QueueError::QueueFull(_request)) => {
@@ -0,0 +1,230 @@
+//! Admission control for the gRPC server.
+//!
+//! Requests over the configured concurrency or rate limits are rejected with `Status::resource_exhausted` rather
+//! than queued. A request holds its concurrency permits until its response body, including the whole stream for
+//! streaming methods, has been sent or dropped, so limits apply uniformly to unary and streaming methods.
+
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tonic::{body::BoxBody, Status};
+use tower::{Layer, Service};
+
+/// Concurrency and rate limits applied to gRPC requests, each limit is disabled if None.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestLimits {
+    /// Maximum number of requests in flight on a single connection.
+    pub max_concurrent_requests_per_connection: Option<usize>,
+    /// Maximum number of requests in flight across all connections.
+    pub max_concurrent_requests: Option<usize>,
+    /// Maximum number of requests admitted per second across all connections, bursts of up to this many
+    /// requests are admitted at once.
+    pub max_requests_per_second: Option<u32>,
+}
+
+/// Token bucket used to enforce [`RequestLimits::max_requests_per_second`].
+#[derive(Debug)]
+struct TokenBucket {
+    /// Maximum number of tokens held, also the refill rate per second.
+    capacity: f64,
+    /// Tokens currently held.
+    tokens: f64,
+    /// Time of the last refill.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Returns a full bucket refilled at `rate` tokens per second.
+    fn new(rate: u32) -> Self {
+        TokenBucket {
+            capacity: rate as f64,
+            tokens: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token, returns false if the bucket is empty.
+    fn try_take(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.capacity).min(self.capacity);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Enforces [`RequestLimits`], the global limits are shared by all clones.
+#[derive(Debug, Clone)]
+pub struct RequestLimiter {
+    /// Maximum number of requests in flight on a single connection.
+    per_connection: Option<usize>,
+    /// Permits for requests in flight across all connections.
+    global: Option<Arc<Semaphore>>,
+    /// Token bucket for requests admitted across all connections.
+    rate: Option<Arc<Mutex<TokenBucket>>>,
+}
+
+impl RequestLimiter {
+    /// Returns a new limiter enforcing `limits`.
+    pub fn new(limits: RequestLimits) -> Self {
+        RequestLimiter {
+            per_connection: limits.max_concurrent_requests_per_connection,
+            global: limits
+                .max_concurrent_requests
+                .map(|limit| Arc::new(Semaphore::new(limit))),
+            rate: limits
+                .max_requests_per_second
+                .map(|rate| Arc::new(Mutex::new(TokenBucket::new(rate)))),
+        }
+    }
+
+    /// Returns a layer enforcing the limits for a single connection, a new layer must be used for each connection.
+    pub fn connection_layer(&self) -> RequestLimitLayer {
+        RequestLimitLayer {
+            limiter: self.clone(),
+            connection: self
+                .per_connection
+                .map(|limit| Arc::new(Semaphore::new(limit))),
+        }
+    }
+
+    /// Admits a request, returns the permits held while the request is in flight or the status to reject it with.
+    fn admit(
+        &self,
+        connection: Option<&Arc<Semaphore>>,
+    ) -> Result<Vec<OwnedSemaphorePermit>, Status> {
+        let mut permits = Vec::with_capacity(2);
+        if let Some(connection) = connection {
+            permits.push(connection.clone().try_acquire_owned().map_err(|_| {
+                Status::resource_exhausted("Too many concurrent requests on this connection.")
+            })?);
+        }
+        if let Some(global) = &self.global {
+            permits.push(global.clone().try_acquire_owned().map_err(|_| {
+                Status::resource_exhausted("Too many concurrent requests, try again later.")
+            })?);
+        }
+        if let Some(rate) = &self.rate {
+            let admitted = rate
+                .lock()
+                .map(|mut bucket| bucket.try_take())
+                .unwrap_or(true);
+            if !admitted {
+                return Err(Status::resource_exhausted(
+                    "Request rate limit exceeded, try again later.",
+                ));
+            }
+        }
+        Ok(permits)
+    }
+}
+
+/// Tower layer wrapping a gRPC service in a [`RequestLimit`] for a single connection.
+#[derive(Debug, Clone)]
+pub struct RequestLimitLayer {
+    /// Global limits.
+    limiter: RequestLimiter,
+    /// Permits for requests in flight on this connection.
+    connection: Option<Arc<Semaphore>>,
+}
+
+impl<S> Layer<S> for RequestLimitLayer {
+    type Service = RequestLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLimit {
+            inner,
+            limiter: self.limiter.clone(),
+            connection: self.connection.clone(),
+        }
+    }
+}
+
+/// gRPC service rejecting requests over its [`RequestLimiter`]'s limits with `Status::resource_exhausted`.
+#[derive(Debug, Clone)]
+pub struct RequestLimit<S> {
+    /// Wrapped service.
+    inner: S,
+    /// Global limits.
+    limiter: RequestLimiter,
+    /// Permits for requests in flight on this connection.
+    connection: Option<Arc<Semaphore>>,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for RequestLimit<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<BoxBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let permits = match self.limiter.admit(self.connection.as_ref()) {
+            Ok(permits) => permits,
+            Err(status) => return Box::pin(async move { Ok(status.to_http()) }),
+        };
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let response = response.await?;
+            Ok(response.map(|body| {
+                tonic::body::boxed(PermitBody {
+                    body,
+                    _permits: permits,
+                })
+            }))
+        })
+    }
+}
+
+/// Response body holding a request's concurrency permits until it is finished or dropped.
+struct PermitBody {
+    /// Wrapped response body.
+    body: BoxBody,
+    /// Permits released when the body is dropped.
+    _permits: Vec<OwnedSemaphorePermit>,
+}
+
+impl http_body::Body for PermitBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.get_mut().body).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.get_mut().body).poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.body.size_hint()
+    }
+}
@@ -0,0 +1,158 @@
+//! Enabled gRPC methods of the lightwallet service.
+//!
+//! Requests for disabled methods are answered with `Status::unimplemented` before reaching the handler, so a
+//! deployment can e.g. serve a read-only endpoint with `SendTransaction` disabled. Requests to other services, such as
+//! gRPC reflection, are not filtered.
+
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use tonic::{body::BoxBody, Status};
+use tower::{Layer, Service};
+
+/// Full name of the lightwallet gRPC service, the first segment of its request paths.
+pub const COMPACT_TX_STREAMER_SERVICE: &str = "cash.z.wallet.sdk.rpc.CompactTxStreamer";
+
+/// Methods of the lightwallet gRPC service.
+pub const GRPC_METHODS: [&str; 22] = [
+    "GetLatestBlock",
+    "GetBlock",
+    "GetBlockNullifiers",
+    "GetBlockRange",
+    "GetBlockRangeNullifiers",
+    "GetTransaction",
+    "SendTransaction",
+    "GetTaddressTxids",
+    "GetTaddressBalance",
+    "GetTaddressBalanceStream",
+    "GetMempoolTx",
+    "GetMempoolStream",
+    "GetTaddressMempoolStream",
+    "GetTreeState",
+    "GetLatestTreeState",
+    "GetSubtreeRoots",
+    "GetAddressUtxos",
+    "GetAddressUtxosStream",
+    "GetLightdInfo",
+    "EstimateFee",
+    "GetServerCapabilities",
+    "Ping",
+];
+
+/// Enabled methods of the lightwallet gRPC service.
+///
+/// A method is enabled if it is in the allowlist, when one is set, and not in the denylist. All methods are enabled
+/// by default.
+#[derive(Debug, Clone, Default)]
+pub struct MethodFilter {
+    /// Methods enabled, every method is enabled if None.
+    allowed: Option<Arc<HashSet<String>>>,
+    /// Methods disabled, takes precedence over `allowed`.
+    denied: Arc<HashSet<String>>,
+}
+
+impl MethodFilter {
+    /// Returns a filter enabling the methods in `allowed`, or every method if None, except those in `denied`.
+    pub fn new(allowed: Option<HashSet<String>>, denied: HashSet<String>) -> Self {
+        MethodFilter {
+            allowed: allowed.map(Arc::new),
+            denied: Arc::new(denied),
+        }
+    }
+
+    /// Returns true if requests for `method`, e.g. `"SendTransaction"`, are served.
+    pub fn is_enabled(&self, method: &str) -> bool {
+        !self.denied.contains(method)
+            && self
+                .allowed
+                .as_ref()
+                .map_or(true, |allowed| allowed.contains(method))
+    }
+
+    /// Returns the enabled methods of [`GRPC_METHODS`], in order.
+    pub fn enabled_methods(&self) -> Vec<String> {
+        GRPC_METHODS
+            .iter()
+            .filter(|method| self.is_enabled(method))
+            .map(|method| method.to_string())
+            .collect()
+    }
+
+    /// Returns true if every method is enabled, in which case the filter layer is a no-op.
+    pub fn allows_all(&self) -> bool {
+        self.allowed.is_none() && self.denied.is_empty()
+    }
+
+    /// Returns true if the request at `path` is served, only lightwallet service paths are filtered.
+    fn is_path_enabled(&self, path: &str) -> bool {
+        match path.strip_prefix('/').and_then(|path| path.split_once('/')) {
+            Some((service, method)) if service == COMPACT_TX_STREAMER_SERVICE => {
+                self.is_enabled(method)
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns a layer rejecting requests for disabled methods.
+    pub fn layer(&self) -> MethodFilterLayer {
+        MethodFilterLayer {
+            filter: self.clone(),
+        }
+    }
+}
+
+/// Tower layer wrapping a gRPC service in a [`MethodFilterService`].
+#[derive(Debug, Clone)]
+pub struct MethodFilterLayer {
+    /// Enabled methods.
+    filter: MethodFilter,
+}
+
+impl<S> Layer<S> for MethodFilterLayer {
+    type Service = MethodFilterService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodFilterService {
+            inner,
+            filter: self.filter.clone(),
+        }
+    }
+}
+
+/// gRPC service answering requests for methods disabled by its [`MethodFilter`] with `Status::unimplemented`.
+#[derive(Debug, Clone)]
+pub struct MethodFilterService<S> {
+    /// Wrapped service.
+    inner: S,
+    /// Enabled methods.
+    filter: MethodFilter,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for MethodFilterService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<BoxBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        if !self.filter.is_path_enabled(request.uri().path()) {
+            let status = Status::unimplemented(format!(
+                "Method {} is disabled on this server.",
+                request.uri().path()
+            ));
+            return Box::pin(async move { Ok(status.to_http()) });
+        }
+        Box::pin(self.inner.call(request))
+    }
+}
@@ -2,9 +2,9 @@
 
 use crate::server::error::RequestError;
 use nym_sphinx_anonymous_replies::requests::AnonymousSenderTag;
-use std::time::SystemTime;
-use tokio::net::TcpStream;
-use zaino_nym::utils::read_nym_request_data;
+use std::{collections::HashSet, time::SystemTime};
+use tokio::net::{TcpStream, UnixStream};
+use zaino_nym::utils::{read_nym_request_data_with_limits, NymRequestLimits};
 
 /// Requests queuing metadata.
 #[derive(Debug, Clone)]
@@ -26,6 +26,14 @@ impl QueueData {
         }
     }
 
+    /// Creates a ZingoIndexerRequest from a gRPC service call, recieved by the gRPC server over a Unix domain socket.
+    pub fn new_from_uds(stream: UnixStream) -> Self {
+        ZingoIndexerRequest::UdsServerRequest(UdsServerRequest {
+            queuedata: QueueData::new(),
+            request: UdsRequest(stream),
+        })
+    }
+
     /// Increases the requeue attempts for the request.
     pub fn increase_requeues(&mut self) {
         self.requeue_attempts += 1;
@@ -84,6 +92,17 @@ impl TcpRequest {
     }
 }
 
+/// UnixStream holding an incoming gRPC request.
+#[derive(Debug)]
+pub struct UdsRequest(UnixStream);
+
+impl UdsRequest {
+    /// Returns the underlying UnixStream held by the request.
+    pub fn get_stream(self) -> UnixStream {
+        self.0
+    }
+}
+
 /// Requests originating from the Nym server.
 #[derive(Debug, Clone)]
 pub struct NymServerRequest {
@@ -112,6 +131,20 @@ impl TcpServerRequest {
     }
 }
 
+/// Requests originating from the Unix domain socket server.
+#[derive(Debug)]
+pub struct UdsServerRequest {
+    queuedata: QueueData,
+    request: UdsRequest,
+}
+
+impl UdsServerRequest {
+    /// Returns the underlying request.
+    pub fn get_request(self) -> UdsRequest {
+        self.request
+    }
+}
+
 /// Zingo-Indexer request, used by request queue.
 #[derive(Debug)]
 pub enum ZingoIndexerRequest {
@@ -119,12 +152,21 @@ pub enum ZingoIndexerRequest {
     NymServerRequest(NymServerRequest),
     /// Requests originating from the gRPC server.
     TcpServerRequest(TcpServerRequest),
+    /// Requests originating from the gRPC server over a Unix domain socket.
+    UdsServerRequest(UdsServerRequest),
 }
 
 impl ZingoIndexerRequest {
     /// Creates a ZingoIndexerRequest from an encoded gRPC service call, recieved by the Nym server.
-    pub fn new_from_nym(metadata: AnonymousSenderTag, bytes: &[u8]) -> Result<Self, RequestError> {
-        let (id, method, body) = read_nym_request_data(bytes)?;
+    ///
+    /// Requests for methods not in `allowed_methods`, or with a method name or body longer than `limits`, are rejected.
+    pub fn new_from_nym(
+        metadata: AnonymousSenderTag,
+        bytes: &[u8],
+        allowed_methods: &HashSet<String>,
+        limits: &NymRequestLimits,
+    ) -> Result<Self, RequestError> {
+        let (id, method, body) = read_nym_request_data_with_limits(bytes, allowed_methods, limits)?;
         Ok(ZingoIndexerRequest::NymServerRequest(NymServerRequest {
             queuedata: QueueData::new(),
             request: NymRequest {
@@ -146,11 +188,20 @@ impl ZingoIndexerRequest {
         })
     }
 
+    /// Creates a ZingoIndexerRequest from a gRPC service call, recieved by the gRPC server over a Unix domain socket.
+    pub fn new_from_uds(stream: UnixStream) -> Self {
+        ZingoIndexerRequest::UdsServerRequest(UdsServerRequest {
+            queuedata: QueueData::new(),
+            request: UdsRequest(stream),
+        })
+    }
+
     /// Increases the requeue attempts for the request.
     pub fn increase_requeues(&mut self) {
         match self {
             ZingoIndexerRequest::NymServerRequest(ref mut req) => req.queuedata.increase_requeues(),
             ZingoIndexerRequest::TcpServerRequest(ref mut req) => req.queuedata.increase_requeues(),
+            ZingoIndexerRequest::UdsServerRequest(ref mut req) => req.queuedata.increase_requeues(),
         }
     }
 
@@ -159,6 +210,7 @@ impl ZingoIndexerRequest {
         match self {
             ZingoIndexerRequest::NymServerRequest(ref req) => req.queuedata.duration(),
             ZingoIndexerRequest::TcpServerRequest(ref req) => req.queuedata.duration(),
+            ZingoIndexerRequest::UdsServerRequest(ref req) => req.queuedata.duration(),
         }
     }
 
@@ -167,6 +219,7 @@ impl ZingoIndexerRequest {
         match self {
             ZingoIndexerRequest::NymServerRequest(ref req) => req.queuedata.requeues(),
             ZingoIndexerRequest::TcpServerRequest(ref req) => req.queuedata.requeues(),
+            ZingoIndexerRequest::UdsServerRequest(ref req) => req.queuedata.requeues(),
         }
     }
 }
@@ -0,0 +1,317 @@
+//! Request logging for the gRPC server.
+//!
+//! When enabled each gRPC request is logged once it completes, with its method, peer, latency and status code.
+//! Streaming requests complete when their response stream ends, or when the client drops it. Request and response
+//! messages are never logged, handlers attach a [`LoggedPayload`] summary to responses whose payload should be
+//! recorded, e.g. the txid and length of a sent transaction in place of its raw bytes.
+
+use std::{
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use tonic::{body::BoxBody, Code, Status};
+use tower::{Layer, Service};
+
+use crate::server::error::ServerError;
+
+/// Maximum length of a logged payload summary, longer summaries are truncated.
+pub const MAX_LOGGED_PAYLOAD_LEN: usize = 128;
+
+/// Level request log entries are emitted at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RequestLogLevel {
+    /// Error level.
+    Error,
+    /// Warn level.
+    Warn,
+    /// Info level.
+    #[default]
+    Info,
+    /// Debug level.
+    Debug,
+    /// Trace level.
+    Trace,
+}
+
+impl FromStr for RequestLogLevel {
+    type Err = ServerError;
+
+    /// Parses a level name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(RequestLogLevel::Error),
+            "warn" => Ok(RequestLogLevel::Warn),
+            "info" => Ok(RequestLogLevel::Info),
+            "debug" => Ok(RequestLogLevel::Debug),
+            "trace" => Ok(RequestLogLevel::Trace),
+            _ => Err(ServerError::ServerConfigError(format!(
+                "Unknown request log level: {}, expected one of error, warn, info, debug or trace.",
+                s
+            ))),
+        }
+    }
+}
+
+/// Summary of a response payload recorded in the request log in place of the payload itself.
+///
+/// Handlers attach a summary to their response's extensions, summaries longer than [`MAX_LOGGED_PAYLOAD_LEN`]
+/// are truncated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedPayload(String);
+
+impl LoggedPayload {
+    /// Returns a summary holding `summary`, truncated to [`MAX_LOGGED_PAYLOAD_LEN`].
+    pub fn new(summary: impl Into<String>) -> Self {
+        let mut summary = summary.into();
+        if summary.len() > MAX_LOGGED_PAYLOAD_LEN {
+            let mut end = MAX_LOGGED_PAYLOAD_LEN;
+            while !summary.is_char_boundary(end) {
+                end -= 1;
+            }
+            summary.truncate(end);
+            summary.push_str("...");
+        }
+        LoggedPayload(summary)
+    }
+
+    /// Returns the summary of a sent transaction, its txid and length in bytes rather than its raw bytes.
+    pub fn transaction(txid: &str, len: usize) -> Self {
+        LoggedPayload::new(format!("txid={} len={}", txid, len))
+    }
+
+    /// Returns the summary.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Logs gRPC requests at a configured level, logging is disabled if no level is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestLogger {
+    /// Level entries are emitted at, None if logging is disabled.
+    level: Option<RequestLogLevel>,
+}
+
+impl RequestLogger {
+    /// Returns a logger emitting entries at `level`, logging is disabled if None.
+    pub fn new(level: Option<RequestLogLevel>) -> Self {
+        RequestLogger { level }
+    }
+
+    /// Returns the level entries are emitted at, None if logging is disabled.
+    pub fn level(&self) -> Option<RequestLogLevel> {
+        self.level
+    }
+
+    /// Returns a layer logging the requests of a single connection from `peer`, a new layer must be used for each
+    /// connection.
+    pub fn connection_layer(&self, peer: impl Into<String>) -> RequestLogLayer {
+        RequestLogLayer {
+            level: self.level,
+            peer: Arc::from(peer.into()),
+        }
+    }
+}
+
+/// Tower layer wrapping a gRPC service in a [`RequestLog`] for a single connection.
+#[derive(Debug, Clone)]
+pub struct RequestLogLayer {
+    /// Level entries are emitted at, None if logging is disabled.
+    level: Option<RequestLogLevel>,
+    /// Address of the connection's peer.
+    peer: Arc<str>,
+}
+
+impl<S> Layer<S> for RequestLogLayer {
+    type Service = RequestLog<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLog {
+            inner,
+            level: self.level,
+            peer: self.peer.clone(),
+        }
+    }
+}
+
+/// gRPC service logging each request once its response has been sent.
+#[derive(Debug, Clone)]
+pub struct RequestLog<S> {
+    /// Wrapped service.
+    inner: S,
+    /// Level entries are emitted at, None if logging is disabled.
+    level: Option<RequestLogLevel>,
+    /// Address of the connection's peer.
+    peer: Arc<str>,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for RequestLog<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<BoxBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let level = match self.level {
+            Some(level) => level,
+            None => return Box::pin(self.inner.call(request)),
+        };
+        let mut entry = RequestLogEntry {
+            level,
+            method: request.uri().path().to_string(),
+            peer: self.peer.clone(),
+            payload: None,
+            start: Instant::now(),
+        };
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let (parts, body) = response.await?.into_parts();
+            entry.payload = parts.extensions.get::<LoggedPayload>().cloned();
+            // NOTE: Requests failing before a response message is sent carry their status in the headers,
+            // others in the trailers sent after the response body.
+            if let Some(code) = grpc_code(&parts.headers) {
+                entry.emit(code);
+                return Ok(http::Response::from_parts(parts, body));
+            }
+            Ok(http::Response::from_parts(
+                parts,
+                tonic::body::boxed(LogBody {
+                    body,
+                    entry: Some(entry),
+                }),
+            ))
+        })
+    }
+}
+
+/// Returns the gRPC status code held in `headers`, None if there is no status.
+fn grpc_code(headers: &http::HeaderMap) -> Option<Code> {
+    headers
+        .get("grpc-status")
+        .and_then(|status| status.to_str().ok())
+        .and_then(|status| status.parse::<i32>().ok())
+        .map(Code::from_i32)
+}
+
+/// A request waiting to be logged.
+struct RequestLogEntry {
+    /// Level the entry is emitted at.
+    level: RequestLogLevel,
+    /// gRPC method path.
+    method: String,
+    /// Address of the connection's peer.
+    peer: Arc<str>,
+    /// Summary of the response payload, if attached by the handler.
+    payload: Option<LoggedPayload>,
+    /// Time the request was received.
+    start: Instant,
+}
+
+/// Emits a request log event at the given `tracing` macro's level.
+macro_rules! emit_at {
+    ($level:ident, $entry:expr, $code:expr) => {
+        tracing::$level!(
+            method = %$entry.method,
+            peer = %$entry.peer,
+            latency_ms = $entry.start.elapsed().as_millis() as u64,
+            code = ?$code,
+            payload = $entry.payload.as_ref().map_or("", LoggedPayload::as_str),
+            "gRPC request completed"
+        )
+    };
+}
+
+impl RequestLogEntry {
+    /// Logs the request as completed with `code`.
+    fn emit(&self, code: Code) {
+        match self.level {
+            RequestLogLevel::Error => emit_at!(error, self, code),
+            RequestLogLevel::Warn => emit_at!(warn, self, code),
+            RequestLogLevel::Info => emit_at!(info, self, code),
+            RequestLogLevel::Debug => emit_at!(debug, self, code),
+            RequestLogLevel::Trace => emit_at!(trace, self, code),
+        }
+    }
+}
+
+/// Response body logging its request once the trailers have been sent, or when dropped before completing.
+struct LogBody {
+    /// Wrapped response body.
+    body: BoxBody,
+    /// Entry logged when the body completes, None once logged.
+    entry: Option<RequestLogEntry>,
+}
+
+impl LogBody {
+    /// Logs the request as completed with `code`, if not already logged.
+    fn complete(&mut self, code: Code) {
+        if let Some(entry) = self.entry.take() {
+            entry.emit(code);
+        }
+    }
+}
+
+impl http_body::Body for LogBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.body).poll_data(cx);
+        if let Poll::Ready(Some(Err(status))) = &poll {
+            this.complete(status.code());
+        }
+        poll
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.body).poll_trailers(cx);
+        match &poll {
+            Poll::Ready(Ok(trailers)) => {
+                let code = trailers
+                    .as_ref()
+                    .and_then(grpc_code)
+                    .unwrap_or(Code::Unknown);
+                this.complete(code);
+            }
+            Poll::Ready(Err(status)) => this.complete(status.code()),
+            Poll::Pending => {}
+        }
+        poll
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.body.size_hint()
+    }
+}
+
+impl Drop for LogBody {
+    fn drop(&mut self) {
+        // The response was dropped before its trailers were sent, e.g. a client cancelling a stream.
+        self.complete(Code::Cancelled);
+    }
+}
@@ -0,0 +1,40 @@
+//! TLS configuration for the gRPC server.
+
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
+
+use crate::server::error::ServerError;
+
+/// Loads the PEM encoded server certificate and key, and optional client CA bundle for mutual TLS, into a ServerTlsConfig.
+///
+/// The config is applied to a server builder before returning so bad certificates fail at startup rather than on the first connection.
+pub fn load_tls_config(
+    cert_path: &str,
+    key_path: &str,
+    client_ca_path: Option<&str>,
+) -> Result<ServerTlsConfig, ServerError> {
+    let cert = read_pem(cert_path, "certificate")?;
+    let key = read_pem(key_path, "key")?;
+    let mut tls_config = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+    if let Some(ca_path) = client_ca_path {
+        let client_ca = read_pem(ca_path, "client CA bundle")?;
+        tls_config = tls_config.client_ca_root(Certificate::from_pem(client_ca));
+    }
+    Server::builder()
+        .tls_config(tls_config.clone())
+        .map_err(|e| ServerError::TlsConfigError(format!("Invalid TLS configuration: {}", e)))?;
+    Ok(tls_config)
+}
+
+/// Reads a PEM file, returning an error if the file is missing or does not hold PEM data.
+fn read_pem(path: &str, kind: &str) -> Result<Vec<u8>, ServerError> {
+    let pem = std::fs::read(path).map_err(|e| {
+        ServerError::TlsConfigError(format!("Failed to read TLS {} at {}: {}", kind, path, e))
+    })?;
+    if !pem.windows(11).any(|window| window == b"-----BEGIN ") {
+        return Err(ServerError::TlsConfigError(format!(
+            "TLS {} at {} is not PEM encoded.",
+            kind, path
+        )));
+    }
+    Ok(pem)
+}
@@ -7,14 +7,26 @@ use std::sync::{
 
 use http::Uri;
 use nym_sphinx_anonymous_replies::requests::AnonymousSenderTag;
-use tonic::transport::Server;
+use tonic::transport::{Server, ServerTlsConfig};
+use tonic_reflection::server::{ServerReflection, ServerReflectionServer};
+use zaino_fetch::{
+    chain::blockcache::BlockCache,
+    jsonrpc::{connection_pool::ConnectionPool, connector::Auth, validator_pool::ValidatorPool},
+    primitives::chain::NetworkKind,
+    task::spawn_named,
+};
+use zaino_state::fetch::{FetchService, NoLocalState};
 
 use crate::{
     rpc::GrpcClient,
     server::{
+        capabilities::ServerFeatures,
         error::{QueueError, WorkerError},
+        limit::RequestLimiter,
+        method_filter::MethodFilter,
         queue::{QueueReceiver, QueueSender},
         request::ZingoIndexerRequest,
+        request_log::RequestLogger,
         AtomicStatus,
     },
 };
@@ -25,6 +37,14 @@ use zaino_proto::proto::service::compact_tx_streamer_server::CompactTxStreamerSe
 #[cfg(feature = "nym_poc")]
 use zcash_client_backend::proto::service::compact_tx_streamer_server::CompactTxStreamerServer;
 
+/// Returns the gRPC reflection service, describing the lightwallet service.
+fn reflection_service(
+) -> Result<ServerReflectionServer<impl ServerReflection>, tonic_reflection::server::Error> {
+    tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(zaino_proto::proto::FILE_DESCRIPTOR_SET)
+        .build()
+}
+
 /// A queue working is the entity that takes requests from the queue and processes them.
 ///
 /// TODO: - Add JsonRpcConnector to worker and pass to underlying RPC services.
@@ -41,6 +61,16 @@ pub(crate) struct Worker {
     nym_response_queue: QueueSender<(Vec<u8>, AnonymousSenderTag)>,
     /// gRPC client used for processing requests received over http.
     grpc_client: GrpcClient,
+    /// TLS config used for requests received over http, served as plaintext if None.
+    tls_config: Option<ServerTlsConfig>,
+    /// Serve the gRPC reflection service alongside the lightwallet service.
+    reflection: bool,
+    /// Concurrency and rate limits applied to requests received over http.
+    request_limiter: RequestLimiter,
+    /// Logs requests received over http.
+    request_logger: RequestLogger,
+    /// Enabled methods of requests received over http.
+    method_filter: MethodFilter,
     /// Thread safe worker status.
     atomic_status: AtomicStatus,
     /// Represents the Online status of the Worker.
@@ -56,12 +86,34 @@ impl Worker {
         nym_response_queue: QueueSender<(Vec<u8>, AnonymousSenderTag)>,
         lightwalletd_uri: Uri,
         zebrad_uri: Uri,
+        auth: Option<Auth>,
+        validator_pool: Option<ValidatorPool>,
+        connection_pool: ConnectionPool,
+        network: NetworkKind,
+        block_cache: BlockCache,
+        fetch_service: Arc<FetchService<NoLocalState>>,
+        block_range_prefetch: usize,
+        tls_config: Option<ServerTlsConfig>,
+        reflection: bool,
+        request_limiter: RequestLimiter,
+        request_logger: RequestLogger,
+        method_filter: MethodFilter,
+        features: ServerFeatures,
         atomic_status: AtomicStatus,
         online: Arc<AtomicBool>,
     ) -> Self {
         let grpc_client = GrpcClient {
             lightwalletd_uri,
             zebrad_uri,
+            auth,
+            validator_pool,
+            connection_pool,
+            network,
+            block_cache,
+            fetch_service,
+            block_range_prefetch,
+            features,
+            method_filter: method_filter.clone(),
             online: online.clone(),
         };
         Worker {
@@ -70,6 +122,11 @@ impl Worker {
             requeue,
             nym_response_queue,
             grpc_client,
+            tls_config,
+            reflection,
+            request_limiter,
+            request_logger,
+            method_filter,
             atomic_status,
             online,
         }
@@ -79,10 +136,15 @@ impl Worker {
     ///
     /// TODO: Add requeue logic for node errors.
     pub(crate) async fn serve(self) -> tokio::task::JoinHandle<Result<(), WorkerError>> {
-        tokio::task::spawn(async move {
+        spawn_named(&format!("worker_{}", self._worker_id), async move {
             // NOTE: This interval may need to be reduced or removed / moved once scale testing begins.
             let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(100));
             let svc = CompactTxStreamerServer::new(self.grpc_client.clone());
+            let reflection_svc = if self.reflection {
+                Some(reflection_service()?)
+            } else {
+                None
+            };
             // TODO: create tonic server here for use within loop.
             self.atomic_status.store(1);
             loop {
@@ -98,7 +160,34 @@ impl Worker {
                                 self.atomic_status.store(2);
                                     match request {
                                         ZingoIndexerRequest::TcpServerRequest(request) => {
-                                            Server::builder().add_service(svc.clone())
+                                            let stream = request.get_request().get_stream();
+                                            let peer = stream
+                                                .peer_addr()
+                                                .map_or_else(|_| "unknown".to_string(), |addr| addr.to_string());
+                                            let mut server_builder = Server::builder();
+                                            if let Some(tls_config) = self.tls_config.clone() {
+                                                server_builder = server_builder.tls_config(tls_config)?;
+                                            }
+                                            // NOTE: The request logger is the outer layer so requests rejected by the limiter or
+                                            // the method filter are also logged, disabled methods do not use up request limits.
+                                            server_builder.layer(self.request_logger.connection_layer(peer))
+                                                .layer(self.method_filter.layer())
+                                                .layer(self.request_limiter.connection_layer())
+                                                .add_service(svc.clone())
+                                                .add_optional_service(reflection_svc.clone())
+                                                .serve_with_incoming( async_stream::stream! {
+                                                    yield Ok::<_, std::io::Error>(stream);
+                                                }
+                                            )
+                                            .await?;
+                                        }
+                                        ZingoIndexerRequest::UdsServerRequest(request) => {
+                                            // Local IPC is served as plaintext.
+                                            Server::builder().layer(self.request_logger.connection_layer("unix"))
+                                                .layer(self.method_filter.layer())
+                                                .layer(self.request_limiter.connection_layer())
+                                                .add_service(svc.clone())
+                                                .add_optional_service(reflection_svc.clone())
                                                 .serve_with_incoming( async_stream::stream! {
                                                     yield Ok::<_, std::io::Error>(
                                                         request.get_request().get_stream()
@@ -242,6 +331,19 @@ impl WorkerPool {
         nym_response_queue: QueueSender<(Vec<u8>, AnonymousSenderTag)>,
         lightwalletd_uri: Uri,
         zebrad_uri: Uri,
+        auth: Option<Auth>,
+        validator_pool: Option<ValidatorPool>,
+        connection_pool: ConnectionPool,
+        network: NetworkKind,
+        block_cache: BlockCache,
+        fetch_service: Arc<FetchService<NoLocalState>>,
+        block_range_prefetch: usize,
+        tls_config: Option<ServerTlsConfig>,
+        reflection: bool,
+        request_limiter: RequestLimiter,
+        request_logger: RequestLogger,
+        method_filter: MethodFilter,
+        features: ServerFeatures,
         status: WorkerPoolStatus,
         online: Arc<AtomicBool>,
     ) -> Self {
@@ -255,6 +357,19 @@ impl WorkerPool {
                     nym_response_queue.clone(),
                     lightwalletd_uri.clone(),
                     zebrad_uri.clone(),
+                    auth.clone(),
+                    validator_pool.clone(),
+                    connection_pool.clone(),
+                    network,
+                    block_cache.clone(),
+                    fetch_service.clone(),
+                    block_range_prefetch,
+                    tls_config.clone(),
+                    reflection,
+                    request_limiter.clone(),
+                    request_logger,
+                    method_filter.clone(),
+                    features.clone(),
                     status.statuses[workers.len()].clone(),
                     online.clone(),
                 )
@@ -296,6 +411,19 @@ impl WorkerPool {
                     self.workers[0].nym_response_queue.clone(),
                     self.workers[0].grpc_client.lightwalletd_uri.clone(),
                     self.workers[0].grpc_client.zebrad_uri.clone(),
+                    self.workers[0].grpc_client.auth.clone(),
+                    self.workers[0].grpc_client.validator_pool.clone(),
+                    self.workers[0].grpc_client.connection_pool.clone(),
+                    self.workers[0].grpc_client.network,
+                    self.workers[0].grpc_client.block_cache.clone(),
+                    self.workers[0].grpc_client.fetch_service.clone(),
+                    self.workers[0].grpc_client.block_range_prefetch,
+                    self.workers[0].tls_config.clone(),
+                    self.workers[0].reflection,
+                    self.workers[0].request_limiter.clone(),
+                    self.workers[0].request_logger,
+                    self.workers[0].method_filter.clone(),
+                    self.workers[0].grpc_client.features.clone(),
                     self.status.statuses[worker_index].clone(),
                     self.online.clone(),
                 )
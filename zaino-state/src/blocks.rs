@@ -0,0 +1,275 @@
+//! Zaino-State new block notifier, broadcasts each block added to the validator's best chain to subscribers.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::broadcast;
+use zaino_fetch::{
+    chain::{
+        blockcache::MAX_REORG_DEPTH,
+        reorg::{ChainTip, ChainTipTracker},
+    },
+    jsonrpc::{
+        connector::JsonRpcConnector, error::JsonRpcConnectorError, response::GetBlockResponse,
+    },
+    primitives::{block::BlockHash, height::ChainHeight},
+    task::spawn_named,
+};
+
+use crate::{
+    status::{ChainTipCache, SyncProgress, SyncProgressCache, TipInfo},
+    sync::{SyncReporter, SyncReporterConfig, SyncStatusCache},
+};
+
+/// A block added to the best chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewBlock {
+    /// Height of the block.
+    pub height: u32,
+    /// Hash of the block.
+    pub hash: BlockHash,
+    /// Unix epoch time when the block was mined.
+    pub time: i64,
+}
+
+/// Polling interval, channel capacity, reorg confirmation depth and sync progress log interval of a [`BlockNotifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockNotifierConfig {
+    /// Interval between polls of the validator's chain tip.
+    pub poll_interval: Duration,
+    /// Capacity of the broadcast channel, subscribers lagging further behind miss blocks.
+    pub channel_capacity: usize,
+    /// Number of blocks below the tip tracked for reorgs.
+    pub reorg_confirmation_depth: u32,
+    /// Minimum interval between two progress lines logged during the initial sync.
+    pub progress_log_interval: Duration,
+}
+
+impl Default for BlockNotifierConfig {
+    fn default() -> Self {
+        BlockNotifierConfig {
+            poll_interval: Duration::from_secs(1),
+            channel_capacity: 64,
+            reorg_confirmation_depth: MAX_REORG_DEPTH,
+            progress_log_interval: SyncReporterConfig::default().log_interval,
+        }
+    }
+}
+
+/// Polls the validator's chain tip and broadcasts a [`NewBlock`] for each block added to the best chain, in height order.
+///
+/// On a reorg the blocks of the new chain above the fork point are sent once the new chain has been synced,
+/// blocks of the abandoned chain are never sent. Subscribers that fall more than
+/// [`BlockNotifierConfig::channel_capacity`] blocks behind receive a [`broadcast::error::RecvError::Lagged`]
+/// error and should re-query the chain tip.
+pub struct BlockNotifier {
+    /// Broadcast channel used to send new blocks to subscribers.
+    sender: broadcast::Sender<NewBlock>,
+    /// Best chain tip synced by the most recent successful poll.
+    chain_tip: ChainTipCache,
+    /// Sync progress recorded by the most recent successful poll.
+    sync_progress: SyncProgressCache,
+    /// Initial sync status, rate and ETA recorded by the most recent successful poll.
+    sync_status: SyncStatusCache,
+    /// Handle of the polling task.
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl BlockNotifier {
+    /// Spawns a block notifier querying `json_rpc` while `online` is true.
+    ///
+    /// The chain tip seen by the first poll is not sent, only blocks added after it.
+    pub fn spawn(
+        json_rpc: JsonRpcConnector,
+        config: BlockNotifierConfig,
+        online: Arc<AtomicBool>,
+    ) -> Self {
+        let (sender, _) = broadcast::channel(config.channel_capacity.max(1));
+        let task_sender = sender.clone();
+        let chain_tip = ChainTipCache::default();
+        let task_chain_tip = chain_tip.clone();
+        let sync_progress = SyncProgressCache::default();
+        let task_sync_progress = sync_progress.clone();
+        let mut sync_reporter = SyncReporter::new(SyncReporterConfig {
+            log_interval: config.progress_log_interval,
+            ..SyncReporterConfig::default()
+        });
+        let sync_status = sync_reporter.status();
+        let handle = spawn_named("block_notifier", async move {
+            let mut tracker = ChainTipTracker::new()
+                .with_reorg_confirmation_depth(config.reorg_confirmation_depth);
+            // Height of the last block sent, or of the first tip seen.
+            let mut notified_height: Option<u32> = None;
+            let mut interval = tokio::time::interval(config.poll_interval);
+            while online.load(Ordering::SeqCst) {
+                interval.tick().await;
+                let (tip, estimated_height) = match json_rpc.get_blockchain_info().await {
+                    Ok(blockchain_info) => (
+                        ChainTip {
+                            height: blockchain_info.blocks.0,
+                            hash: blockchain_info.best_block_hash,
+                        },
+                        blockchain_info.estimated_height.0,
+                    ),
+                    Err(e) => {
+                        eprintln!("Failed to fetch chain tip: {}.", e);
+                        continue;
+                    }
+                };
+                let fetch_hash = |height: u32| {
+                    let json_rpc = &json_rpc;
+                    async move {
+                        match json_rpc.get_block(height, Some(1)).await? {
+                            GetBlockResponse::Object { hash, .. } => Ok(hash.0),
+                            GetBlockResponse::Raw(_) => Err(JsonRpcConnectorError::new(
+                                "Received raw block type, this should not be possible here.",
+                            )),
+                        }
+                    }
+                };
+                let reorg = match tracker.sync(tip, fetch_hash).await {
+                    Ok(reorg) => {
+                        task_chain_tip.record(tip);
+                        task_sync_progress.record(SyncProgress::new(tip.height, estimated_height));
+                        sync_reporter.record(
+                            tip.height,
+                            estimated_height,
+                            std::time::Instant::now(),
+                        );
+                        reorg
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to sync chain tip: {}.", e);
+                        continue;
+                    }
+                };
+                let from_height = match (notified_height, reorg) {
+                    (None, _) => {
+                        notified_height = Some(tip.height);
+                        record_tip_info(&json_rpc, &task_chain_tip, tip).await;
+                        continue;
+                    }
+                    (Some(height), Some(event)) => height.min(event.fork_height),
+                    (Some(height), None) => height,
+                };
+                notified_height = Some(
+                    notify_blocks(
+                        &json_rpc,
+                        &tracker,
+                        &task_sender,
+                        &task_chain_tip,
+                        from_height,
+                        tip.height,
+                    )
+                    .await,
+                );
+                if !task_chain_tip
+                    .tip_info()
+                    .is_some_and(|tip_info| tip_info.hash == tip.hash)
+                {
+                    record_tip_info(&json_rpc, &task_chain_tip, tip).await;
+                }
+            }
+        });
+        BlockNotifier {
+            sender,
+            chain_tip,
+            sync_progress,
+            sync_status,
+            handle,
+        }
+    }
+
+    /// Returns a receiver of blocks added to the best chain after this call.
+    pub fn subscribe_blocks(&self) -> broadcast::Receiver<NewBlock> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the best chain tip synced by the most recent successful poll.
+    pub fn chain_tip(&self) -> ChainTipCache {
+        self.chain_tip.clone()
+    }
+
+    /// Returns the sync progress recorded by the most recent successful poll.
+    pub fn sync_progress(&self) -> SyncProgressCache {
+        self.sync_progress.clone()
+    }
+
+    /// Returns the initial sync status, rate and ETA recorded by the most recent successful poll.
+    pub fn sync_status(&self) -> SyncStatusCache {
+        self.sync_status.clone()
+    }
+
+    /// Stops the polling task.
+    pub fn shutdown(self) {
+        self.handle.abort();
+    }
+}
+
+/// Fetches the block at `tip` and records its time in `chain_tip`, unless the block no longer matches the tip.
+async fn record_tip_info(json_rpc: &JsonRpcConnector, chain_tip: &ChainTipCache, tip: ChainTip) {
+    match json_rpc.get_block(tip.height, Some(1)).await {
+        Ok(GetBlockResponse::Object { hash, time, .. }) if hash.0 == tip.hash => {
+            chain_tip.record_tip_info(TipInfo {
+                height: tip.height,
+                hash: tip.hash,
+                time: time.unwrap_or_default(),
+            });
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to fetch tip block {}: {}.", tip.height, e),
+    }
+}
+
+/// Sends the blocks above `from_height` up to `tip_height`, returns the height of the last block sent.
+///
+/// The block at `tip_height` is also recorded as the tip block in `chain_tip`. Stops early if a block no longer
+/// matches the chain synced by `tracker`, the remaining blocks are sent once the next poll has synced the new chain.
+async fn notify_blocks(
+    json_rpc: &JsonRpcConnector,
+    tracker: &ChainTipTracker,
+    sender: &broadcast::Sender<NewBlock>,
+    chain_tip: &ChainTipCache,
+    from_height: u32,
+    tip_height: u32,
+) -> u32 {
+    for height in from_height.saturating_add(1)..=tip_height {
+        let last_sent = ChainHeight(height).saturating_sub(1).0;
+        let block = match json_rpc.get_block(height, Some(1)).await {
+            Ok(GetBlockResponse::Object { hash, time, .. }) => NewBlock {
+                height,
+                hash: hash.0,
+                time: time.unwrap_or_default(),
+            },
+            Ok(GetBlockResponse::Raw(_)) => {
+                eprintln!("Received raw block type, this should not be possible here.");
+                return last_sent;
+            }
+            Err(e) => {
+                eprintln!("Failed to fetch new block {}: {}.", height, e);
+                return last_sent;
+            }
+        };
+        if tracker
+            .hash_at(height)
+            .is_some_and(|tracked_hash| tracked_hash != block.hash)
+        {
+            return last_sent;
+        }
+        if height == tip_height {
+            chain_tip.record_tip_info(TipInfo {
+                height,
+                hash: block.hash,
+                time: block.time,
+            });
+        }
+        // NOTE: Send only fails when there are no active subscribers, blocks are dropped in that case.
+        let _ = sender.send(block);
+    }
+    tip_height.max(from_height)
+}
@@ -0,0 +1,247 @@
+//! Zaino-State in-memory caches of confirmed transactions, keyed by txid, and of the node's `getinfo` response.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use zaino_fetch::{
+    chain::reorg::ReorgEvent,
+    jsonrpc::response::{GetBlockHash, GetInfoResponse, GetTransactionResponse},
+    primitives::transaction::SerializedTransaction,
+};
+
+use crate::status::TransactionCacheStatus;
+
+/// A confirmed transaction with its block context.
+#[derive(Debug)]
+struct CachedTransaction {
+    /// The raw transaction.
+    hex: SerializedTransaction,
+    /// Height of the block containing the transaction.
+    height: u32,
+    /// Hash of the block containing the transaction.
+    block_hash: Option<GetBlockHash>,
+    /// Time of the block containing the transaction.
+    block_time: Option<i64>,
+    /// Tick of the last access.
+    last_used: u64,
+}
+
+/// Cached transactions with their recency index.
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Cached transactions keyed by lowercase txid.
+    transactions: HashMap<String, CachedTransaction>,
+    /// Txids keyed by the tick of their last access, least recently used first.
+    recency: BTreeMap<u64, String>,
+    /// Access counter used to order transactions by recency.
+    tick: u64,
+    /// Highest chain tip observed, used to count the confirmations of cached transactions.
+    tip_height: u32,
+}
+
+impl CacheState {
+    /// Removes the transaction with the given txid from the transaction and recency indexes.
+    fn remove(&mut self, txid: &str) {
+        if let Some(transaction) = self.transactions.remove(txid) {
+            self.recency.remove(&transaction.last_used);
+        }
+    }
+}
+
+/// Bounded LRU cache of confirmed transactions, keyed by txid.
+///
+/// Only transactions with a confirmed height are held, mempool transactions are never cached.
+/// Confirmations of a cache hit are counted from the highest chain tip observed by the cache.
+#[derive(Debug)]
+pub struct TransactionCache {
+    /// Maximum number of transactions held.
+    capacity: usize,
+    /// Cached transactions.
+    state: Mutex<CacheState>,
+    /// Hit, miss and eviction counts.
+    status: TransactionCacheStatus,
+}
+
+impl TransactionCache {
+    /// Returns an empty cache holding at most `capacity` transactions, caching is disabled if `capacity` is 0.
+    pub fn new(capacity: usize) -> Self {
+        TransactionCache {
+            capacity,
+            state: Mutex::new(CacheState::default()),
+            status: TransactionCacheStatus::default(),
+        }
+    }
+
+    /// Returns the cache's hit, miss and eviction counts.
+    pub fn status(&self) -> TransactionCacheStatus {
+        self.status.clone()
+    }
+
+    /// Returns the cached transaction with the given txid, in the form requested by `verbose`.
+    pub fn get(&self, txid_hex: &str, verbose: Option<u8>) -> Option<GetTransactionResponse> {
+        let transaction = match self.state.lock() {
+            Ok(mut state) => {
+                state.tick += 1;
+                let tick = state.tick;
+                let tip_height = state.tip_height;
+                match state.transactions.get_mut(&txid_hex.to_lowercase()) {
+                    Some(transaction) => {
+                        let last_used = std::mem::replace(&mut transaction.last_used, tick);
+                        let response = match verbose {
+                            Some(verbosity) if verbosity != 0 => GetTransactionResponse::Object {
+                                hex: transaction.hex.clone(),
+                                height: transaction.height as i32,
+                                confirmations: tip_height.saturating_sub(transaction.height) + 1,
+                                block_hash: transaction.block_hash,
+                                block_time: transaction.block_time,
+                                expiry_height: None,
+                                mempool_time: None,
+                            },
+                            _ => GetTransactionResponse::Raw(transaction.hex.clone()),
+                        };
+                        state.recency.remove(&last_used);
+                        state.recency.insert(tick, txid_hex.to_lowercase());
+                        Some(response)
+                    }
+                    None => None,
+                }
+            }
+            Err(_) => None,
+        };
+        self.status.record_lookup(transaction.is_some());
+        transaction
+    }
+
+    /// Adds a transaction as most recently used if it has a confirmed height, evicting the least recently used
+    /// transactions if the cache is full.
+    pub fn insert(&self, txid_hex: &str, transaction: &GetTransactionResponse) {
+        let (hex, height, confirmations, block_hash, block_time) = match transaction {
+            GetTransactionResponse::Object {
+                hex,
+                height,
+                confirmations,
+                block_hash,
+                block_time,
+                ..
+            } if *height >= 0 && *confirmations > 0 => (
+                hex,
+                *height as u32,
+                *confirmations,
+                *block_hash,
+                *block_time,
+            ),
+            _ => return,
+        };
+        if self.capacity == 0 {
+            return;
+        }
+        if let Ok(mut state) = self.state.lock() {
+            let txid = txid_hex.to_lowercase();
+            state.remove(&txid);
+            state.tick += 1;
+            let tick = state.tick;
+            state.tip_height = state
+                .tip_height
+                .max(height.saturating_add(confirmations - 1));
+            state.recency.insert(tick, txid.clone());
+            state.transactions.insert(
+                txid,
+                CachedTransaction {
+                    hex: hex.clone(),
+                    height,
+                    block_hash,
+                    block_time,
+                    last_used: tick,
+                },
+            );
+            while state.transactions.len() > self.capacity {
+                let txid = match state.recency.first_key_value() {
+                    Some((_, txid)) => txid.clone(),
+                    None => break,
+                };
+                state.remove(&txid);
+                self.status.record_evictions(1);
+            }
+        }
+    }
+
+    /// Drops the transactions in blocks rolled back by `event`, returns the number of transactions removed.
+    ///
+    /// Transactions held in blocks above the fork height may have been dropped or mined at a different height.
+    pub fn handle_reorg(&self, event: &ReorgEvent) -> usize {
+        match self.state.lock() {
+            Ok(mut state) => {
+                let stale: Vec<String> = state
+                    .transactions
+                    .iter()
+                    .filter(|(_, transaction)| transaction.height > event.fork_height)
+                    .map(|(txid, _)| txid.clone())
+                    .collect();
+                for txid in stale.iter() {
+                    state.remove(txid);
+                }
+                state.tip_height = event.new_tip.height;
+                self.status.record_evictions(stale.len() as u64);
+                stale.len()
+            }
+            Err(_) => 0,
+        }
+    }
+}
+
+/// Cache of the node's `getinfo` response, which does not change while the node runs.
+///
+/// The response is refetched once it is older than the cache's TTL or after [`InfoCache::invalidate`].
+#[derive(Debug)]
+pub struct InfoCache {
+    /// Time a cached response is served for.
+    ttl: Duration,
+    /// Cached response with the time it was fetched.
+    entry: Mutex<Option<(Instant, GetInfoResponse)>>,
+}
+
+impl InfoCache {
+    /// Returns an empty cache serving responses for `ttl`, caching is disabled if `ttl` is zero.
+    pub fn new(ttl: Duration) -> Self {
+        InfoCache {
+            ttl,
+            entry: Mutex::new(None),
+        }
+    }
+
+    /// Returns the time a cached response is served for.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the cached response if it was fetched less than the TTL ago.
+    pub fn get(&self) -> Option<GetInfoResponse> {
+        match self.entry.lock() {
+            Ok(entry) => entry
+                .as_ref()
+                .filter(|(fetched, _)| fetched.elapsed() < self.ttl)
+                .map(|(_, info)| info.clone()),
+            Err(_) => None,
+        }
+    }
+
+    /// Caches a response fetched from the node.
+    pub fn insert(&self, info: &GetInfoResponse) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        if let Ok(mut entry) = self.entry.lock() {
+            *entry = Some((Instant::now(), info.clone()));
+        }
+    }
+
+    /// Drops the cached response, the next request is fetched from the node.
+    pub fn invalidate(&self) {
+        if let Ok(mut entry) = self.entry.lock() {
+            *entry = None;
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Hold error types for the FetchService and related functionality.
+
+use zaino_fetch::{chain::error::ParseError, jsonrpc::error::JsonRpcConnectorError};
+
+/// FetchService errors.
+#[derive(Debug, thiserror::Error)]
+pub enum StateServiceError {
+    /// Errors from the local chain state.
+    #[error("State error: {0}")]
+    StateError(String),
+
+    /// Errors from the JsonRPC fallback.
+    #[error("JsonRPC connector error: {0}")]
+    JsonRpcError(#[from] JsonRpcConnectorError),
+
+    /// Errors parsing data received from the node.
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
+
+    /// The request could not be answered from the local state and the JsonRPC fallback is disabled for the method.
+    #[error("Not found in state, JsonRPC fallback disabled for {0}")]
+    NotFound(&'static str),
+
+    /// The transaction's block does not hold the transaction, or its Merkle root does not match the block header.
+    #[error("Invalid Merkle proof for transaction {0}: {1}")]
+    InvalidMerkleProof(String, String),
+
+    /// Every slot of the upstream queue, of the given capacity, is held by a request waiting on the validator.
+    ///
+    /// Callers should answer with a resource exhausted status rather than retrying immediately.
+    #[error("Upstream queue full, {0} requests already waiting on the validator")]
+    UpstreamQueueFull(usize),
+}
+
+impl StateServiceError {
+    /// Maps StateServiceError to tonic::Status.
+    ///
+    /// Local state failures are reported as Internal with a generic message, node and parse errors keep the mapping of
+    /// their source error.
+    pub fn to_grpc_status(&self) -> tonic::Status {
+        match self {
+            StateServiceError::StateError(e) => {
+                eprintln!("Error occurred: {}.", e);
+                tonic::Status::internal("Failed to read local chain state")
+            }
+            StateServiceError::JsonRpcError(e) => e.to_grpc_status(),
+            StateServiceError::ParseError(e) => e.to_grpc_status(),
+            StateServiceError::NotFound(_) => tonic::Status::not_found(self.to_string()),
+            StateServiceError::InvalidMerkleProof(txid, _) => {
+                eprintln!("Error occurred: {}.", self);
+                tonic::Status::internal(format!("Invalid Merkle proof for transaction {}", txid))
+            }
+            StateServiceError::UpstreamQueueFull(_) => {
+                tonic::Status::resource_exhausted(self.to_string())
+            }
+        }
+    }
+}
+
+impl From<StateServiceError> for tonic::Status {
+    fn from(err: StateServiceError) -> Self {
+        err.to_grpc_status()
+    }
+}
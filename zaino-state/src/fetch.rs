@@ -0,0 +1,627 @@
+//! Zaino-State FetchService, serves requests from the local chain state with an opt-in JsonRPC fallback.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use tokio::sync::broadcast;
+use zaino_fetch::{
+    chain::{
+        frontier::TreeFrontiers, mempool::MempoolUpdate, merkle::MerkleBranch, reorg::ReorgEvent,
+    },
+    jsonrpc::{
+        connector::JsonRpcConnector,
+        response::{GetBlockResponse, GetInfoResponse, GetTransactionResponse, TxidsResponse},
+    },
+    primitives::{
+        block::BlockHash,
+        height::ChainHeight,
+        transaction::{SerializedTransaction, TransactionHash},
+    },
+    task::spawn_named,
+};
+
+use crate::{
+    blocks::{BlockNotifier, NewBlock},
+    cache::{InfoCache, TransactionCache},
+    error::StateServiceError,
+    mempool::{MempoolDump, MempoolPoller, MempoolView},
+    queue::{QueueSlot, UpstreamQueue},
+    status::{
+        ChainTipCache, FetchServiceStatus, MempoolPollStatus, ServedBy, TipInfo,
+        TransactionCacheStatus, UpstreamQueueStatus,
+    },
+};
+
+/// Local chain state queried by the [`FetchService`], e.g. zebra's `ReadStateService`.
+///
+/// Methods return `Ok(None)` when the request can not be answered from the local state.
+pub trait StateSource: Send + Sync {
+    /// Returns the transaction with the given txid if held in the local state.
+    fn get_raw_transaction(
+        &self,
+        txid_hex: &str,
+        verbose: Option<u8>,
+    ) -> impl Future<Output = Result<Option<GetTransactionResponse>, StateServiceError>> + Send;
+
+    /// Returns the txids of the mempool if the local state tracks the mempool.
+    fn get_raw_mempool(
+        &self,
+    ) -> impl Future<Output = Result<Option<TxidsResponse>, StateServiceError>> + Send;
+}
+
+/// A [`StateSource`] holding no local chain state, every request is answered by the [`FetchService`]'s JsonRPC
+/// fallbacks, see [`FetchService::from_node`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoLocalState;
+
+impl StateSource for NoLocalState {
+    async fn get_raw_transaction(
+        &self,
+        _txid_hex: &str,
+        _verbose: Option<u8>,
+    ) -> Result<Option<GetTransactionResponse>, StateServiceError> {
+        Ok(None)
+    }
+
+    async fn get_raw_mempool(&self) -> Result<Option<TxidsResponse>, StateServiceError> {
+        Ok(None)
+    }
+}
+
+/// Methods that fall back to the JsonRPC connector when the local state can not answer a request.
+///
+/// All fallbacks are disabled by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FallbackConfig {
+    /// Fall back to `getrawtransaction`, e.g. for transactions held in the mempool but not yet in state.
+    pub get_raw_transaction: bool,
+    /// Fall back to `getrawmempool`.
+    pub get_raw_mempool: bool,
+    /// Fall back to `getblockchaininfo` for the best chain tip, used until the block notifier has synced a tip.
+    pub get_best_block_height: bool,
+    /// Fall back to `getblock`, used to build transaction Merkle proofs.
+    pub get_block: bool,
+    /// Fall back to `getinfo`, the local state does not hold node information so `get_info` always needs it.
+    pub get_info: bool,
+    /// Fall back to `z_gettreestate`, the local state does not hold tree states so `get_tree_frontiers` always
+    /// needs it.
+    pub get_treestate: bool,
+}
+
+/// Time a `getinfo` response is cached for by a [`FetchService`], unless set with
+/// [`FetchService::with_info_cache_ttl`].
+pub const DEFAULT_INFO_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// A transaction returned by [`FetchService::get_transaction_with_proof`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionProof {
+    /// The transaction is mined in a best chain block.
+    Confirmed {
+        /// The raw transaction.
+        transaction: SerializedTransaction,
+        /// Hash of the block holding the transaction.
+        block_hash: BlockHash,
+        /// Height of the block holding the transaction.
+        height: ChainHeight,
+        /// Merkle branch tying the transaction to the block header's Merkle root.
+        branch: MerkleBranch,
+    },
+    /// The transaction is held in the mempool, there is no block to prove inclusion in.
+    Unconfirmed {
+        /// The raw transaction.
+        transaction: SerializedTransaction,
+    },
+}
+
+/// Mempool txids returned by [`FetchService::get_mempool`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MempoolSnapshot {
+    /// Txids held in the mempool, empty while the mempool is unavailable.
+    pub txids: TxidsResponse,
+    /// False if the validator's mempool RPC is unavailable, the txids are not live in that case.
+    pub available: bool,
+}
+
+/// Serves chain and mempool requests from the local state, falling back to the JsonRPC connector
+/// for methods enabled in its [`FallbackConfig`].
+pub struct FetchService<S: StateSource> {
+    /// Local chain state.
+    state: S,
+    /// JsonRPC connector used for fallbacks, fallbacks are disabled if None.
+    json_rpc: Option<JsonRpcConnector>,
+    /// Methods that fall back to the JsonRPC connector.
+    fallback: FallbackConfig,
+    /// Request counters by serving path.
+    status: FetchServiceStatus,
+    /// Cache of confirmed transactions, disabled if None.
+    transaction_cache: Option<TransactionCache>,
+    /// Broadcasts blocks added to the best chain, disabled if None.
+    block_notifier: Option<BlockNotifier>,
+    /// Mempool availability, as seen by a mempool poller, the mempool is assumed available if None.
+    mempool_status: Option<MempoolPollStatus>,
+    /// Mempool held by a mempool poller, returned by [`FetchServiceSubscriber::mempool_snapshot`], disabled if None.
+    mempool_view: Option<MempoolView>,
+    /// Mempool updates pushed by a mempool poller to [`FetchServiceSubscriber::subscribe_mempool`], disabled if None.
+    mempool_updates: Option<broadcast::Sender<MempoolUpdate>>,
+    /// Cache of the node's `getinfo` response.
+    info_cache: InfoCache,
+    /// Bounds the JsonRPC fallbacks waiting on the validator at once, unbounded if None.
+    upstream_queue: Option<UpstreamQueue>,
+}
+
+impl FetchService<NoLocalState> {
+    /// Returns a FetchService without local chain state, serving every request from `json_rpc`.
+    ///
+    /// Every fallback is enabled, the node is the only source the service can answer from.
+    pub fn from_node(json_rpc: JsonRpcConnector) -> Self {
+        FetchService::new(
+            NoLocalState,
+            Some(json_rpc),
+            FallbackConfig {
+                get_raw_transaction: true,
+                get_raw_mempool: true,
+                get_best_block_height: true,
+                get_block: true,
+                get_info: true,
+                get_treestate: true,
+            },
+        )
+    }
+}
+
+impl<S: StateSource> std::fmt::Debug for FetchService<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FetchService")
+            .field("fallback", &self.fallback)
+            .field("status", &self.status)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: StateSource> FetchService<S> {
+    /// Returns a new FetchService.
+    ///
+    /// If `json_rpc` is None no method falls back, regardless of `fallback`.
+    pub fn new(state: S, json_rpc: Option<JsonRpcConnector>, fallback: FallbackConfig) -> Self {
+        FetchService {
+            state,
+            json_rpc,
+            fallback,
+            status: FetchServiceStatus::default(),
+            transaction_cache: None,
+            block_notifier: None,
+            mempool_status: None,
+            mempool_view: None,
+            mempool_updates: None,
+            info_cache: InfoCache::new(DEFAULT_INFO_CACHE_TTL),
+            upstream_queue: None,
+        }
+    }
+
+    /// Caches up to `capacity` confirmed transactions returned by [`FetchService::get_raw_transaction`].
+    pub fn with_transaction_cache(mut self, capacity: usize) -> Self {
+        self.transaction_cache = Some(TransactionCache::new(capacity));
+        self
+    }
+
+    /// Broadcasts the blocks added to the best chain, as seen by `block_notifier`, to [`FetchService::subscribe_blocks`].
+    pub fn with_block_notifier(mut self, block_notifier: BlockNotifier) -> Self {
+        self.block_notifier = Some(block_notifier);
+        self
+    }
+
+    /// Answers [`FetchService::get_mempool`] with an empty, unavailable, mempool while `mempool_status` reports the
+    /// validator's mempool RPC unavailable.
+    pub fn with_mempool_status(mut self, mempool_status: MempoolPollStatus) -> Self {
+        self.mempool_status = Some(mempool_status);
+        self
+    }
+
+    /// Serves [`FetchServiceSubscriber::mempool_snapshot`] from `mempool_view`, see [`MempoolPoller::view`](crate::mempool::MempoolPoller::view).
+    pub fn with_mempool_view(mut self, mempool_view: MempoolView) -> Self {
+        self.mempool_view = Some(mempool_view);
+        self
+    }
+
+    /// Follows the mempool polled by `mempool_poller`, as [`FetchService::with_mempool_status`] and
+    /// [`FetchService::with_mempool_view`], and pushes its updates to [`FetchServiceSubscriber::subscribe_mempool`].
+    ///
+    /// The poller's polling interval is set by the [`MempoolPollConfig`](crate::mempool::MempoolPollConfig) it was
+    /// spawned with.
+    pub fn with_mempool_poller(mut self, mempool_poller: &MempoolPoller) -> Self {
+        self.mempool_status = Some(mempool_poller.status());
+        self.mempool_view = Some(mempool_poller.view());
+        self.mempool_updates = Some(mempool_poller.sender());
+        self
+    }
+
+    /// Caches the node's `getinfo` response for `ttl` rather than [`DEFAULT_INFO_CACHE_TTL`], caching is disabled if
+    /// `ttl` is zero.
+    pub fn with_info_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.info_cache = InfoCache::new(ttl);
+        self
+    }
+
+    /// Allows at most `capacity` JsonRPC fallbacks to wait on the validator at once.
+    ///
+    /// Fallbacks beyond the capacity fail with [`StateServiceError::UpstreamQueueFull`] rather than queueing, so a
+    /// stalled validator can not build up an unbounded backlog of requests.
+    pub fn with_upstream_queue(mut self, capacity: usize) -> Self {
+        self.upstream_queue = Some(UpstreamQueue::new(capacity));
+        self
+    }
+
+    /// Returns a receiver of blocks added to the best chain after this call, None if no block notifier is set.
+    ///
+    /// Receivers lagging behind the notifier's channel capacity receive a `Lagged` error and should re-query the chain tip.
+    pub fn subscribe_blocks(&self) -> Option<broadcast::Receiver<NewBlock>> {
+        self.block_notifier
+            .as_ref()
+            .map(BlockNotifier::subscribe_blocks)
+    }
+
+    /// Returns a cloneable handle to the service's update streams and cached chain tip and mempool.
+    pub fn subscriber(&self) -> FetchServiceSubscriber {
+        FetchServiceSubscriber {
+            chain_tip: self.block_notifier.as_ref().map(BlockNotifier::chain_tip),
+            mempool_view: self.mempool_view.clone(),
+            mempool_updates: self.mempool_updates.clone(),
+        }
+    }
+
+    /// Returns the service's request counters.
+    pub fn status(&self) -> FetchServiceStatus {
+        self.status.clone()
+    }
+
+    /// Returns the transaction cache's hit, miss and eviction counts, None if the cache is disabled.
+    pub fn transaction_cache_status(&self) -> Option<TransactionCacheStatus> {
+        self.transaction_cache
+            .as_ref()
+            .map(TransactionCache::status)
+    }
+
+    /// Returns the upstream queue's depth and rejection counts, None if the queue is unbounded.
+    pub fn upstream_queue_status(&self) -> Option<UpstreamQueueStatus> {
+        self.upstream_queue.as_ref().map(UpstreamQueue::status)
+    }
+
+    /// Drops cached transactions held in blocks rolled back by `event`, returns the number of transactions removed.
+    pub fn handle_reorg(&self, event: &ReorgEvent) -> usize {
+        self.transaction_cache
+            .as_ref()
+            .map_or(0, |cache| cache.handle_reorg(event))
+    }
+
+    /// Drops the cached `getinfo` response, the next [`FetchService::get_info`] request is fetched from the node.
+    pub fn invalidate_info(&self) {
+        self.info_cache.invalidate();
+    }
+
+    /// Returns the JsonRPC connector if `enabled`.
+    fn fallback_connector(&self, enabled: bool) -> Option<&JsonRpcConnector> {
+        self.json_rpc.as_ref().filter(|_| enabled)
+    }
+
+    /// Takes an upstream queue slot for a request to the validator, e.g. a JsonRPC fallback, to be held until the
+    /// request's responses have been received.
+    ///
+    /// Returns None if the queue is unbounded.
+    pub fn enter_upstream(&self) -> Result<Option<QueueSlot>, StateServiceError> {
+        self.upstream_queue
+            .as_ref()
+            .map(UpstreamQueue::try_enter)
+            .transpose()
+    }
+
+    /// Returns the transaction with the given txid, from the transaction cache, the local state or the JsonRPC fallback.
+    ///
+    /// Confirmed transactions are added to the transaction cache, if enabled.
+    pub async fn get_raw_transaction(
+        &self,
+        txid_hex: String,
+        verbose: Option<u8>,
+    ) -> Result<GetTransactionResponse, StateServiceError> {
+        if let Some(cache) = &self.transaction_cache {
+            if let Some(transaction) = cache.get(&txid_hex, verbose) {
+                return Ok(transaction);
+            }
+        }
+        if let Some(transaction) = self.state.get_raw_transaction(&txid_hex, verbose).await? {
+            self.status.record_get_raw_transaction(ServedBy::State);
+            self.cache_transaction(&txid_hex, &transaction);
+            return Ok(transaction);
+        }
+        match self.fallback_connector(self.fallback.get_raw_transaction) {
+            Some(json_rpc) => {
+                let _slot = self.enter_upstream()?;
+                let transaction = json_rpc
+                    .get_raw_transaction(txid_hex.clone(), verbose)
+                    .await?;
+                self.status.record_get_raw_transaction(ServedBy::JsonRpc);
+                self.cache_transaction(&txid_hex, &transaction);
+                Ok(transaction)
+            }
+            None => {
+                self.status.record_get_raw_transaction(ServedBy::Miss);
+                Err(StateServiceError::NotFound("get_raw_transaction"))
+            }
+        }
+    }
+
+    /// Adds a transaction to the transaction cache, if enabled.
+    fn cache_transaction(&self, txid_hex: &str, transaction: &GetTransactionResponse) {
+        if let Some(cache) = &self.transaction_cache {
+            cache.insert(txid_hex, transaction);
+        }
+    }
+
+    /// Returns the txids of the mempool, from the local state or the JsonRPC fallback.
+    pub async fn get_raw_mempool(&self) -> Result<TxidsResponse, StateServiceError> {
+        if let Some(mempool) = self.state.get_raw_mempool().await? {
+            self.status.record_get_raw_mempool(ServedBy::State);
+            return Ok(mempool);
+        }
+        match self.fallback_connector(self.fallback.get_raw_mempool) {
+            Some(json_rpc) => {
+                let _slot = self.enter_upstream()?;
+                let mempool = json_rpc.get_raw_mempool().await?;
+                self.status.record_get_raw_mempool(ServedBy::JsonRpc);
+                Ok(mempool)
+            }
+            None => {
+                self.status.record_get_raw_mempool(ServedBy::Miss);
+                Err(StateServiceError::NotFound("get_raw_mempool"))
+            }
+        }
+    }
+
+    /// Returns the txids of the mempool as [`FetchService::get_raw_mempool`], or an empty mempool flagged as
+    /// unavailable while the validator's mempool RPC is unavailable (see [`FetchService::with_mempool_status`]).
+    pub async fn get_mempool(&self) -> Result<MempoolSnapshot, StateServiceError> {
+        if self
+            .mempool_status
+            .as_ref()
+            .is_some_and(|mempool_status| !mempool_status.is_available())
+        {
+            return Ok(MempoolSnapshot {
+                txids: TxidsResponse {
+                    transactions: Vec::new(),
+                },
+                available: false,
+            });
+        }
+        let txids = self.get_raw_mempool().await?;
+        Ok(MempoolSnapshot {
+            txids,
+            available: true,
+        })
+    }
+
+    /// Returns the node's version information, from the info cache or the JsonRPC fallback.
+    ///
+    /// Responses are cached for the info cache's TTL, so bursts of requests only fetch from the node once.
+    pub async fn get_info(&self) -> Result<GetInfoResponse, StateServiceError> {
+        if let Some(info) = self.info_cache.get() {
+            self.status.record_get_info(ServedBy::State);
+            return Ok(info);
+        }
+        match self.fallback_connector(self.fallback.get_info) {
+            Some(json_rpc) => {
+                let _slot = self.enter_upstream()?;
+                let info = json_rpc.get_info().await?;
+                self.status.record_get_info(ServedBy::JsonRpc);
+                self.info_cache.insert(&info);
+                Ok(info)
+            }
+            None => {
+                self.status.record_get_info(ServedBy::Miss);
+                Err(StateServiceError::NotFound("get_info"))
+            }
+        }
+    }
+
+    /// Returns the Sapling and Orchard note commitment tree frontiers at `height`, parsed from the node's tree state.
+    ///
+    /// A pool's frontier is None below its activation height.
+    pub async fn get_tree_frontiers(
+        &self,
+        height: ChainHeight,
+    ) -> Result<TreeFrontiers, StateServiceError> {
+        match self.fallback_connector(self.fallback.get_treestate) {
+            Some(json_rpc) => {
+                let slot = self.enter_upstream()?;
+                let treestate = json_rpc.get_treestate(height.0.to_string()).await?;
+                drop(slot);
+                self.status.record_get_treestate(ServedBy::JsonRpc);
+                Ok(TreeFrontiers::try_from(&treestate)?)
+            }
+            None => {
+                self.status.record_get_treestate(ServedBy::Miss);
+                Err(StateServiceError::NotFound("get_treestate"))
+            }
+        }
+    }
+
+    /// Returns the transaction with the given txid and the Merkle branch proving its inclusion in its block.
+    ///
+    /// The transaction is fetched as by [`FetchService::get_raw_transaction`], its block from the JsonRPC fallback.
+    /// If the node returns the block's Merkle root the branch is checked against it.
+    pub async fn get_transaction_with_proof(
+        &self,
+        txid_hex: String,
+    ) -> Result<TransactionProof, StateServiceError> {
+        let (transaction, height, block_hash) =
+            match self.get_raw_transaction(txid_hex.clone(), Some(1)).await? {
+                GetTransactionResponse::Object {
+                    hex,
+                    height,
+                    block_hash: Some(block_hash),
+                    ..
+                } if height >= 0 => (hex, ChainHeight(height as u32), block_hash.0),
+                GetTransactionResponse::Object { hex, .. } => {
+                    return Ok(TransactionProof::Unconfirmed { transaction: hex })
+                }
+                GetTransactionResponse::Raw(_) => {
+                    return Err(StateServiceError::StateError(
+                        "Received raw transaction type, this should not be possible here."
+                            .to_string(),
+                    ))
+                }
+            };
+        let json_rpc = match self.fallback_connector(self.fallback.get_block) {
+            Some(json_rpc) => json_rpc,
+            None => {
+                self.status.record_get_block(ServedBy::Miss);
+                return Err(StateServiceError::NotFound("get_block"));
+            }
+        };
+        let slot = self.enter_upstream()?;
+        let block = json_rpc.get_main_chain_block(block_hash, Some(1)).await?;
+        drop(slot);
+        let (txids, merkle_root) = match block {
+            GetBlockResponse::Object {
+                tx, merkle_root, ..
+            } => (tx.txids(), merkle_root),
+            GetBlockResponse::Raw(_) => {
+                return Err(StateServiceError::StateError(
+                    "Received raw block type, this should not be possible here.".to_string(),
+                ))
+            }
+        };
+        self.status.record_get_block(ServedBy::JsonRpc);
+        let invalid_proof = |reason: &str| {
+            StateServiceError::InvalidMerkleProof(txid_hex.clone(), reason.to_string())
+        };
+        let txids = txids
+            .iter()
+            .map(|txid| txid.parse::<TransactionHash>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid_proof("block holds an invalid txid"))?;
+        let txid = txid_hex
+            .parse::<TransactionHash>()
+            .map_err(|_| invalid_proof("invalid txid"))?;
+        let branch = txids
+            .iter()
+            .position(|block_txid| *block_txid == txid)
+            .and_then(|index| MerkleBranch::new(&txids, index))
+            .ok_or_else(|| invalid_proof("transaction not held in its block"))?;
+        if merkle_root.is_some_and(|merkle_root| branch.root(&txid) != merkle_root) {
+            return Err(invalid_proof("Merkle root does not match the block header"));
+        }
+        Ok(TransactionProof::Confirmed {
+            transaction,
+            block_hash,
+            height,
+            branch,
+        })
+    }
+
+    /// Returns the height and hash of the best chain tip, from the block notifier's cached tip or the JsonRPC fallback.
+    ///
+    /// The hash is always the one at the returned height, both are taken from a single synced tip or
+    /// `getblockchaininfo` response.
+    pub async fn get_best_block_height(
+        &self,
+    ) -> Result<(ChainHeight, BlockHash), StateServiceError> {
+        if let Some(tip) = self
+            .block_notifier
+            .as_ref()
+            .and_then(|block_notifier| block_notifier.chain_tip().tip())
+        {
+            self.status.record_get_best_block_height(ServedBy::State);
+            return Ok((ChainHeight(tip.height), tip.hash));
+        }
+        match self.fallback_connector(self.fallback.get_best_block_height) {
+            Some(json_rpc) => {
+                let _slot = self.enter_upstream()?;
+                let best_block = json_rpc.get_best_block_height().await?;
+                self.status.record_get_best_block_height(ServedBy::JsonRpc);
+                Ok(best_block)
+            }
+            None => {
+                self.status.record_get_best_block_height(ServedBy::Miss);
+                Err(StateServiceError::NotFound("get_best_block_height"))
+            }
+        }
+    }
+}
+
+impl<S: StateSource + 'static> FetchService<S> {
+    /// Spawns a task passing each reorg received on `reorgs` to [`FetchService::handle_reorg`], e.g. the reorgs of a
+    /// [`ChainTipMonitor`](zaino_fetch::chain::reorg::ChainTipMonitor).
+    ///
+    /// The task runs until the reorg channel is closed. Reorgs missed by a lagging receiver are logged, transactions
+    /// they rolled back stay cached until evicted.
+    pub fn spawn_reorg_handler(
+        fetch_service: Arc<Self>,
+        mut reorgs: broadcast::Receiver<ReorgEvent>,
+    ) -> tokio::task::JoinHandle<()> {
+        spawn_named("fetch_service_reorg_handler", async move {
+            loop {
+                match reorgs.recv().await {
+                    Ok(event) => {
+                        let removed = fetch_service.handle_reorg(&event);
+                        println!(
+                            "Dropped {} cached transactions above reorg fork height {}.",
+                            removed, event.fork_height
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        eprintln!("FetchService missed {} reorg events.", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+/// Cloneable handle to a [`FetchService`]'s update streams and cached chain tip and mempool, returned by
+/// [`FetchService::subscriber`].
+#[derive(Debug, Clone)]
+pub struct FetchServiceSubscriber {
+    /// Best chain tip synced by the service's block notifier, disabled if None.
+    chain_tip: Option<ChainTipCache>,
+    /// Mempool held by the service's mempool poller, disabled if None.
+    mempool_view: Option<MempoolView>,
+    /// Mempool updates pushed by the service's mempool poller, disabled if None.
+    mempool_updates: Option<broadcast::Sender<MempoolUpdate>>,
+}
+
+impl FetchServiceSubscriber {
+    /// Returns a stream of txids added to and removed from the mempool, starting from the mempool poller's next
+    /// snapshot diff. Returns None if the service does not follow a mempool poller, see
+    /// [`FetchService::with_mempool_poller`].
+    ///
+    /// Subscribers lagging behind the poller's channel capacity receive [`MempoolUpdate::Lagged`] with the number of
+    /// dropped updates and should re-fetch the full mempool to resync.
+    pub fn subscribe_mempool(&self) -> Option<impl futures::Stream<Item = MempoolUpdate>> {
+        self.mempool_updates.as_ref().map(|sender| {
+            futures::stream::unfold(sender.subscribe(), |mut receiver| async move {
+                match receiver.recv().await {
+                    Ok(update) => Some((update, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        Some((MempoolUpdate::Lagged(skipped), receiver))
+                    }
+                    Err(broadcast::error::RecvError::Closed) => None,
+                }
+            })
+        })
+    }
+
+    /// Returns the mempool held after the mempool poller's most recent successful poll, without querying the node.
+    ///
+    /// The snapshot holds the txids the poller's update stream has converged to, with the time of the poll. Returns
+    /// None if the service holds no mempool view or the mempool has not been polled yet.
+    pub fn mempool_snapshot(&self) -> Option<MempoolDump> {
+        self.mempool_view.as_ref().and_then(MempoolView::dump)
+    }
+
+    /// Returns the height, hash and time of the best chain tip block, from the block notifier's cached tip.
+    ///
+    /// The fields are always those of a single block and are updated as each new block is synced, None if the
+    /// service has no block notifier or it has not fetched a tip block yet.
+    pub fn best_block(&self) -> Option<TipInfo> {
+        self.chain_tip.as_ref().and_then(ChainTipCache::tip_info)
+    }
+}
@@ -2,3 +2,12 @@
 
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
+
+pub mod blocks;
+pub mod cache;
+pub mod error;
+pub mod fetch;
+pub mod mempool;
+pub mod queue;
+pub mod status;
+pub mod sync;
@@ -0,0 +1,270 @@
+//! Zaino-State mempool poller, pushes the diff between successive validator mempool snapshots to subscribers.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, SystemTime},
+};
+
+use tokio::sync::broadcast;
+use zaino_fetch::{
+    chain::mempool::MempoolUpdate, jsonrpc::connector::JsonRpcConnector,
+    primitives::transaction::TransactionHash, task::spawn_named,
+};
+
+use crate::status::MempoolPollStatus;
+
+/// Polling intervals of a [`MempoolPoller`].
+///
+/// The poller waits `poll_interval` between polls while the mempool is changing,
+/// doubling the wait after each poll that sees no change, up to `max_poll_interval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MempoolPollConfig {
+    /// Interval between polls while the mempool is changing.
+    pub poll_interval: Duration,
+    /// Maximum interval between polls while the mempool is idle.
+    pub max_poll_interval: Duration,
+}
+
+impl Default for MempoolPollConfig {
+    fn default() -> Self {
+        MempoolPollConfig {
+            poll_interval: Duration::from_secs(1),
+            max_poll_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+impl MempoolPollConfig {
+    /// Returns the interval to wait after a poll, given the interval waited before it.
+    ///
+    /// Resets to `poll_interval` if the poll saw the mempool change, otherwise backs off toward `max_poll_interval`.
+    pub fn next_interval(&self, current: Duration, changed: bool) -> Duration {
+        if changed {
+            self.poll_interval
+        } else {
+            current.saturating_mul(2).clamp(
+                self.poll_interval,
+                self.max_poll_interval.max(self.poll_interval),
+            )
+        }
+    }
+}
+
+/// A transaction held in a [`MempoolDump`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MempoolDumpEntry {
+    /// Txid of the transaction.
+    pub txid: TransactionHash,
+    /// Size of the transaction in bytes, None if not yet fetched or the validator does not serve verbose mempool
+    /// entries.
+    pub size: Option<u64>,
+    /// Fee paid by the transaction in zatoshis, None if not yet fetched or the validator does not serve verbose
+    /// mempool entries.
+    pub fee: Option<u64>,
+}
+
+/// The mempool held by a [`MempoolPoller`] after a single poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MempoolDump {
+    /// Time of the poll the mempool was taken from.
+    pub taken_at: SystemTime,
+    /// Transactions held in the mempool, ordered by txid.
+    pub entries: Vec<MempoolDumpEntry>,
+}
+
+/// Holds the mempool most recently polled by a [`MempoolPoller`], the state its update stream has converged to.
+#[derive(Debug, Clone, Default)]
+pub struct MempoolView {
+    /// Most recently polled mempool, None before the first successful poll.
+    dump: Arc<RwLock<Option<MempoolDump>>>,
+}
+
+impl MempoolView {
+    /// Returns the most recently polled mempool, None if the validator's mempool has not been polled yet.
+    pub fn dump(&self) -> Option<MempoolDump> {
+        self.dump.read().ok().and_then(|dump| dump.clone())
+    }
+
+    /// Records the mempool polled at `taken_at`, with the sizes and fees fetched so far.
+    fn record(
+        &self,
+        taken_at: SystemTime,
+        txids: &HashSet<TransactionHash>,
+        details: &HashMap<TransactionHash, (u64, u64)>,
+    ) {
+        let mut entries: Vec<MempoolDumpEntry> = txids
+            .iter()
+            .map(|txid| {
+                let detail = details.get(txid);
+                MempoolDumpEntry {
+                    txid: *txid,
+                    size: detail.map(|(size, _)| *size),
+                    fee: detail.map(|(_, fee)| *fee),
+                }
+            })
+            .collect();
+        entries.sort_unstable_by_key(|entry| entry.txid);
+        if let Ok(mut dump) = self.dump.write() {
+            *dump = Some(MempoolDump { taken_at, entries });
+        }
+    }
+}
+
+/// Polls the validator's mempool and pushes the diff between successive snapshots to subscribers,
+/// backing off while the mempool is idle.
+///
+/// After [`MempoolPoller::DISABLE_AFTER_FAILURES`] consecutive failed polls, e.g. on nodes with `getrawmempool`
+/// disabled, the mempool is marked unavailable in the poller's [`MempoolPollStatus`]. Polling continues at
+/// `max_poll_interval`, the mempool is marked available again by the next successful poll.
+///
+/// The polled mempool is also held in the poller's [`MempoolView`]. The sizes and fees of new transactions are
+/// fetched with a verbose `getrawmempool` after each poll that saw them, until the validator reports verbose requests
+/// unsupported. Other failed verbose requests are retried after the next poll.
+pub struct MempoolPoller {
+    /// Broadcast channel used to send updates to subscribers.
+    sender: broadcast::Sender<MempoolUpdate>,
+    /// Effective polling interval and poll count.
+    status: MempoolPollStatus,
+    /// Mempool held after the most recent successful poll.
+    view: MempoolView,
+    /// Handle of the polling task.
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl MempoolPoller {
+    /// Capacity of the broadcast channel, subscribers lagging further behind miss updates.
+    const CHANNEL_CAPACITY: usize = 1024;
+
+    /// Number of consecutive failed polls after which the mempool is marked unavailable.
+    pub const DISABLE_AFTER_FAILURES: u64 = 3;
+
+    /// Spawns a mempool poller querying `json_rpc` while `online` is true.
+    ///
+    /// The first poll is sent immediately, failed polls count as polls that saw no change.
+    pub fn spawn(
+        json_rpc: JsonRpcConnector,
+        config: MempoolPollConfig,
+        online: Arc<AtomicBool>,
+    ) -> Self {
+        let (sender, _) = broadcast::channel(Self::CHANNEL_CAPACITY);
+        let status = MempoolPollStatus::new(config.poll_interval);
+        let task_sender = sender.clone();
+        let task_status = status.clone();
+        let view = MempoolView::default();
+        let task_view = view.clone();
+        let handle = spawn_named("mempool_poller", async move {
+            let mut snapshot: HashSet<TransactionHash> = HashSet::new();
+            // Size and fee of each transaction in the snapshot, when known.
+            let mut details: HashMap<TransactionHash, (u64, u64)> = HashMap::new();
+            let mut verbose_supported = true;
+            let mut interval = config.poll_interval;
+            while online.load(Ordering::SeqCst) {
+                let mut taken_at = None;
+                let changed = match json_rpc.get_raw_mempool().await {
+                    Ok(txids) => {
+                        let new_snapshot: HashSet<TransactionHash> = txids
+                            .transactions
+                            .iter()
+                            .filter_map(|txid| txid.parse().ok())
+                            .collect();
+                        let changed = new_snapshot != snapshot;
+                        // NOTE: Send only fails when there are no active subscribers, updates are dropped in that case.
+                        for txid in snapshot.difference(&new_snapshot) {
+                            let _ = task_sender.send(MempoolUpdate::Removed(*txid));
+                        }
+                        for txid in new_snapshot.difference(&snapshot) {
+                            let _ = task_sender.send(MempoolUpdate::Added(*txid));
+                        }
+                        snapshot = new_snapshot;
+                        details.retain(|txid, _| snapshot.contains(txid));
+                        let poll_time = SystemTime::now();
+                        task_view.record(poll_time, &snapshot, &details);
+                        taken_at = Some(poll_time);
+                        if !task_status.is_available() {
+                            println!("Mempool RPC available again, mempool re-enabled.");
+                        }
+                        task_status.record_snapshot();
+                        changed
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to poll mempool: {}.", e);
+                        if task_status.record_failure() == Self::DISABLE_AFTER_FAILURES {
+                            eprintln!(
+                                "Mempool RPC failed {} consecutive polls, mempool disabled until the node responds.",
+                                Self::DISABLE_AFTER_FAILURES
+                            );
+                            task_status.mark_unavailable();
+                        }
+                        false
+                    }
+                };
+                interval = config.next_interval(interval, changed);
+                task_status.record_poll(interval);
+                if let Some(taken_at) = taken_at {
+                    if verbose_supported && snapshot.iter().any(|txid| !details.contains_key(txid))
+                    {
+                        match json_rpc.get_raw_mempool_verbose().await {
+                            Ok(entries) => {
+                                for entry in entries {
+                                    if let Ok(txid) = entry.txid.parse::<TransactionHash>() {
+                                        if snapshot.contains(&txid) {
+                                            details.insert(txid, (entry.size, entry.fee));
+                                        }
+                                    }
+                                }
+                                task_view.record(taken_at, &snapshot, &details);
+                            }
+                            Err(e) if e.is_method_not_found() => {
+                                eprintln!(
+                                    "Validator does not serve verbose mempool entries: {}, mempool sizes and fees are not tracked.",
+                                    e
+                                );
+                                verbose_supported = false;
+                            }
+                            // NOTE: Other failures, e.g. timeouts, are retried after the next poll.
+                            Err(e) => {
+                                eprintln!("Failed to fetch verbose mempool: {}.", e);
+                            }
+                        }
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+        MempoolPoller {
+            sender,
+            status,
+            view,
+            handle,
+        }
+    }
+
+    /// Returns the poller's effective polling interval, poll count and mempool availability.
+    pub fn status(&self) -> MempoolPollStatus {
+        self.status.clone()
+    }
+
+    /// Returns the mempool held after the most recent successful poll.
+    pub fn view(&self) -> MempoolView {
+        self.view.clone()
+    }
+
+    /// Returns a receiver of mempool updates, starting from the next snapshot diff.
+    pub fn subscribe(&self) -> broadcast::Receiver<MempoolUpdate> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the sender of the poller's update channel, used to subscribe to updates from other handles.
+    pub(crate) fn sender(&self) -> broadcast::Sender<MempoolUpdate> {
+        self.sender.clone()
+    }
+
+    /// Stops the polling task.
+    pub fn shutdown(self) {
+        self.handle.abort();
+    }
+}
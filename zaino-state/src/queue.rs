@@ -0,0 +1,68 @@
+//! Zaino-State bounded queue of upstream-bound work, requests beyond its capacity are rejected rather than queued.
+
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{error::StateServiceError, status::UpstreamQueueStatus};
+
+/// Bounds the number of requests waiting on the validator at once.
+///
+/// Each JsonRPC fallback holds a slot of the queue until its response has been received, so a stalled validator
+/// holds at most `capacity` requests and their buffers. Requests arriving while every slot is held fail fast with
+/// [`StateServiceError::UpstreamQueueFull`].
+#[derive(Debug)]
+pub struct UpstreamQueue {
+    /// Permits for queue slots.
+    slots: Arc<Semaphore>,
+    /// Depth and rejection counts.
+    status: UpstreamQueueStatus,
+}
+
+impl UpstreamQueue {
+    /// Returns an empty queue holding at most `capacity` requests, a capacity of 0 is raised to 1.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        UpstreamQueue {
+            slots: Arc::new(Semaphore::new(capacity)),
+            status: UpstreamQueueStatus::new(capacity),
+        }
+    }
+
+    /// Returns the queue's depth and rejection counts.
+    pub fn status(&self) -> UpstreamQueueStatus {
+        self.status.clone()
+    }
+
+    /// Takes a queue slot, held until the returned slot is dropped, or rejects the request if the queue is full.
+    pub fn try_enter(&self) -> Result<QueueSlot, StateServiceError> {
+        match self.slots.clone().try_acquire_owned() {
+            Ok(permit) => {
+                self.status.record_enter();
+                Ok(QueueSlot {
+                    _permit: permit,
+                    status: self.status.clone(),
+                })
+            }
+            Err(_) => {
+                self.status.record_rejection();
+                Err(StateServiceError::UpstreamQueueFull(self.status.capacity()))
+            }
+        }
+    }
+}
+
+/// A slot of an [`UpstreamQueue`], released when dropped, including when the request is cancelled.
+#[derive(Debug)]
+pub struct QueueSlot {
+    /// Permit returned to the queue on drop.
+    _permit: OwnedSemaphorePermit,
+    /// Status of the queue the slot was taken from.
+    status: UpstreamQueueStatus,
+}
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        self.status.record_exit();
+    }
+}
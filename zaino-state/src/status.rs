@@ -0,0 +1,417 @@
+//! Holds the FetchService request and transaction cache counters, the upstream queue status, the mempool poller
+//! status, the cached chain tip, the sync progress and the validator connection pool status.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+use zaino_fetch::{chain::reorg::ChainTip, primitives::block::BlockHash};
+
+/// Utilization and retry counts of the validator connection pool shared by JsonRPC requests.
+pub use zaino_fetch::jsonrpc::connection_pool::ConnectionPoolStatus;
+
+/// The path that served a FetchService request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServedBy {
+    /// Served from the local chain state.
+    State,
+    /// Served by the JsonRPC fallback.
+    JsonRpc,
+    /// Not found in the local state, with the JsonRPC fallback disabled.
+    Miss,
+}
+
+/// Number of requests to a single method served by each path.
+#[derive(Debug, Default)]
+pub struct PathCounts {
+    state: AtomicU64,
+    json_rpc: AtomicU64,
+    miss: AtomicU64,
+}
+
+impl PathCounts {
+    /// Returns the number of requests served by `path`.
+    pub fn get(&self, path: ServedBy) -> u64 {
+        self.counter(path).load(Ordering::Relaxed)
+    }
+
+    /// Records a request served by `path`.
+    fn record(&self, path: ServedBy) {
+        self.counter(path).fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn counter(&self, path: ServedBy) -> &AtomicU64 {
+        match path {
+            ServedBy::State => &self.state,
+            ServedBy::JsonRpc => &self.json_rpc,
+            ServedBy::Miss => &self.miss,
+        }
+    }
+}
+
+/// Holds the request counters of a [`crate::fetch::FetchService`], by method and serving path.
+#[derive(Debug, Clone, Default)]
+pub struct FetchServiceStatus {
+    get_raw_transaction: Arc<PathCounts>,
+    get_raw_mempool: Arc<PathCounts>,
+    get_best_block_height: Arc<PathCounts>,
+    get_block: Arc<PathCounts>,
+    get_info: Arc<PathCounts>,
+    get_treestate: Arc<PathCounts>,
+}
+
+impl FetchServiceStatus {
+    /// Returns the `get_raw_transaction` request counters.
+    pub fn get_raw_transaction(&self) -> &PathCounts {
+        &self.get_raw_transaction
+    }
+
+    /// Returns the `get_raw_mempool` request counters.
+    pub fn get_raw_mempool(&self) -> &PathCounts {
+        &self.get_raw_mempool
+    }
+
+    /// Returns the `get_best_block_height` request counters, requests answered by the cached chain tip count as
+    /// served from the local state.
+    pub fn get_best_block_height(&self) -> &PathCounts {
+        &self.get_best_block_height
+    }
+
+    /// Returns the `get_block` request counters, blocks are only fetched to build transaction Merkle proofs.
+    pub fn get_block(&self) -> &PathCounts {
+        &self.get_block
+    }
+
+    /// Returns the `get_info` request counters, requests answered by the cached response count as served from the
+    /// local state.
+    pub fn get_info(&self) -> &PathCounts {
+        &self.get_info
+    }
+
+    /// Returns the `get_treestate` request counters, tree states are only fetched to build tree frontiers.
+    pub fn get_treestate(&self) -> &PathCounts {
+        &self.get_treestate
+    }
+
+    /// Records a `get_raw_transaction` request served by `path`.
+    pub(crate) fn record_get_raw_transaction(&self, path: ServedBy) {
+        self.get_raw_transaction.record(path);
+    }
+
+    /// Records a `get_raw_mempool` request served by `path`.
+    pub(crate) fn record_get_raw_mempool(&self, path: ServedBy) {
+        self.get_raw_mempool.record(path);
+    }
+
+    /// Records a `get_best_block_height` request served by `path`.
+    pub(crate) fn record_get_best_block_height(&self, path: ServedBy) {
+        self.get_best_block_height.record(path);
+    }
+
+    /// Records a `get_block` request served by `path`.
+    pub(crate) fn record_get_block(&self, path: ServedBy) {
+        self.get_block.record(path);
+    }
+
+    /// Records a `get_info` request served by `path`.
+    pub(crate) fn record_get_info(&self, path: ServedBy) {
+        self.get_info.record(path);
+    }
+
+    /// Records a `get_treestate` request served by `path`.
+    pub(crate) fn record_get_treestate(&self, path: ServedBy) {
+        self.get_treestate.record(path);
+    }
+}
+
+/// Height, hash and time of a best chain tip block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TipInfo {
+    /// Height of the block.
+    pub height: u32,
+    /// Hash of the block.
+    pub hash: BlockHash,
+    /// Unix epoch time when the block was mined.
+    pub time: i64,
+}
+
+/// Holds the best chain tip most recently synced by a [`crate::blocks::BlockNotifier`].
+///
+/// The tip's height and hash are always recorded together, so a reader never sees the height of one block
+/// paired with the hash of another. The tip block's time is recorded once the block has been fetched, with its
+/// height and hash, so [`ChainTipCache::tip_info`] may briefly lag [`ChainTipCache::tip`].
+#[derive(Debug, Clone, Default)]
+pub struct ChainTipCache {
+    /// Most recently synced tip, None before the first successful poll.
+    tip: Arc<RwLock<Option<ChainTip>>>,
+    /// Most recently fetched tip block, None before the first tip block is fetched.
+    tip_info: Arc<RwLock<Option<TipInfo>>>,
+}
+
+impl ChainTipCache {
+    /// Returns the most recently synced tip, None if the validator has not been reached yet.
+    pub fn tip(&self) -> Option<ChainTip> {
+        self.tip.read().ok().and_then(|tip| *tip)
+    }
+
+    /// Returns the height, hash and time of the most recently fetched tip block, None if no tip block has been
+    /// fetched yet.
+    pub fn tip_info(&self) -> Option<TipInfo> {
+        self.tip_info.read().ok().and_then(|tip_info| *tip_info)
+    }
+
+    /// Records a synced tip.
+    pub(crate) fn record(&self, tip: ChainTip) {
+        if let Ok(mut cached_tip) = self.tip.write() {
+            *cached_tip = Some(tip);
+        }
+    }
+
+    /// Records a fetched tip block.
+    pub(crate) fn record_tip_info(&self, tip_info: TipInfo) {
+        if let Ok(mut cached_tip_info) = self.tip_info.write() {
+            *cached_tip_info = Some(tip_info);
+        }
+    }
+}
+
+/// Progress of the indexer's synced chain tip towards the network height estimated by the validator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncProgress {
+    /// Height of the best chain tip synced by the indexer.
+    pub tip_height: u32,
+    /// Network height estimated by the validator, from `getblockchaininfo`.
+    pub estimated_height: u32,
+    /// Fraction of the estimated chain synced, between 0 and 1.
+    ///
+    /// Clamped to 1 when the estimate is at or below the synced tip.
+    pub fraction: f64,
+}
+
+impl SyncProgress {
+    /// Returns the progress of a synced tip at `tip_height` towards `estimated_height`.
+    pub fn new(tip_height: u32, estimated_height: u32) -> Self {
+        let fraction = if tip_height >= estimated_height {
+            1.0
+        } else {
+            tip_height as f64 / estimated_height as f64
+        };
+        SyncProgress {
+            tip_height,
+            estimated_height,
+            fraction,
+        }
+    }
+
+    /// Returns the fraction of the estimated chain synced as a percentage, between 0 and 100.
+    pub fn percent(&self) -> f64 {
+        self.fraction * 100.0
+    }
+}
+
+/// Holds the sync progress recorded by the most recent successful poll of a [`crate::blocks::BlockNotifier`].
+#[derive(Debug, Clone, Default)]
+pub struct SyncProgressCache {
+    /// Most recently recorded progress, None before the first successful poll.
+    progress: Arc<RwLock<Option<SyncProgress>>>,
+}
+
+impl SyncProgressCache {
+    /// Returns the most recently recorded progress, None if the validator has not been reached yet.
+    pub fn sync_progress(&self) -> Option<SyncProgress> {
+        self.progress.read().ok().and_then(|progress| *progress)
+    }
+
+    /// Records the progress of a synced tip, and sets the sync progress gauge if metrics are enabled.
+    pub(crate) fn record(&self, progress: SyncProgress) {
+        zaino_fetch::metrics::set_sync_progress(progress.fraction);
+        if let Ok(mut cached_progress) = self.progress.write() {
+            *cached_progress = Some(progress);
+        }
+    }
+}
+
+/// Holds the effective polling interval, poll count and mempool availability of a [`crate::mempool::MempoolPoller`].
+#[derive(Debug, Clone)]
+pub struct MempoolPollStatus {
+    /// Current interval between polls, in milliseconds.
+    interval_ms: Arc<AtomicU64>,
+    /// Number of polls sent.
+    polls: Arc<AtomicU64>,
+    /// Number of polls that fetched a mempool snapshot.
+    snapshots: Arc<AtomicU64>,
+    /// Number of polls that failed since the last snapshot.
+    consecutive_failures: Arc<AtomicU64>,
+    /// Cleared while the validator's mempool RPC is disabled or persistently failing.
+    available: Arc<AtomicBool>,
+}
+
+impl MempoolPollStatus {
+    /// Returns a new status with the given starting interval.
+    pub(crate) fn new(interval: Duration) -> Self {
+        MempoolPollStatus {
+            interval_ms: Arc::new(AtomicU64::new(interval.as_millis() as u64)),
+            polls: Arc::new(AtomicU64::new(0)),
+            snapshots: Arc::new(AtomicU64::new(0)),
+            consecutive_failures: Arc::new(AtomicU64::new(0)),
+            available: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// Returns the interval the poller currently waits between polls.
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::Relaxed))
+    }
+
+    /// Returns the number of polls sent.
+    pub fn polls(&self) -> u64 {
+        self.polls.load(Ordering::Relaxed)
+    }
+
+    /// Records a poll, followed by a wait of `next_interval`.
+    pub(crate) fn record_poll(&self, next_interval: Duration) {
+        self.interval_ms
+            .store(next_interval.as_millis() as u64, Ordering::Relaxed);
+        self.polls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of polls that fetched a mempool snapshot, failed polls are not counted.
+    pub fn snapshots(&self) -> u64 {
+        self.snapshots.load(Ordering::Relaxed)
+    }
+
+    /// Records a poll that fetched a mempool snapshot, marking the mempool available.
+    pub(crate) fn record_snapshot(&self) {
+        self.snapshots.fetch_add(1, Ordering::Relaxed);
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        self.available.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the number of polls that failed since the last snapshot.
+    pub fn consecutive_failures(&self) -> u64 {
+        self.consecutive_failures.load(Ordering::Relaxed)
+    }
+
+    /// Records a failed poll, returns the number of polls that failed since the last snapshot.
+    pub(crate) fn record_failure(&self) -> u64 {
+        self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Returns false while the validator's mempool RPC is disabled or persistently failing, mempool data is not
+    /// live in that case.
+    pub fn is_available(&self) -> bool {
+        self.available.load(Ordering::Relaxed)
+    }
+
+    /// Marks the mempool unavailable until the next snapshot.
+    pub(crate) fn mark_unavailable(&self) {
+        self.available.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Holds the hit, miss and eviction counts of a [`crate::cache::TransactionCache`].
+#[derive(Debug, Clone, Default)]
+pub struct TransactionCacheStatus {
+    /// Number of lookups served from the cache.
+    hits: Arc<AtomicU64>,
+    /// Number of lookups not held by the cache.
+    misses: Arc<AtomicU64>,
+    /// Number of transactions dropped, to stay within capacity or after a reorg.
+    evictions: Arc<AtomicU64>,
+}
+
+impl TransactionCacheStatus {
+    /// Returns the number of lookups served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups not held by the cache.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of transactions dropped, to stay within capacity or after a reorg.
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// Records a cache lookup.
+    pub(crate) fn record_lookup(&self, hit: bool) {
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records `count` dropped transactions.
+    pub(crate) fn record_evictions(&self, count: u64) {
+        self.evictions.fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+/// Holds the depth and rejection counts of a [`crate::queue::UpstreamQueue`].
+#[derive(Debug, Clone)]
+pub struct UpstreamQueueStatus {
+    /// Maximum number of requests waiting on the validator at once.
+    capacity: usize,
+    /// Number of requests currently waiting on the validator.
+    depth: Arc<AtomicUsize>,
+    /// Highest number of requests that waited on the validator at once.
+    peak_depth: Arc<AtomicUsize>,
+    /// Number of requests rejected because the queue was full.
+    rejections: Arc<AtomicU64>,
+}
+
+impl UpstreamQueueStatus {
+    /// Returns a status with no requests recorded.
+    pub(crate) fn new(capacity: usize) -> Self {
+        UpstreamQueueStatus {
+            capacity,
+            depth: Arc::new(AtomicUsize::new(0)),
+            peak_depth: Arc::new(AtomicUsize::new(0)),
+            rejections: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns the maximum number of requests waiting on the validator at once.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of requests currently waiting on the validator.
+    pub fn depth(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    /// Returns the highest number of requests that waited on the validator at once, never above the capacity.
+    pub fn peak_depth(&self) -> usize {
+        self.peak_depth.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests rejected because the queue was full.
+    pub fn rejections(&self) -> u64 {
+        self.rejections.load(Ordering::Relaxed)
+    }
+
+    /// Records a request taking a queue slot.
+    pub(crate) fn record_enter(&self) {
+        let depth = self.depth.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak_depth.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// Records a request releasing its queue slot.
+    pub(crate) fn record_exit(&self) {
+        self.depth.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Records a request rejected because the queue was full.
+    pub(crate) fn record_rejection(&self) {
+        self.rejections.fetch_add(1, Ordering::Relaxed);
+    }
+}
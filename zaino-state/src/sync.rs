@@ -0,0 +1,208 @@
+//! Zaino-State initial sync reporting, logs throttled progress lines while the indexer catches up with the network.
+//!
+//! The sync rate is an exponentially weighted moving average of the blocks synced per second between polls, so the
+//! ETA does not swing with every batch of blocks the validator hands out.
+
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::status::SyncProgress;
+
+/// Log interval and rate smoothing of a [`SyncReporter`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncReporterConfig {
+    /// Minimum interval between two progress lines.
+    pub log_interval: Duration,
+    /// Weight of the latest rate sample in the moving average, between 0 and 1.
+    ///
+    /// Lower values give a steadier ETA that adapts more slowly to rate changes.
+    pub smoothing: f64,
+}
+
+impl Default for SyncReporterConfig {
+    fn default() -> Self {
+        SyncReporterConfig {
+            log_interval: Duration::from_secs(10),
+            smoothing: 0.2,
+        }
+    }
+}
+
+/// Whether the initial sync has caught up with the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncState {
+    /// The synced tip is below the network height estimated by the validator.
+    Syncing,
+    /// The synced tip has reached the estimated network height.
+    Synced,
+}
+
+/// Initial sync status recorded by the most recent successful poll.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncStatus {
+    /// Whether the initial sync has caught up.
+    pub state: SyncState,
+    /// Height of the best chain tip synced by the indexer.
+    pub height: u32,
+    /// Network height estimated by the validator.
+    pub target_height: u32,
+    /// Smoothed sync rate, 0 before two polls have been recorded.
+    pub blocks_per_second: f64,
+    /// Estimated time until the target height is reached, None while the sync rate is unknown.
+    pub eta: Option<Duration>,
+}
+
+impl SyncStatus {
+    /// Returns the progress of the synced tip towards the target height.
+    pub fn progress(&self) -> SyncProgress {
+        SyncProgress::new(self.height, self.target_height)
+    }
+}
+
+impl fmt::Display for SyncStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.state {
+            SyncState::Synced => write!(f, "Sync complete at block {}.", self.height),
+            SyncState::Syncing => {
+                write!(
+                    f,
+                    "Syncing block {} of {} ({:.2}%), {:.1} blocks/s",
+                    self.height,
+                    self.target_height,
+                    self.progress().percent(),
+                    self.blocks_per_second
+                )?;
+                match self.eta {
+                    Some(eta) => write!(f, ", ETA {}s.", eta.as_secs()),
+                    None => write!(f, ", ETA unknown."),
+                }
+            }
+        }
+    }
+}
+
+/// Holds the initial sync status recorded by a [`SyncReporter`].
+#[derive(Debug, Clone, Default)]
+pub struct SyncStatusCache {
+    /// Most recently recorded status, None before the first successful poll.
+    status: Arc<RwLock<Option<SyncStatus>>>,
+}
+
+impl SyncStatusCache {
+    /// Returns the most recently recorded status, None if the validator has not been reached yet.
+    pub fn sync_status(&self) -> Option<SyncStatus> {
+        self.status.read().ok().and_then(|status| *status)
+    }
+
+    /// Records a sync status.
+    fn record(&self, status: SyncStatus) {
+        if let Ok(mut cached_status) = self.status.write() {
+            *cached_status = Some(status);
+        }
+    }
+}
+
+/// Tracks the initial sync rate and logs throttled progress lines, then a single line once the sync completes.
+///
+/// The reporter stays [`SyncState::Synced`] once caught up, later lag behind the network is reported by the health
+/// endpoint rather than as a new initial sync.
+#[derive(Debug)]
+pub struct SyncReporter {
+    /// Log interval and rate smoothing.
+    config: SyncReporterConfig,
+    /// Status shared with readers.
+    status: SyncStatusCache,
+    /// Time and height of the previous poll.
+    last_sample: Option<(Instant, u32)>,
+    /// Smoothed sync rate, None before two polls have been recorded.
+    blocks_per_second: Option<f64>,
+    /// Time of the last progress line.
+    last_log: Option<Instant>,
+    /// Set once the synced tip has reached the target height.
+    synced: bool,
+}
+
+impl SyncReporter {
+    /// Returns a reporter that has not recorded a poll yet.
+    pub fn new(config: SyncReporterConfig) -> Self {
+        SyncReporter {
+            config,
+            status: SyncStatusCache::default(),
+            last_sample: None,
+            blocks_per_second: None,
+            last_log: None,
+            synced: false,
+        }
+    }
+
+    /// Returns the status recorded by the reporter.
+    pub fn status(&self) -> SyncStatusCache {
+        self.status.clone()
+    }
+
+    /// Records a poll at `now` finding the synced tip at `height` and the network at `target_height`.
+    ///
+    /// Logs and returns the recorded status if a progress or sync complete line is due, None otherwise. No sync
+    /// complete line is logged if the first poll finds the indexer already synced.
+    pub fn record(&mut self, height: u32, target_height: u32, now: Instant) -> Option<SyncStatus> {
+        if let Some((last_time, last_height)) = self.last_sample {
+            let elapsed = now.saturating_duration_since(last_time).as_secs_f64();
+            if elapsed > 0.0 {
+                let sample = height.saturating_sub(last_height) as f64 / elapsed;
+                let smoothing = self.config.smoothing.clamp(0.0, 1.0);
+                self.blocks_per_second = Some(match self.blocks_per_second {
+                    Some(rate) => smoothing * sample + (1.0 - smoothing) * rate,
+                    None => sample,
+                });
+            }
+        }
+        self.last_sample = Some((now, height));
+        let blocks_per_second = self.blocks_per_second.unwrap_or(0.0);
+
+        if self.synced || height >= target_height {
+            let status = SyncStatus {
+                state: SyncState::Synced,
+                height,
+                target_height,
+                blocks_per_second,
+                eta: Some(Duration::ZERO),
+            };
+            self.status.record(status);
+            // A progress line has been logged iff a poll has found the indexer behind the network.
+            let completed = !self.synced && self.last_log.is_some();
+            self.synced = true;
+            if completed {
+                println!("{}", status);
+                return Some(status);
+            }
+            return None;
+        }
+
+        let eta = match self.blocks_per_second {
+            Some(rate) if rate > 0.0 => Some(Duration::from_secs_f64(
+                (target_height - height) as f64 / rate,
+            )),
+            _ => None,
+        };
+        let status = SyncStatus {
+            state: SyncState::Syncing,
+            height,
+            target_height,
+            blocks_per_second,
+            eta,
+        };
+        self.status.record(status);
+        let due = self.last_log.map_or(true, |last_log| {
+            now.saturating_duration_since(last_log) >= self.config.log_interval
+        });
+        if due {
+            self.last_log = Some(now);
+            println!("{}", status);
+            return Some(status);
+        }
+        None
+    }
+}
@@ -0,0 +1,998 @@
+//! Darkside-style scripted validator for deterministic chain and mempool tests.
+//!
+//! [`FakeValidator`] serves the subset of the zcashd / zebrad JsonRPC interface used by Zingo-Indexer from an
+//! in-process chain whose blocks and mempool are scripted by the test, so reorgs and mempool changes can be
+//! reproduced without a live node:
+//! - `getblockchaininfo`, network upgrades are scripted with [`FakeValidator::set_network_upgrade`], `getblockcount`,
+//!   `getbestblockhash`, `getblockhash`
+//! - `getblock`, verbosity 1 (verbosity 2 is answered as verbosity 1) with a Merkle root computed from the block's
+//!   txids when they are all 32 byte hashes and the value pools scripted with [`FakeValidator::set_value_pools`],
+//!   verbosity 0 for blocks mined with [`FakeValidator::append_full_blocks`]
+//! - `getrawmempool`, verbose entries report the size of the raw transaction and the fee set with
+//!   [`FakeValidator::set_transaction_fee`], can be disabled with [`FakeValidator::set_mempool_disabled`]
+//! - `getrawtransaction`
+//! - `sendrawtransaction`, the transaction is added to the mempool under the double SHA-256 txid of its raw bytes
+//! - `getaddressutxos`, transparent outputs are scripted with [`FakeValidator::add_utxo`]
+//! - `z_gettreestate`, Orchard tree states are empty below the scripted Orchard activation height
+//!
+//! Blocks hold no transaction data beyond the txids of the transactions mined into them, except full blocks which
+//! are serialized with a header and a coinbase transaction so they can be parsed into compact blocks. Tree states are note
+//! commitment trees of placeholder nodes unique to each block, grown by the note commitments scripted with
+//! [`FakeValidator::set_note_commitments`] for each mined transaction.
+//!
+//! Requests are counted by method and can be required to carry the node's credentials, responses can be delayed to
+//! script a slow validator, and requests of a method can be scripted to fail, e.g. to reproduce the transient
+//! not-found errors seen while the node switches chains. Raw blocks can be delayed by height, to script blocks fetched
+//! concurrently completing out of order.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use serde_json::{json, Value};
+use zaino_fetch::{
+    chain::{
+        frontier::TreeFrontier,
+        merkle::{double_sha256, merkle_root},
+        reorg::ChainTip,
+        utils::CompactSize,
+    },
+    jsonrpc::response::{GetBlockHash, ValuePoolBalance},
+    primitives::{
+        block::{BlockHash, MerkleRoot},
+        chain::NetworkUpgrade,
+        transaction::TransactionHash,
+    },
+};
+
+/// Unix epoch time of the genesis block.
+const GENESIS_TIME: i64 = 1_700_000_000;
+
+/// Time between blocks, in seconds.
+const BLOCK_SPACING: i64 = 75;
+
+/// Consensus branch id reported for the tip and the next block (NU5).
+const CONSENSUS_BRANCH_ID: &str = "c2d6d0b4";
+
+/// Consensus branch id of the coinbase transactions of full blocks (NU5).
+const NU5_BRANCH_ID: u32 = 0xC2D6D0B4;
+
+/// Block subsidy paid by the coinbase transactions of full blocks, in zatoshis.
+const BLOCK_SUBSIDY: u64 = 625_000_000;
+
+/// A block of the scripted chain.
+#[derive(Debug, Clone)]
+struct FakeBlock {
+    /// Hash of the block.
+    hash: BlockHash,
+    /// Unix epoch time when the block was mined.
+    time: i64,
+    /// Txids of the transactions mined into the block.
+    txids: Vec<String>,
+    /// Number of Sapling note commitments in the chain up to and including the block.
+    sapling_tree_size: u64,
+    /// Number of Orchard note commitments in the chain up to and including the block.
+    orchard_tree_size: u64,
+    /// Serialized block served by verbosity 0 `getblock`, None for blocks holding only txids.
+    raw: Option<Vec<u8>>,
+}
+
+impl FakeBlock {
+    /// Returns the Merkle root of the block's transactions, None if the block holds no transactions or a txid
+    /// is not a 32 byte hash.
+    fn merkle_root(&self) -> Option<MerkleRoot> {
+        let txids = self
+            .txids
+            .iter()
+            .map(|txid| txid.parse::<TransactionHash>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        merkle_root(&txids)
+    }
+}
+
+/// An unspent transparent output of the scripted chain.
+#[derive(Debug, Clone)]
+struct FakeUtxo {
+    /// Base58check encoded address the output pays to.
+    address: String,
+    /// Txid of the transaction holding the output.
+    txid: String,
+    /// Index of the output in its transaction.
+    output_index: u32,
+    /// Value of the output, in zatoshis.
+    satoshis: u64,
+    /// Height of the block the output was mined in.
+    height: u32,
+}
+
+/// Chain and mempool state of a [`FakeValidator`].
+#[derive(Debug)]
+struct FakeChain {
+    /// Best chain blocks, indexed by height.
+    blocks: Vec<FakeBlock>,
+    /// Number of reorgs scripted, mixed into block hashes so competing chains never share a hash above the fork.
+    branch: u8,
+    /// Raw transactions keyed by txid, both mined and held in the mempool.
+    transactions: HashMap<String, Vec<u8>>,
+    /// Txids held in the mempool, in insertion order.
+    mempool: BTreeMap<u64, String>,
+    /// Insertion counter used to order the mempool.
+    mempool_counter: u64,
+    /// Fees in zatoshis reported by verbose `getrawmempool`, keyed by txid, 0 if not set.
+    fees: HashMap<String, u64>,
+    /// Sapling and Orchard note commitments added to the trees by each transaction, keyed by txid, none if not set.
+    note_commitments: HashMap<String, (u64, u64)>,
+    /// Height of NU5 activation, blocks below it hold no Orchard tree state.
+    orchard_activation_height: u32,
+    /// Network height reported by `getblockchaininfo`, the tip height if None.
+    estimated_height: Option<u32>,
+    /// Network upgrades reported by `getblockchaininfo`, as (consensus branch id, upgrade, activation height).
+    network_upgrades: Vec<(String, NetworkUpgrade, u32)>,
+    /// Value pools reported by verbose `getblock`, by block height, not reported if not set.
+    value_pools: HashMap<u32, Vec<ValuePoolBalance>>,
+    /// Set to reject `getrawmempool`, scripting a node with the mempool RPC disabled.
+    mempool_disabled: bool,
+    /// Unspent transparent outputs, in insertion order.
+    utxos: Vec<FakeUtxo>,
+    /// Number of requests received, by method.
+    requests: HashMap<String, usize>,
+    /// Time each response is delayed by.
+    response_delay: Duration,
+    /// Scripted failures by method, the number of requests left to fail and the JsonRPC error they fail with.
+    failures: HashMap<String, (usize, i32, String)>,
+    /// Scripted failures of verbose `getrawmempool` requests, the number of requests left to fail and the JsonRPC
+    /// error they fail with.
+    verbose_mempool_failures: Option<(usize, i32, String)>,
+    /// Time verbosity 0 `getblock` responses are delayed by, by block height, on top of the response delay.
+    raw_block_delays: HashMap<u32, Duration>,
+    /// Heights of the raw blocks served, in the order their responses were sent.
+    raw_blocks_served: Vec<u32>,
+    /// "Authorization" header requests must be sent with, requests are not authenticated if None.
+    authorization: Option<String>,
+}
+
+impl FakeChain {
+    /// Returns a chain holding only the genesis block.
+    fn new() -> Self {
+        let mut chain = FakeChain {
+            blocks: Vec::new(),
+            branch: 0,
+            transactions: HashMap::new(),
+            mempool: BTreeMap::new(),
+            mempool_counter: 0,
+            fees: HashMap::new(),
+            note_commitments: HashMap::new(),
+            orchard_activation_height: 1,
+            estimated_height: None,
+            network_upgrades: Vec::new(),
+            value_pools: HashMap::new(),
+            mempool_disabled: false,
+            utxos: Vec::new(),
+            requests: HashMap::new(),
+            response_delay: Duration::ZERO,
+            failures: HashMap::new(),
+            verbose_mempool_failures: None,
+            raw_block_delays: HashMap::new(),
+            raw_blocks_served: Vec::new(),
+            authorization: None,
+        };
+        chain.push_block(Vec::new());
+        chain
+    }
+
+    /// Returns the tip of the chain.
+    fn tip(&self) -> ChainTip {
+        let height = self.blocks.len() as u32 - 1;
+        ChainTip {
+            height,
+            hash: self.blocks[height as usize].hash,
+        }
+    }
+
+    /// Appends a block holding `txids` to the chain.
+    fn push_block(&mut self, txids: Vec<String>) {
+        let height = self.blocks.len() as u32;
+        let mut hash = [0; 32];
+        hash[0..4].copy_from_slice(&height.to_le_bytes());
+        hash[4] = self.branch;
+        // Marks the hash as scripted, so no block hash is all zeros.
+        hash[31] = 0x0f;
+        let (mut sapling_tree_size, mut orchard_tree_size) =
+            self.blocks.last().map_or((0, 0), |parent| {
+                (parent.sapling_tree_size, parent.orchard_tree_size)
+            });
+        for txid in &txids {
+            if let Some((sapling, orchard)) = self.note_commitments.get(txid) {
+                sapling_tree_size += sapling;
+                orchard_tree_size += orchard;
+            }
+        }
+        self.blocks.push(FakeBlock {
+            hash: BlockHash(hash),
+            time: GENESIS_TIME + BLOCK_SPACING * height as i64,
+            txids,
+            sapling_tree_size,
+            orchard_tree_size,
+            raw: None,
+        });
+    }
+
+    /// Appends a full block holding only a coinbase transaction to the chain, the mempool is left untouched.
+    ///
+    /// The block's hash is the double SHA-256 of its header and the coinbase txid the double SHA-256 of its raw bytes.
+    fn push_full_block(&mut self) {
+        let height = self.blocks.len() as u32;
+        let time = GENESIS_TIME + BLOCK_SPACING * height as i64;
+        let coinbase = coinbase_transaction(height);
+        let coinbase_txid = double_sha256(&coinbase);
+        let (prev_hash, sapling_tree_size, orchard_tree_size) =
+            self.blocks.last().map_or(([0; 32], 0, 0), |parent| {
+                (
+                    parent.hash.0,
+                    parent.sapling_tree_size,
+                    parent.orchard_tree_size,
+                )
+            });
+        let mut nonce = [0; 32];
+        nonce[0] = self.branch;
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&4u32.to_le_bytes());
+        raw.extend_from_slice(&prev_hash);
+        raw.extend_from_slice(&coinbase_txid);
+        // Block commitments.
+        raw.extend_from_slice(&[0; 32]);
+        raw.extend_from_slice(&(time as u32).to_le_bytes());
+        // Difficulty bits.
+        raw.extend_from_slice(&[0x07; 4]);
+        raw.extend_from_slice(&nonce);
+        write_compact_size(&mut raw, 1344);
+        raw.extend(std::iter::repeat(0).take(1344));
+        let hash = double_sha256(&raw);
+        write_compact_size(&mut raw, 1);
+        raw.extend(coinbase);
+        self.blocks.push(FakeBlock {
+            hash: BlockHash(hash),
+            time,
+            txids: vec![TransactionHash(coinbase_txid).to_string()],
+            sapling_tree_size,
+            orchard_tree_size,
+            raw: Some(raw),
+        });
+    }
+
+    /// Returns the height of the full block requested by a verbosity 0 `getblock` request, None for other requests.
+    fn raw_block_request_height(&self, request: &Value) -> Option<u32> {
+        let params = request.get("params")?.as_array()?;
+        if request.get("method")?.as_str()? != "getblock" || params.get(1)?.as_u64()? != 0 {
+            return None;
+        }
+        let height = self.block_height(params.first()).ok()?;
+        self.blocks[height as usize].raw.as_ref().map(|_| height)
+    }
+
+    /// Removes and returns the txids held in the mempool, in insertion order.
+    fn drain_mempool(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.mempool).into_values().collect()
+    }
+
+    /// Adds a transaction to the mempool, a transaction already held keeps its position.
+    fn insert_mempool_transaction(&mut self, txid: String, raw_transaction: Vec<u8>) {
+        self.transactions.insert(txid.clone(), raw_transaction);
+        if !self.mempool.values().any(|held| *held == txid) {
+            self.mempool_counter += 1;
+            let counter = self.mempool_counter;
+            self.mempool.insert(counter, txid);
+        }
+    }
+
+    /// Returns the height of the block holding `txid`, None if the transaction is not mined.
+    fn mined_height(&self, txid: &str) -> Option<u32> {
+        self.blocks
+            .iter()
+            .position(|block| block.txids.iter().any(|mined| mined == txid))
+            .map(|height| height as u32)
+    }
+
+    /// Answers a single JsonRPC request, returns the result or the (code, message) error.
+    fn handle(&mut self, method: &str, params: &[Value]) -> Result<Value, (i32, String)> {
+        match method {
+            "getinfo" => Ok(json!({
+                "build": "v0.0.0-fake",
+                "subversion": "/FakeValidator:0.0.0/",
+            })),
+            "getblockchaininfo" => {
+                let tip = self.tip();
+                let upgrades: serde_json::Map<String, Value> = self
+                    .network_upgrades
+                    .iter()
+                    .map(|(branch_id, upgrade, activation_height)| {
+                        let status = if *activation_height <= tip.height {
+                            "active"
+                        } else {
+                            "pending"
+                        };
+                        let info = json!({
+                            "name": upgrade,
+                            "activationheight": activation_height,
+                            "status": status,
+                        });
+                        (branch_id.clone(), info)
+                    })
+                    .collect();
+                Ok(json!({
+                    "chain": "regtest",
+                    "blocks": tip.height,
+                    "bestblockhash": GetBlockHash(tip.hash),
+                    "estimatedheight": self.estimated_height.unwrap_or(tip.height),
+                    "upgrades": upgrades,
+                    "consensus": {
+                        "chaintip": CONSENSUS_BRANCH_ID,
+                        "nextblock": CONSENSUS_BRANCH_ID,
+                    },
+                }))
+            }
+            "getblockcount" => Ok(json!(self.tip().height)),
+            "getbestblockhash" => Ok(json!(GetBlockHash(self.tip().hash))),
+            "getblockhash" => {
+                let height = params
+                    .first()
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| (-8, "Invalid block height".to_string()))?;
+                self.blocks
+                    .get(height as usize)
+                    .map(|block| json!(GetBlockHash(block.hash)))
+                    .ok_or_else(|| (-8, "Block height out of range".to_string()))
+            }
+            "getblock" => {
+                let height = self.block_height(params.first())?;
+                let block = &self.blocks[height as usize];
+                if params.get(1).and_then(Value::as_u64) == Some(0) {
+                    return match &block.raw {
+                        Some(raw) => Ok(json!(hex::encode(raw))),
+                        None => Err((
+                            -8,
+                            "Verbosity 0 is only supported for full blocks".to_string(),
+                        )),
+                    };
+                }
+                let mut response = json!({
+                    "hash": GetBlockHash(block.hash),
+                    "confirmations": self.tip().height - height + 1,
+                    "height": height,
+                    "time": block.time,
+                    "tx": block.txids,
+                    "trees": {"sapling": {"size": 0}, "orchard": {"size": 0}},
+                });
+                if let Some(merkle_root) = block.merkle_root() {
+                    response["merkleroot"] = json!(merkle_root);
+                }
+                if let Some(value_pools) = self.value_pools.get(&height) {
+                    response["valuePools"] = json!(value_pools);
+                }
+                Ok(response)
+            }
+            "getrawmempool" => {
+                if self.mempool_disabled {
+                    return Err((-32601, "Method not found".to_string()));
+                }
+                if params.first().and_then(Value::as_bool) == Some(true) {
+                    let tip = self.tip();
+                    let time = self.blocks[tip.height as usize].time;
+                    let entries: serde_json::Map<String, Value> = self
+                        .mempool
+                        .values()
+                        .map(|txid| {
+                            let size = self.transactions.get(txid).map_or(0, Vec::len);
+                            let fee = self.fees.get(txid).copied().unwrap_or(0);
+                            let entry = json!({
+                                "size": size,
+                                "fee": fee as f64 / 100_000_000.0,
+                                "time": time,
+                                "height": tip.height,
+                            });
+                            (txid.clone(), entry)
+                        })
+                        .collect();
+                    return Ok(Value::Object(entries));
+                }
+                Ok(json!(self.mempool.values().collect::<Vec<_>>()))
+            }
+            "getaddressutxos" => {
+                // zcashd takes `{"addresses": [..]}`, a bare list of addresses is also accepted.
+                let addresses = params
+                    .first()
+                    .map(|param| param.get("addresses").unwrap_or(param))
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| (-8, "Invalid addresses".to_string()))?;
+                Ok(json!(self
+                    .utxos
+                    .iter()
+                    .filter(|utxo| addresses.iter().any(|address| *address == utxo.address))
+                    .map(|utxo| json!({
+                        "address": utxo.address,
+                        "txid": utxo.txid,
+                        "outputIndex": utxo.output_index,
+                        "script": format!("76a914{}88ac", "00".repeat(20)),
+                        "satoshis": utxo.satoshis,
+                        "height": utxo.height,
+                    }))
+                    .collect::<Vec<_>>()))
+            }
+            "getrawtransaction" => {
+                let txid = params
+                    .first()
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| (-8, "Invalid txid".to_string()))?;
+                let raw = self
+                    .transactions
+                    .get(txid)
+                    .ok_or_else(|| (-5, "No such mempool or blockchain transaction".to_string()))?;
+                let hex = hex::encode(raw);
+                if params.get(1).and_then(Value::as_u64).unwrap_or(0) == 0 {
+                    return Ok(json!(hex));
+                }
+                match self.mined_height(txid) {
+                    Some(height) => {
+                        let block = &self.blocks[height as usize];
+                        Ok(json!({
+                            "hex": hex,
+                            "txid": txid,
+                            "height": height,
+                            "confirmations": self.tip().height - height + 1,
+                            "blockhash": GetBlockHash(block.hash),
+                            "blocktime": block.time,
+                        }))
+                    }
+                    None => Ok(json!({"hex": hex, "txid": txid})),
+                }
+            }
+            "sendrawtransaction" => {
+                let raw_transaction = params
+                    .first()
+                    .and_then(Value::as_str)
+                    .and_then(|hex_tx| hex::decode(hex_tx).ok())
+                    .ok_or_else(|| (-22, "TX decode failed".to_string()))?;
+                let txid = TransactionHash(double_sha256(&raw_transaction)).to_string();
+                self.insert_mempool_transaction(txid.clone(), raw_transaction);
+                Ok(json!(txid))
+            }
+            "z_gettreestate" => {
+                let height = self.block_height(params.first())?;
+                let block = &self.blocks[height as usize];
+                let orchard = if height >= self.orchard_activation_height {
+                    json!({"commitments": {"finalState": placeholder_tree_state(0x02, block, block.orchard_tree_size)}})
+                } else {
+                    json!({"commitments": {}})
+                };
+                Ok(json!({
+                    "hash": GetBlockHash(block.hash),
+                    "height": height,
+                    "time": block.time,
+                    "sapling": {"commitments": {"finalState": placeholder_tree_state(0x01, block, block.sapling_tree_size)}},
+                    "orchard": orchard,
+                }))
+            }
+            _ => Err((-32601, "Method not found".to_string())),
+        }
+    }
+
+    /// Returns the height of the best chain block identified by a `getblock` hash or height parameter.
+    fn block_height(&self, block_id: Option<&Value>) -> Result<u32, (i32, String)> {
+        let block_id = match block_id {
+            Some(Value::String(block_id)) => block_id.clone(),
+            Some(Value::Number(height)) => height.to_string(),
+            _ => return Err((-8, "Invalid block identifier".to_string())),
+        };
+        if block_id.len() == 64 {
+            let hash: GetBlockHash = serde_json::from_value(json!(block_id))
+                .map_err(|_| (-8, "Invalid block hash".to_string()))?;
+            return self
+                .blocks
+                .iter()
+                .position(|block| block.hash == hash.0)
+                .map(|height| height as u32)
+                .ok_or_else(|| (-5, "Block not found".to_string()));
+        }
+        match block_id.parse::<u32>() {
+            Ok(height) if (height as usize) < self.blocks.len() => Ok(height),
+            Ok(_) => Err((-8, "Block height out of range".to_string())),
+            Err(_) => Err((-8, "Invalid block identifier".to_string())),
+        }
+    }
+}
+
+/// Returns the hex-encoded tree state of `size` note commitments at `block`, its nodes are placeholders tagged with
+/// the pool's `pool_tag`.
+fn placeholder_tree_state(pool_tag: u8, block: &FakeBlock, size: u64) -> String {
+    let node = |level: u8| {
+        let mut node = block.hash.0;
+        node[5] = pool_tag;
+        node[6] = level;
+        node
+    };
+    let ommer_count = size.saturating_sub(1).count_ones() as u8;
+    let frontier = TreeFrontier {
+        size,
+        leaf: (size > 0).then(|| node(0)),
+        ommers: (1..=ommer_count).map(node).collect(),
+    };
+    hex::encode(frontier.to_bytes())
+}
+
+/// Returns a v5 coinbase transaction for the block at `height`, paying the block subsidy to an empty script.
+fn coinbase_transaction(height: u32) -> Vec<u8> {
+    let mut raw = Vec::new();
+    raw.extend_from_slice(&((1u32 << 31) | 5).to_le_bytes());
+    // Version group id.
+    raw.extend_from_slice(&0x26A7270Au32.to_le_bytes());
+    raw.extend_from_slice(&NU5_BRANCH_ID.to_le_bytes());
+    // Lock time and expiry height.
+    raw.extend_from_slice(&[0; 8]);
+    // A single input spending the null outpoint, its script pushes the block height.
+    write_compact_size(&mut raw, 1);
+    raw.extend_from_slice(&[0; 32]);
+    raw.extend_from_slice(&u32::MAX.to_le_bytes());
+    write_compact_size(&mut raw, 5);
+    raw.push(0x04);
+    raw.extend_from_slice(&height.to_le_bytes());
+    raw.extend_from_slice(&u32::MAX.to_le_bytes());
+    // A single output with an empty script.
+    write_compact_size(&mut raw, 1);
+    raw.extend_from_slice(&BLOCK_SUBSIDY.to_le_bytes());
+    write_compact_size(&mut raw, 0);
+    // No Sapling spends, Sapling outputs or Orchard actions.
+    write_compact_size(&mut raw, 0);
+    write_compact_size(&mut raw, 0);
+    write_compact_size(&mut raw, 0);
+    raw
+}
+
+/// Appends `size` to `raw` as a CompactSize.
+fn write_compact_size(raw: &mut Vec<u8>, size: usize) {
+    CompactSize::write(raw, size).expect("Writing to a Vec does not fail");
+}
+
+/// In-process validator serving a scripted chain over JsonRPC, see the [module docs](self).
+///
+/// The chain starts with only the genesis block at height 0.
+pub struct FakeValidator {
+    /// Scripted chain and mempool.
+    chain: Arc<Mutex<FakeChain>>,
+    /// Address the JsonRPC server is bound to.
+    addr: SocketAddr,
+    /// Number of connections accepted by the JsonRPC server.
+    connections: Arc<AtomicUsize>,
+    /// Handle of the JsonRPC server task.
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl FakeValidator {
+    /// Spawns a fake validator listening on a free localhost port.
+    pub async fn spawn() -> Self {
+        let chain = Arc::new(Mutex::new(FakeChain::new()));
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")
+            .expect("Failed to bind fake validator listener");
+        listener
+            .set_nonblocking(true)
+            .expect("Failed to set fake validator listener to non-blocking");
+        let addr = listener
+            .local_addr()
+            .expect("Failed to read fake validator address");
+        let connections = Arc::new(AtomicUsize::new(0));
+        let service_chain = chain.clone();
+        let service_connections = connections.clone();
+        let make_service = make_service_fn(move |_| {
+            service_connections.fetch_add(1, Ordering::SeqCst);
+            let chain = service_chain.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle_request(chain.clone(), request)
+                }))
+            }
+        });
+        let server = Server::from_tcp(listener)
+            .expect("Failed to start fake validator server")
+            .serve(make_service);
+        let handle = tokio::spawn(async move {
+            if let Err(e) = server.await {
+                eprintln!("Fake validator server error: {}.", e);
+            }
+        });
+        FakeValidator {
+            chain,
+            addr,
+            connections,
+            handle,
+        }
+    }
+
+    /// Returns the uri of the JsonRPC server, JsonRpcConnectors created for it may use any credentials unless
+    /// [`FakeValidator::require_credentials`] is set.
+    pub fn uri(&self) -> http::Uri {
+        format!("http://{}", self.addr)
+            .parse()
+            .expect("Failed to build fake validator uri")
+    }
+
+    /// Returns the port the JsonRPC server is bound to.
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// Returns the number of connections accepted by the JsonRPC server.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    /// Answers requests not authenticated with the Basic credentials `user` and `password` with 401 Unauthorized,
+    /// scripting a node only accepting its cookie, e.g. the user `__cookie__` and the cookie file's password.
+    pub fn require_credentials(&self, user: &str, password: &str) {
+        self.lock().authorization = Some(format!(
+            "Basic {}",
+            base64::encode(format!("{}:{}", user, password))
+        ));
+    }
+
+    /// Returns the number of requests received for the JsonRPC `method`, including requests of batches.
+    pub fn requests(&self, method: &str) -> usize {
+        self.lock().requests.get(method).copied().unwrap_or(0)
+    }
+
+    /// Delays each response by `delay`, scripting a slow validator.
+    pub fn set_response_delay(&self, delay: Duration) {
+        self.lock().response_delay = delay;
+    }
+
+    /// Fails the next `count` requests for the JsonRPC `method` with the JsonRPC error `code` and `message`.
+    pub fn fail_requests(&self, method: impl Into<String>, count: usize, code: i32, message: &str) {
+        self.lock()
+            .failures
+            .insert(method.into(), (count, code, message.to_string()));
+    }
+
+    /// Fails the next `count` verbose `getrawmempool` requests with the JsonRPC error `code` and `message`,
+    /// non-verbose requests are served as usual.
+    pub fn fail_verbose_mempool_requests(&self, count: usize, code: i32, message: &str) {
+        self.lock().verbose_mempool_failures = Some((count, code, message.to_string()));
+    }
+
+    /// Returns the tip of the best chain.
+    pub fn tip(&self) -> ChainTip {
+        self.lock().tip()
+    }
+
+    /// Returns the hash of the best chain block at `height`, None if `height` is above the tip.
+    pub fn block_hash(&self, height: u32) -> Option<BlockHash> {
+        self.lock()
+            .blocks
+            .get(height as usize)
+            .map(|block| block.hash)
+    }
+
+    /// Sets the height of NU5 activation, `z_gettreestate` returns an empty Orchard tree state for blocks below it.
+    ///
+    /// Orchard is active from height 1 by default, matching the regtest network upgrades used by the test manager.
+    pub fn set_orchard_activation_height(&self, height: u32) {
+        self.lock().orchard_activation_height = height;
+    }
+
+    /// Rejects `getrawmempool` requests while `disabled`, scripting a node with the mempool RPC disabled.
+    pub fn set_mempool_disabled(&self, disabled: bool) {
+        self.lock().mempool_disabled = disabled;
+    }
+
+    /// Sets the network height reported by `getblockchaininfo`, scripting a validator still syncing the chain.
+    pub fn set_estimated_height(&self, height: u32) {
+        self.lock().estimated_height = Some(height);
+    }
+
+    /// Reports the network upgrade `upgrade` under `branch_id` in `getblockchaininfo`, activating at
+    /// `activation_height`.
+    ///
+    /// The upgrade is reported active once the tip reaches its activation height and pending before. No network
+    /// upgrades are reported by default.
+    pub fn set_network_upgrade(
+        &self,
+        branch_id: impl Into<String>,
+        upgrade: NetworkUpgrade,
+        activation_height: u32,
+    ) {
+        self.lock()
+            .network_upgrades
+            .push((branch_id.into(), upgrade, activation_height));
+    }
+
+    /// Reports `value_pools` as the chain value of each value pool in verbose `getblock` responses for the block at
+    /// `height`.
+    pub fn set_value_pools(&self, height: u32, value_pools: Vec<ValuePoolBalance>) {
+        self.lock().value_pools.insert(height, value_pools);
+    }
+
+    /// Mines `count` blocks, the mempool is mined into the first new block. Returns the new tip.
+    pub fn append_blocks(&self, count: u32) -> ChainTip {
+        let mut chain = self.lock();
+        for index in 0..count {
+            let txids = if index == 0 {
+                chain.drain_mempool()
+            } else {
+                Vec::new()
+            };
+            chain.push_block(txids);
+        }
+        chain.tip()
+    }
+
+    /// Mines `count` full blocks, each holding only a coinbase transaction, the mempool is left untouched. Returns the
+    /// new tip.
+    ///
+    /// Unlike scripted blocks, full blocks are also served by verbosity 0 `getblock`, so they can be fetched as
+    /// compact blocks.
+    pub fn append_full_blocks(&self, count: u32) -> ChainTip {
+        let mut chain = self.lock();
+        for _ in 0..count {
+            chain.push_full_block();
+        }
+        chain.tip()
+    }
+
+    /// Delays verbosity 0 `getblock` responses for the full block at `height` by `delay`, on top of the response delay.
+    pub fn set_raw_block_delay(&self, height: u32, delay: Duration) {
+        self.lock().raw_block_delays.insert(height, delay);
+    }
+
+    /// Returns the heights of the raw blocks served by verbosity 0 `getblock`, in the order their responses were sent.
+    pub fn raw_blocks_served(&self) -> Vec<u32> {
+        self.lock().raw_blocks_served.clone()
+    }
+
+    /// Rolls back the blocks above `fork_height` and mines a competing chain up to `new_tip_height`. Returns the new tip.
+    ///
+    /// Transactions mined in the rolled back blocks are returned to the mempool, except the coinbase transactions of full
+    /// blocks. The competing chain holds no transactions.
+    /// Panics if `fork_height` is not below the current tip or `new_tip_height` is not above `fork_height`.
+    pub fn reorg(&self, fork_height: u32, new_tip_height: u32) -> ChainTip {
+        let mut chain = self.lock();
+        assert!(
+            fork_height < chain.tip().height,
+            "Fork height must be below the tip"
+        );
+        assert!(
+            new_tip_height > fork_height,
+            "New tip must be above the fork height"
+        );
+        let rolled_back = chain.blocks.split_off(fork_height as usize + 1);
+        for txid in rolled_back
+            .into_iter()
+            .filter(|block| block.raw.is_none())
+            .flat_map(|block| block.txids)
+        {
+            chain.mempool_counter += 1;
+            let counter = chain.mempool_counter;
+            chain.mempool.insert(counter, txid);
+        }
+        chain.branch = chain.branch.wrapping_add(1);
+        while chain.tip().height < new_tip_height {
+            chain.push_block(Vec::new());
+        }
+        chain.tip()
+    }
+
+    /// Adds a transaction to the mempool.
+    pub fn add_mempool_transaction(&self, txid: impl Into<String>, raw_transaction: Vec<u8>) {
+        self.lock()
+            .insert_mempool_transaction(txid.into(), raw_transaction);
+    }
+
+    /// Sets the fee, in zatoshis, reported for `txid` by verbose `getrawmempool`.
+    pub fn set_transaction_fee(&self, txid: impl Into<String>, fee: u64) {
+        self.lock().fees.insert(txid.into(), fee);
+    }
+
+    /// Sets the Sapling and Orchard note commitments `txid` adds to the trees once mined.
+    pub fn set_note_commitments(&self, txid: impl Into<String>, sapling: u64, orchard: u64) {
+        self.lock()
+            .note_commitments
+            .insert(txid.into(), (sapling, orchard));
+    }
+
+    /// Removes a transaction from the mempool without mining it, returns false if it was not held.
+    pub fn remove_mempool_transaction(&self, txid: &str) -> bool {
+        let mut chain = self.lock();
+        let key = chain
+            .mempool
+            .iter()
+            .find(|(_, held)| held.as_str() == txid)
+            .map(|(key, _)| *key);
+        match key {
+            Some(key) => {
+                chain.mempool.remove(&key);
+                if chain.mined_height(txid).is_none() {
+                    chain.transactions.remove(txid);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds an unspent transparent output paying `satoshis` to `address`, mined at `height`.
+    pub fn add_utxo(
+        &self,
+        address: impl Into<String>,
+        txid: impl Into<String>,
+        output_index: u32,
+        satoshis: u64,
+        height: u32,
+    ) {
+        self.lock().utxos.push(FakeUtxo {
+            address: address.into(),
+            txid: txid.into(),
+            output_index,
+            satoshis,
+            height,
+        });
+    }
+
+    /// Marks a transparent output as spent, returns false if it was not held.
+    pub fn spend_utxo(&self, txid: &str, output_index: u32) -> bool {
+        let mut chain = self.lock();
+        let held = chain.utxos.len();
+        chain
+            .utxos
+            .retain(|utxo| !(utxo.txid == txid && utxo.output_index == output_index));
+        chain.utxos.len() < held
+    }
+
+    /// Returns the txids held in the mempool, in insertion order.
+    pub fn mempool(&self) -> Vec<String> {
+        self.lock().mempool.values().cloned().collect()
+    }
+
+    /// Stops the JsonRPC server.
+    pub fn shutdown(self) {
+        self.handle.abort();
+    }
+
+    /// Locks the scripted chain.
+    fn lock(&self) -> std::sync::MutexGuard<'_, FakeChain> {
+        self.chain
+            .lock()
+            .expect("Fake validator chain lock poisoned")
+    }
+}
+
+/// Answers a single or batched JsonRPC request against the scripted chain.
+async fn handle_request(
+    chain: Arc<Mutex<FakeChain>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let authorized = match chain.lock() {
+        Ok(chain) => chain.authorization.as_ref().map_or(true, |authorization| {
+            request
+                .headers()
+                .get(hyper::header::AUTHORIZATION)
+                .is_some_and(|header| header.as_bytes() == authorization.as_bytes())
+        }),
+        Err(_) => true,
+    };
+    if !authorized {
+        return Ok(Response::builder()
+            .status(http::StatusCode::UNAUTHORIZED)
+            .body(Body::empty())
+            .expect("Failed to build fake validator response"));
+    }
+    let body = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(body) => body,
+        Err(_) => return Ok(json_response(&rpc_error(Value::Null, -32700, "Read error"))),
+    };
+    let request: Value = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(_) => {
+            return Ok(json_response(&rpc_error(
+                Value::Null,
+                -32700,
+                "Parse error",
+            )))
+        }
+    };
+    let (response, delay, raw_heights) = match chain.lock() {
+        Ok(mut chain) => {
+            let requests: Vec<&Value> = match &request {
+                Value::Array(batch) => batch.iter().collect(),
+                request => vec![request],
+            };
+            let raw_heights: Vec<u32> = requests
+                .iter()
+                .filter_map(|request| chain.raw_block_request_height(request))
+                .collect();
+            let raw_delay = raw_heights
+                .iter()
+                .filter_map(|height| chain.raw_block_delays.get(height).copied())
+                .max()
+                .unwrap_or(Duration::ZERO);
+            let response = match request {
+                Value::Array(batch) => Value::Array(
+                    batch
+                        .iter()
+                        .map(|request| respond(&mut chain, request))
+                        .collect(),
+                ),
+                request => respond(&mut chain, &request),
+            };
+            (response, chain.response_delay + raw_delay, raw_heights)
+        }
+        Err(_) => (
+            rpc_error(Value::Null, -32603, "Fake validator chain lock poisoned"),
+            Duration::ZERO,
+            Vec::new(),
+        ),
+    };
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+    if !raw_heights.is_empty() {
+        if let Ok(mut chain) = chain.lock() {
+            chain.raw_blocks_served.extend(raw_heights);
+        }
+    }
+    Ok(json_response(&response))
+}
+
+/// Returns the JsonRPC response to a single request, counting the request.
+fn respond(chain: &mut FakeChain, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+    *chain.requests.entry(method.to_string()).or_insert(0) += 1;
+    let params = request
+        .get("params")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    if let Some((remaining, code, message)) = chain.failures.get_mut(method) {
+        if *remaining > 0 {
+            *remaining -= 1;
+            return rpc_error(id, *code, message);
+        }
+    }
+    if method == "getrawmempool" && params.first().and_then(Value::as_bool) == Some(true) {
+        if let Some((remaining, code, message)) = &mut chain.verbose_mempool_failures {
+            if *remaining > 0 {
+                *remaining -= 1;
+                return rpc_error(id, *code, message);
+            }
+        }
+    }
+    match chain.handle(method, &params) {
+        Ok(result) => json!({"jsonrpc": "2.0", "result": result, "error": null, "id": id}),
+        Err((code, message)) => rpc_error(id, code, &message),
+    }
+}
+
+/// Returns a JsonRPC error response.
+fn rpc_error(id: Value, code: i32, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "result": null,
+        "error": {"code": code, "message": message},
+        "id": id,
+    })
+}
+
+/// Wraps a JsonRPC response body in an HTTP response.
+fn json_response(body: &Value) -> Response<Body> {
+    Response::builder()
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("Failed to build fake validator response")
+}
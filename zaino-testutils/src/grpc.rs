@@ -0,0 +1,108 @@
+//! gRPC service fixtures serving from a [`FakeValidator`].
+//!
+//! [`GrpcClientBuilder`] builds the [`GrpcClient`] a worker would serve requests with, so tests can call the
+//! `CompactTxStreamer` methods directly against a scripted chain.
+
+use std::sync::{atomic::AtomicBool, Arc};
+
+use zaino_fetch::{
+    chain::blockcache::BlockCache,
+    jsonrpc::{
+        connection_pool::{ConnectionPool, ConnectionPoolConfig},
+        connector::{Auth, JsonRpcConnector},
+    },
+    primitives::chain::NetworkKind,
+};
+use zaino_serve::{
+    rpc::GrpcClient,
+    server::{capabilities::ServerFeatures, method_filter::MethodFilter},
+};
+use zaino_state::fetch::{FetchService, NoLocalState};
+
+use crate::darkside::FakeValidator;
+
+/// Builds a [`GrpcClient`] serving regtest requests from a [`FakeValidator`].
+///
+/// By default the client has a 16 block cache, a prefetch window of 16 blocks, every method enabled and a
+/// [`FetchService`] falling back to the validator for every method.
+pub struct GrpcClientBuilder {
+    client: GrpcClient,
+}
+
+impl GrpcClientBuilder {
+    /// Returns a builder for a client serving from `validator`.
+    pub fn new(validator: &FakeValidator) -> Self {
+        GrpcClientBuilder {
+            client: GrpcClient {
+                lightwalletd_uri: validator.uri(),
+                zebrad_uri: validator.uri(),
+                auth: None,
+                validator_pool: None,
+                connection_pool: ConnectionPool::new(ConnectionPoolConfig::default()),
+                network: NetworkKind::Regtest,
+                block_cache: BlockCache::new(16, 8),
+                fetch_service: Arc::new(FetchService::from_node(JsonRpcConnector::new_with_auth(
+                    validator.uri(),
+                    None,
+                ))),
+                block_range_prefetch: 16,
+                features: ServerFeatures::default(),
+                method_filter: MethodFilter::default(),
+                online: Arc::new(AtomicBool::new(true)),
+            },
+        }
+    }
+
+    /// Authenticates requests to the validator with `auth`, by default requests are sent without credentials.
+    ///
+    /// Replaces the fetch service with one authenticating with `auth`, set a custom fetch service afterwards.
+    pub fn with_auth(mut self, auth: Auth) -> Self {
+        self.client.fetch_service = Arc::new(FetchService::from_node(
+            JsonRpcConnector::new_with_auth(self.client.zebrad_uri.clone(), Some(auth.clone())),
+        ));
+        self.client.auth = Some(auth);
+        self
+    }
+
+    /// Serves blocks from `block_cache`, e.g. a cache warmed up by the test.
+    pub fn with_block_cache(mut self, block_cache: BlockCache) -> Self {
+        self.client.block_cache = block_cache;
+        self
+    }
+
+    /// Serves transaction, mempool and node info requests through `fetch_service`.
+    pub fn with_fetch_service(mut self, fetch_service: FetchService<NoLocalState>) -> Self {
+        self.client.fetch_service = Arc::new(fetch_service);
+        self
+    }
+
+    /// Fetches `block_range_prefetch` blocks concurrently ahead of the client in GetBlockRange streams.
+    pub fn with_block_range_prefetch(mut self, block_range_prefetch: usize) -> Self {
+        self.client.block_range_prefetch = block_range_prefetch;
+        self
+    }
+
+    /// Reports `features` in GetServerCapabilities.
+    pub fn with_features(mut self, features: ServerFeatures) -> Self {
+        self.client.features = features;
+        self
+    }
+
+    /// Serves only the methods enabled by `method_filter`.
+    pub fn with_method_filter(mut self, method_filter: MethodFilter) -> Self {
+        self.client.method_filter = method_filter;
+        self
+    }
+
+    /// Returns the client.
+    pub fn build(self) -> GrpcClient {
+        self.client
+    }
+}
+
+impl FakeValidator {
+    /// Returns a [`GrpcClient`] serving from the validator with the [`GrpcClientBuilder`] defaults.
+    pub fn grpc_client(&self) -> GrpcClient {
+        GrpcClientBuilder::new(self).build()
+    }
+}
@@ -3,7 +3,17 @@
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
-use std::io::Write;
+pub mod darkside;
+pub mod grpc;
+
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use zaino_fetch::primitives::chain::{NetworkKind, NetworkUpgrade};
 
 static CTRL_C_ONCE: std::sync::Once = std::sync::Once::new();
 
@@ -18,22 +28,23 @@ pub struct TestManager {
     pub regtest_network: zingoconfig::RegtestNetwork,
     /// Zingo-Indexer gRPC listen port.
     pub indexer_port: u16,
+    /// Zingo-Indexer gRPC Unix domain socket path.
+    pub indexer_uds_path: std::path::PathBuf,
     /// Zingo-Indexer Nym listen address.
     pub nym_addr: Option<String>,
     /// Zebrad/Zcashd JsonRpc listen port.
     pub zebrad_port: u16,
-    /// Online status of Zingo-Indexer.
-    pub online: std::sync::Arc<std::sync::atomic::AtomicBool>,
 }
 
 impl TestManager {
     /// Launches a zingo regtest manager and zingo-indexer, created TempDir for configuration and log files.
-    pub async fn launch(
-        online: std::sync::Arc<std::sync::atomic::AtomicBool>,
-    ) -> (
+    ///
+    /// The indexer is closed by passing the returned handle to [`drop_test_manager`], test managers are independent
+    /// of each other so tests can run in parallel.
+    pub async fn launch() -> (
         Self,
         zingo_testutils::regtest::ChildProcessHandler,
-        tokio::task::JoinHandle<Result<(), zainodlib::error::IndexerError>>,
+        zainodlib::indexer::IndexerHandle,
     ) {
         let lwd_port = portpicker::pick_unused_port().expect("No ports free");
         let zebrad_port = portpicker::pick_unused_port().expect("No ports free");
@@ -43,8 +54,6 @@ impl TestManager {
         let temp_conf_path = temp_conf_dir.path().to_path_buf();
         let _nym_conf_path = temp_conf_path.join("nym");
 
-        set_custom_drops(online.clone(), Some(temp_conf_path.clone()));
-
         let regtest_network = zingoconfig::RegtestNetwork::new(1, 1, 1, 1, 1, 1);
 
         let regtest_manager = zingo_testutils::regtest::RegtestManager::new(temp_conf_path.clone());
@@ -54,23 +63,64 @@ impl TestManager {
 
         // TODO: This turns nym functionality off. for nym tests we will need to add option to include nym in test manager.
         // - queue and workerpool sizes may need to be changed here.
+        let indexer_uds_path = temp_conf_path.join("zaino.sock");
         let indexer_config = zainodlib::config::IndexerConfig {
             tcp_active: true,
             listen_port: Some(indexer_port),
             nym_active: false,
             nym_conf_path: None,
             lightwalletd_port: lwd_port,
-            zebrad_port,
+            network: zaino_fetch::primitives::chain::NetworkKind::Regtest,
+            zebrad_port: Some(zebrad_port),
             node_user: Some("xxxxxx".to_string()),
             node_password: Some("xxxxxx".to_string()),
+            node_cookie_path: None,
             max_queue_size: 512,
             max_worker_pool_size: 96,
             idle_worker_pool_size: 48,
+            block_cache_capacity: None,
+            block_cache_gc_target: None,
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_client_ca_path: None,
+            metrics_listen_addr: None,
+            nym_allowed_methods: None,
+            node_request_timeout_secs: None,
+            health_listen_addr: None,
+            health_max_staleness_secs: None,
+            health_max_block_lag: None,
+            block_cache_dir: None,
+            validator_failover_uris: None,
+            mempool_poll_interval_ms: None,
+            mempool_max_poll_interval_ms: None,
+            transaction_cache_capacity: None,
+            grpc_uds_path: Some(indexer_uds_path.to_string_lossy().into_owned()),
+            grpc_reflection: Some(true),
+            new_block_channel_capacity: None,
+            grpc_max_concurrent_requests_per_connection: None,
+            grpc_max_concurrent_requests: None,
+            grpc_max_requests_per_second: None,
+            node_max_connections: Some(8),
+            warmup_blocks: None,
+            warmup_start_degraded: None,
+            reorg_confirmation_depth: Some(100),
+            block_range_prefetch: Some(16),
+            info_cache_ttl_ms: None,
+            grpc_request_log: None,
+            grpc_request_log_level: None,
+            upstream_queue_capacity: None,
+            grpc_enabled_methods: None,
+            grpc_disabled_methods: None,
+            sync_progress_log_interval_secs: Some(10),
+            nym_max_body_len: Some(2_000_000),
         };
-        let indexer_handler =
-            zainodlib::indexer::Indexer::start_indexer_service(indexer_config, online.clone())
-                .await
-                .unwrap();
+        let indexer_handler = zainodlib::indexer::Indexer::start_indexer_service(indexer_config)
+            .await
+            .unwrap();
+        set_custom_drops(
+            Some(temp_conf_path.clone()),
+            indexer_handler.shutdown_signal(),
+        );
         // NOTE: This is required to give the server time to launch, this is not used in production code but could be rewritten to improve testing efficiency.
         tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
         (
@@ -79,9 +129,9 @@ impl TestManager {
                 regtest_manager,
                 regtest_network,
                 indexer_port,
+                indexer_uds_path,
                 nym_addr: None,
                 zebrad_port,
-                online,
             },
             regtest_handler,
             indexer_handler,
@@ -122,12 +172,16 @@ impl TestManager {
 }
 
 /// Closes test manager child processes, optionally cleans configuration and log files for test.
+///
+/// Awaits the indexer closing before the regtest node is closed.
 pub async fn drop_test_manager(
     temp_conf_path: Option<std::path::PathBuf>,
     child_process_handler: zingo_testutils::regtest::ChildProcessHandler,
-    online: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    indexer_handler: zainodlib::indexer::IndexerHandle,
 ) {
-    online.store(false, std::sync::atomic::Ordering::SeqCst);
+    if let Err(e) = indexer_handler.shutdown().await {
+        eprintln!("Failed to close indexer: {}.", e);
+    }
     drop(child_process_handler);
 
     let mut temp_wallet_path = temp_conf_path.clone().unwrap();
@@ -151,12 +205,12 @@ pub async fn drop_test_manager(
     }
 }
 
-fn set_custom_drops(
-    online: std::sync::Arc<std::sync::atomic::AtomicBool>,
-    temp_conf_path: Option<std::path::PathBuf>,
-) {
-    let online_panic = online.clone();
-    let online_ctrlc = online.clone();
+/// Closes the indexer and deletes the test's temporary directories on panic or Ctrl-C.
+///
+/// The indexer is signalled through `indexer_shutdown`, see [`zainodlib::indexer::IndexerHandle::shutdown_signal`].
+fn set_custom_drops(temp_conf_path: Option<std::path::PathBuf>, indexer_shutdown: Arc<AtomicBool>) {
+    let indexer_shutdown_panic = indexer_shutdown.clone();
+    let indexer_shutdown_ctrlc = indexer_shutdown;
     let temp_conf_path_panic = temp_conf_path.clone();
     let temp_conf_path_ctrlc = temp_conf_path.clone();
 
@@ -172,7 +226,7 @@ fn set_custom_drops(
 
     std::panic::set_hook(Box::new(move |panic_info| {
         default_panic_hook(panic_info);
-        online_panic.store(false, std::sync::atomic::Ordering::SeqCst);
+        indexer_shutdown_panic.store(false, Ordering::SeqCst);
         if let Some(ref path) = temp_conf_path_panic {
             if let Err(e) = std::fs::remove_dir_all(path) {
                 eprintln!(
@@ -194,7 +248,7 @@ fn set_custom_drops(
     CTRL_C_ONCE.call_once(|| {
         ctrlc::set_handler(move || {
             println!("Received Ctrl+C, exiting.");
-            online_ctrlc.store(false, std::sync::atomic::Ordering::SeqCst);
+            indexer_shutdown_ctrlc.store(false, Ordering::SeqCst);
             if let Some(ref path) = temp_conf_path_ctrlc {
                 if let Err(e) = std::fs::remove_dir_all(path) {
                     eprintln!(
@@ -234,12 +288,14 @@ fn write_zcash_conf(dir: &std::path::Path, rpcport: u16) -> Result<(), Box<dyn s
     let file_path = dir.join("zcash.conf");
     let mut file = std::fs::File::create(file_path)?;
     writeln!(file, "regtest=1")?;
-    writeln!(file, "nuparams=5ba81b19:1 # Overwinter")?;
-    writeln!(file, "nuparams=76b809bb:1 # Sapling")?;
-    writeln!(file, "nuparams=2bb40e60:1 # Blossom")?;
-    writeln!(file, "nuparams=f5b9230b:1 # Heartwood")?;
-    writeln!(file, "nuparams=e9ff75a6:1 # Canopy")?;
-    writeln!(file, "nuparams=c2d6d0b4:1 # NU5")?;
+    for upgrade in NetworkUpgrade::ACTIVATED_UPGRADES {
+        if let (Some(branch_id), Some(height)) = (
+            upgrade.branch_id(),
+            NetworkKind::Regtest.activation_height(upgrade),
+        ) {
+            writeln!(file, "nuparams={}:{} # {}", branch_id, height.0, upgrade)?;
+        }
+    }
     writeln!(file, "txindex=1")?;
     writeln!(file, "insightexplorer=1")?;
     writeln!(file, "experimentalfeatures=1")?;
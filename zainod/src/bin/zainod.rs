@@ -2,7 +2,8 @@
 
 use clap::Parser;
 use std::path::PathBuf;
-use zainodlib::{config::load_config, indexer::Indexer};
+use zaino_fetch::error::error_chain;
+use zainodlib::{config::IndexerConfig, indexer::Indexer};
 
 #[derive(Parser, Debug)]
 #[command(name = "zindexer", about = "A server for Zingo-Indexer")]
@@ -14,11 +15,21 @@ struct Args {
 
 #[tokio::main]
 async fn main() {
-    Indexer::start(load_config(
-        &Args::parse()
-            .config
-            .unwrap_or_else(|| PathBuf::from("./zainod/zindexer.toml")),
-    ))
-    .await
-    .unwrap();
+    #[cfg(feature = "tokio-console")]
+    console_subscriber::init();
+
+    let config = match Args::parse().config {
+        Some(path) => match IndexerConfig::from_file(&path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Failed to load config: {}", error_chain(&e));
+                std::process::exit(1);
+            }
+        },
+        None => IndexerConfig::default(),
+    };
+    if let Err(e) = Indexer::start(config).await {
+        eprintln!("Zingdexer exited with error: {}", error_chain(&e));
+        std::process::exit(1);
+    }
 }
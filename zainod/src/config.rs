@@ -1,7 +1,22 @@
 //! Zaino config.
 
 use crate::error::IndexerError;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use zaino_fetch::{
+    jsonrpc::{connection_pool::ConnectionPoolConfig, connector::Auth},
+    primitives::chain::NetworkKind,
+};
+use zaino_nym::utils::NymRequestLimits;
+use zaino_serve::server::{
+    capabilities::{Feature, ServerFeatures},
+    limit::RequestLimits,
+    method_filter::{MethodFilter, GRPC_METHODS},
+    request_log::{RequestLogLevel, RequestLogger},
+};
+use zaino_state::{
+    blocks::BlockNotifierConfig, fetch::DEFAULT_INFO_CACHE_TTL, mempool::MempoolPollConfig,
+};
 
 /// Config information required for Zaino.
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -13,34 +28,426 @@ pub struct IndexerConfig {
     /// Sets the NymIngestor's and NymDispatchers status.
     pub nym_active: bool,
     /// Nym conf path used for micnet client conf.
+    ///
+    /// The Nym clients' keys and nym addresses are persisted here, keeping the advertised nym address stable across restarts.
     pub nym_conf_path: Option<String>,
     /// LightWalletD listen port [DEPRECATED].
     /// Used by nym_poc and zingo-testutils.
     pub lightwalletd_port: u16,
-    /// Full node / validator listen port.
-    pub zebrad_port: u16,
+    /// Network the full node / validator is running on, used to select default ports.
+    #[serde(default)]
+    pub network: NetworkKind,
+    /// Full node / validator listen port, defaults to the network's default JsonRPC port if None.
+    pub zebrad_port: Option<u16>,
     /// Full node Username.
     pub node_user: Option<String>,
     /// full node Password.
     pub node_password: Option<String>,
+    /// Path to the full node's `.cookie` file, used to authenticate instead of node_user / node_password if set.
+    ///
+    /// The cookie is re-read on each connection, so it may be rotated by the node while the indexer runs.
+    pub node_cookie_path: Option<String>,
     /// Maximum requests allowed in the request queue.
     pub max_queue_size: u16,
     /// Maximum workers allowed in the worker pool
     pub max_worker_pool_size: u16,
     /// Minimum number of workers held in the workerpool when idle.
     pub idle_worker_pool_size: u16,
+    /// Maximum number of compact blocks held in the block cache, caching is disabled if set to 0.
+    pub block_cache_capacity: Option<usize>,
+    /// Number of compact blocks the block cache GC trims the cache down to when idle.
+    pub block_cache_gc_target: Option<usize>,
+    /// Path to the PEM encoded TLS certificate, the gRPC server is served as plaintext if None.
+    pub tls_cert_path: Option<String>,
+    /// Path to the PEM encoded TLS private key.
+    pub tls_key_path: Option<String>,
+    /// Path to a PEM encoded CA bundle used to verify client certificates (mutual TLS).
+    pub tls_client_ca_path: Option<String>,
+    /// Address the Prometheus metrics endpoint listens at, the endpoint is disabled if None.
+    pub metrics_listen_addr: Option<std::net::SocketAddr>,
+    /// Request methods reachable over the Nym mixnet, defaults to a read-only set if None.
+    /// Sensitive methods (SendTransaction) are only reachable if listed here.
+    pub nym_allowed_methods: Option<Vec<String>>,
+    /// Seconds a JsonRPC request to the node may take before failing with a timeout, defaults to 60 if None.
+    pub node_request_timeout_secs: Option<u64>,
+    /// Address the /healthz health check endpoint listens at, the endpoint is disabled if None.
+    pub health_listen_addr: Option<std::net::SocketAddr>,
+    /// Maximum seconds since the last successful node getblockchaininfo call before /healthz reports unhealthy, defaults to 30 if None.
+    pub health_max_staleness_secs: Option<u64>,
+    /// Maximum number of blocks the node's tip may be behind its estimated height before /healthz reports unhealthy, defaults to 10 if None.
+    pub health_max_block_lag: Option<u32>,
+    /// Directory of the on-disk cache of finalized blocks and treestates, the on-disk cache is disabled if None.
+    pub block_cache_dir: Option<String>,
+    /// Backup validator JsonRPC uris, in priority order, requests fail over to these when the primary node is unreachable.
+    /// Failover is disabled if None or empty.
+    pub validator_failover_uris: Option<Vec<String>>,
+    /// Milliseconds between mempool polls while the mempool is changing, defaults to 1000 if None.
+    pub mempool_poll_interval_ms: Option<u64>,
+    /// Maximum milliseconds between mempool polls, the interval backs off toward this while the mempool is idle, defaults to 10000 if None.
+    pub mempool_max_poll_interval_ms: Option<u64>,
+    /// Maximum number of confirmed transactions held by the FetchService transaction cache, defaults to 10000 if None.
+    /// Caching is disabled if set to 0.
+    pub transaction_cache_capacity: Option<usize>,
+    /// Path of a Unix domain socket the gRPC server also listens at, disabled if None.
+    pub grpc_uds_path: Option<String>,
+    /// Serve the gRPC reflection service, for introspection by tools such as grpcurl, disabled if None or false.
+    pub grpc_reflection: Option<bool>,
+    /// Capacity of the new block broadcast channel, subscribers lagging further behind miss blocks.
+    pub new_block_channel_capacity: Option<usize>,
+    /// Maximum number of gRPC requests in flight on a single connection, further requests are rejected as resource exhausted.
+    pub grpc_max_concurrent_requests_per_connection: Option<usize>,
+    /// Maximum number of gRPC requests in flight across all connections, further requests are rejected as resource exhausted.
+    pub grpc_max_concurrent_requests: Option<usize>,
+    /// Maximum number of gRPC requests admitted per second across all connections, further requests are rejected as resource exhausted.
+    pub grpc_max_requests_per_second: Option<u32>,
+    /// Maximum number of connections open to the validator at once, shared by all JsonRPC requests.
+    pub node_max_connections: Option<usize>,
+    /// Number of recent blocks fetched into the block cache before the gRPC server starts, warm-up is disabled if None.
+    pub warmup_blocks: Option<u32>,
+    /// Start serving with cold caches if warm-up fails, rather than exiting. Defaults to false.
+    pub warmup_start_degraded: Option<bool>,
+    /// Number of confirmations after which a block is considered final, defaults to 100.
+    ///
+    /// Bounds the blocks dropped from the block cache on a reorg, the blocks written to the on-disk cache and the chain tracked for reorgs.
+    pub reorg_confirmation_depth: Option<u32>,
+    /// Number of blocks fetched concurrently ahead of the client in a GetBlockRange stream, defaults to 16.
+    ///
+    /// Blocks are still streamed in height order, larger windows hide more of the validator round-trip latency.
+    pub block_range_prefetch: Option<usize>,
+    /// Milliseconds a `getinfo` response is cached by the FetchService, defaults to 5000 if None.
+    /// Caching is disabled if set to 0.
+    pub info_cache_ttl_ms: Option<u64>,
+    /// Log every gRPC request with its method, peer, latency and status code. Defaults to false.
+    ///
+    /// Transaction payloads are logged as their txid and length, never in full.
+    pub grpc_request_log: Option<bool>,
+    /// Level gRPC requests are logged at, one of "error", "warn", "info", "debug" or "trace". Defaults to "info".
+    pub grpc_request_log_level: Option<String>,
+    /// Maximum number of FetchService JsonRPC fallbacks and gRPC requests waiting on the validator at once, defaults to
+    /// 64 if None.
+    /// Further requests are rejected with a resource exhausted error rather than queued while the validator is slow.
+    pub upstream_queue_capacity: Option<usize>,
+    /// gRPC methods served, e.g. `["GetLightdInfo", "GetBlockRange"]`, every method is served if None.
+    /// Requests for other methods are answered with unimplemented.
+    pub grpc_enabled_methods: Option<Vec<String>>,
+    /// gRPC methods not served, e.g. `["SendTransaction"]` for a read-only endpoint, takes precedence over grpc_enabled_methods.
+    pub grpc_disabled_methods: Option<Vec<String>>,
+    /// Minimum interval between two progress lines logged during the initial sync, in seconds. Defaults to 10.
+    pub sync_progress_log_interval_secs: Option<u64>,
+    /// Largest Nym request body accepted, in bytes, longer requests are rejected before they are read. Defaults to 2000000.
+    pub nym_max_body_len: Option<usize>,
 }
 
 impl IndexerConfig {
+    /// Config keys that must be present in a config file.
+    const REQUIRED_KEYS: [&'static str; 6] = [
+        "tcp_active",
+        "nym_active",
+        "lightwalletd_port",
+        "max_queue_size",
+        "max_worker_pool_size",
+        "idle_worker_pool_size",
+    ];
+
+    /// Config keys that may be omitted from a config file.
+    const OPTIONAL_KEYS: [&'static str; 42] = [
+        "network",
+        "zebrad_port",
+        "listen_port",
+        "nym_conf_path",
+        "node_user",
+        "node_password",
+        "node_cookie_path",
+        "block_cache_capacity",
+        "block_cache_gc_target",
+        "tls_cert_path",
+        "tls_key_path",
+        "tls_client_ca_path",
+        "metrics_listen_addr",
+        "nym_allowed_methods",
+        "node_request_timeout_secs",
+        "health_listen_addr",
+        "health_max_staleness_secs",
+        "health_max_block_lag",
+        "block_cache_dir",
+        "validator_failover_uris",
+        "mempool_poll_interval_ms",
+        "mempool_max_poll_interval_ms",
+        "transaction_cache_capacity",
+        "grpc_uds_path",
+        "grpc_reflection",
+        "new_block_channel_capacity",
+        "grpc_max_concurrent_requests_per_connection",
+        "grpc_max_concurrent_requests",
+        "grpc_max_requests_per_second",
+        "node_max_connections",
+        "warmup_blocks",
+        "warmup_start_degraded",
+        "reorg_confirmation_depth",
+        "block_range_prefetch",
+        "info_cache_ttl_ms",
+        "grpc_request_log",
+        "grpc_request_log_level",
+        "upstream_queue_capacity",
+        "grpc_enabled_methods",
+        "grpc_disabled_methods",
+        "sync_progress_log_interval_secs",
+        "nym_max_body_len",
+    ];
+
+    /// Loads and checks config data from the toml file at the specified path.
+    ///
+    /// Unknown keys are reported with a warning and ignored, missing required keys return a ConfigError naming the key.
+    pub fn from_file(file_path: &Path) -> Result<Self, IndexerError> {
+        let (config, unknown_keys) = Self::from_file_with_unknown_keys(file_path)?;
+        for key in unknown_keys {
+            eprintln!(
+                "Warning: Unknown key `{}` in config file {} ignored.",
+                key,
+                file_path.display()
+            );
+        }
+        Ok(config)
+    }
+
+    /// Loads and checks config data from the toml file at the specified path as [`IndexerConfig::from_file`],
+    /// returning the unknown keys found in the file rather than reporting them.
+    pub fn from_file_with_unknown_keys(
+        file_path: &Path,
+    ) -> Result<(Self, Vec<String>), IndexerError> {
+        let contents = std::fs::read_to_string(file_path).map_err(|e| {
+            IndexerError::ConfigError(format!(
+                "Could not read config file {}: {}",
+                file_path.display(),
+                e
+            ))
+        })?;
+        let table = contents.parse::<toml::Value>().map_err(|e| {
+            IndexerError::ConfigError(format!(
+                "Could not parse config file {}: {}",
+                file_path.display(),
+                e
+            ))
+        })?;
+        let table = table.as_table().ok_or_else(|| {
+            IndexerError::ConfigError(format!(
+                "Config file {} must contain a toml table.",
+                file_path.display()
+            ))
+        })?;
+
+        let unknown_keys: Vec<String> = table
+            .keys()
+            .filter(|key| {
+                !Self::REQUIRED_KEYS.contains(&key.as_str())
+                    && !Self::OPTIONAL_KEYS.contains(&key.as_str())
+            })
+            .cloned()
+            .collect();
+        if let Some(key) = Self::REQUIRED_KEYS
+            .iter()
+            .find(|key| !table.contains_key(**key))
+        {
+            return Err(IndexerError::ConfigError(format!(
+                "Missing required key `{}` in config file {}.",
+                key,
+                file_path.display()
+            )));
+        }
+
+        let config: IndexerConfig = toml::Value::Table(table.clone()).try_into().map_err(|e| {
+            IndexerError::ConfigError(format!(
+                "Invalid value in config file {}: {}",
+                file_path.display(),
+                e
+            ))
+        })?;
+        config.check_config()?;
+        Ok((config, unknown_keys))
+    }
+
+    /// Returns the full node / validator JsonRPC port.
+    ///
+    /// Uses the configured zebrad_port if set, otherwise the default port for the configured network.
+    pub fn node_rpc_port(&self) -> u16 {
+        self.zebrad_port
+            .unwrap_or_else(|| self.network.default_rpc_port())
+    }
+
+    /// Returns the full node / validator authentication source.
+    ///
+    /// Uses the cookie file at node_cookie_path if set, otherwise the node_user / node_password credentials.
+    pub fn node_auth(&self) -> Option<Auth> {
+        match (&self.node_cookie_path, &self.node_user, &self.node_password) {
+            (Some(cookie_path), _, _) => Some(Auth::CookieFile(PathBuf::from(cookie_path))),
+            (None, Some(user), Some(password)) => {
+                Some(Auth::UserPass(user.clone(), password.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the parsed backup validator JsonRPC uris, in priority order.
+    pub fn validator_failover_uris(&self) -> Result<Vec<http::Uri>, IndexerError> {
+        self.validator_failover_uris
+            .iter()
+            .flatten()
+            .map(|uri| {
+                uri.parse().map_err(|e| {
+                    IndexerError::ConfigError(format!(
+                        "Invalid validator_failover_uris entry {}: {}.",
+                        uri, e
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the mempool polling intervals, using the default for any interval not set.
+    pub fn mempool_poll_config(&self) -> MempoolPollConfig {
+        let default = MempoolPollConfig::default();
+        MempoolPollConfig {
+            poll_interval: self
+                .mempool_poll_interval_ms
+                .map_or(default.poll_interval, std::time::Duration::from_millis),
+            max_poll_interval: self
+                .mempool_max_poll_interval_ms
+                .map_or(default.max_poll_interval, std::time::Duration::from_millis),
+        }
+    }
+
+    /// Returns the time the FetchService caches the node's `getinfo` response for, using the default TTL if not set.
+    pub fn info_cache_ttl(&self) -> std::time::Duration {
+        self.info_cache_ttl_ms
+            .map_or(DEFAULT_INFO_CACHE_TTL, std::time::Duration::from_millis)
+    }
+
+    /// Returns the new block notifier settings, using the default channel capacity and reorg confirmation depth
+    /// if not set.
+    pub fn block_notifier_config(&self) -> BlockNotifierConfig {
+        let default = BlockNotifierConfig::default();
+        BlockNotifierConfig {
+            channel_capacity: self
+                .new_block_channel_capacity
+                .unwrap_or(default.channel_capacity),
+            reorg_confirmation_depth: self
+                .reorg_confirmation_depth
+                .unwrap_or(default.reorg_confirmation_depth),
+            progress_log_interval: self.sync_progress_log_interval_secs.map_or(
+                default.progress_log_interval,
+                std::time::Duration::from_secs,
+            ),
+            ..default
+        }
+    }
+
+    /// Returns the validator connection pool settings, using the default pool size if not set.
+    pub fn connection_pool_config(&self) -> ConnectionPoolConfig {
+        let default = ConnectionPoolConfig::default();
+        ConnectionPoolConfig {
+            max_connections: self.node_max_connections.unwrap_or(default.max_connections),
+            ..default
+        }
+    }
+
+    /// Returns the gRPC request concurrency and rate limits.
+    pub fn request_limits(&self) -> RequestLimits {
+        RequestLimits {
+            max_concurrent_requests_per_connection: self
+                .grpc_max_concurrent_requests_per_connection,
+            max_concurrent_requests: self.grpc_max_concurrent_requests,
+            max_requests_per_second: self.grpc_max_requests_per_second,
+        }
+    }
+
+    /// Returns the gRPC request logger, requests are not logged unless grpc_request_log is set.
+    ///
+    /// The level is checked by [`IndexerConfig::check_config`], an invalid level falls back to info.
+    pub fn request_logger(&self) -> RequestLogger {
+        if self.grpc_request_log != Some(true) {
+            return RequestLogger::new(None);
+        }
+        let level = self
+            .grpc_request_log_level
+            .as_deref()
+            .and_then(|level| RequestLogLevel::from_str(level).ok())
+            .unwrap_or_default();
+        RequestLogger::new(Some(level))
+    }
+
+    /// Returns the gRPC methods served, every method is served unless grpc_enabled_methods or grpc_disabled_methods
+    /// is set.
+    pub fn method_filter(&self) -> MethodFilter {
+        MethodFilter::new(
+            self.grpc_enabled_methods
+                .clone()
+                .map(|methods| methods.into_iter().collect()),
+            self.grpc_disabled_methods
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        )
+    }
+
+    /// Returns the bounds on the length prefixes of Nym requests, using the default bounds if not set.
+    pub fn nym_request_limits(&self) -> NymRequestLimits {
+        let default = NymRequestLimits::default();
+        NymRequestLimits {
+            max_body_len: self.nym_max_body_len.unwrap_or(default.max_body_len),
+            ..default
+        }
+    }
+
+    /// Returns the optional features enabled by the config and the features zainod was built with.
+    pub fn server_features(&self) -> ServerFeatures {
+        let features = [
+            (Feature::Tcp, self.tcp_active),
+            (Feature::UnixSocket, self.grpc_uds_path.is_some()),
+            (Feature::Nym, self.nym_active),
+            (Feature::Tls, self.tls_cert_path.is_some()),
+            (Feature::Metrics, self.metrics_listen_addr.is_some()),
+            (Feature::Health, self.health_listen_addr.is_some()),
+            (Feature::GrpcReflection, self.grpc_reflection == Some(true)),
+            (Feature::RequestLog, self.grpc_request_log == Some(true)),
+            (
+                Feature::ValidatorFailover,
+                self.validator_failover_uris
+                    .as_ref()
+                    .is_some_and(|uris| !uris.is_empty()),
+            ),
+            (Feature::DiskBlockCache, self.block_cache_dir.is_some()),
+            (Feature::Warmup, self.warmup_blocks.is_some()),
+            (
+                Feature::MempoolStream,
+                self.method_filter().is_enabled("GetMempoolStream"),
+            ),
+            (Feature::NymPoc, cfg!(feature = "nym_poc")),
+            (Feature::TokioConsole, cfg!(feature = "tokio-console")),
+        ];
+        ServerFeatures::new(
+            features
+                .into_iter()
+                .filter_map(|(feature, enabled)| enabled.then_some(feature)),
+        )
+    }
+
     /// Performs checks on config data.
     ///
     /// - Checks that at least 1 of nym or tpc is active.
     /// - Checks listen port is given is tcp is active.
     /// - Checks nym_conf_path is given if nym is active and holds a valid utf8 string.
+    /// - Checks tls cert and key paths are either both given or both omitted.
+    /// - Checks validator failover uris are valid uris.
+    /// - Checks the mempool poll interval is non zero and not above the maximum poll interval.
+    /// - Checks the new block channel capacity is non zero.
+    /// - Checks the gRPC request log level is a known level.
+    /// - Checks the upstream queue capacity is non zero.
+    /// - Checks the enabled and disabled gRPC methods are lightwallet service methods.
     pub fn check_config(&self) -> Result<(), IndexerError> {
-        if (!self.tcp_active) && (!self.nym_active) {
+        if (!self.tcp_active) && (!self.nym_active) && self.grpc_uds_path.is_none() {
             return Err(IndexerError::ConfigError(
-                "Cannot start server with no ingestors selected, at least one of either nym or tcp must be set to active in conf.".to_string(),
+                "Cannot start server with no ingestors selected, at least one of nym, tcp or a unix domain socket must be set to active in conf.".to_string(),
             ));
         }
         if self.tcp_active && self.listen_port.is_none() {
@@ -61,6 +468,108 @@ impl IndexerConfig {
                 ));
             }
         }
+        if self.node_cookie_path.as_deref() == Some("") {
+            return Err(IndexerError::ConfigError(
+                "node_cookie_path must not be empty.".to_string(),
+            ));
+        }
+        if self.grpc_uds_path.as_deref() == Some("") {
+            return Err(IndexerError::ConfigError(
+                "grpc_uds_path must not be empty.".to_string(),
+            ));
+        }
+        if self.tls_cert_path.is_some() != self.tls_key_path.is_some() {
+            return Err(IndexerError::ConfigError(
+                "Both tls_cert_path and tls_key_path must be provided to enable TLS.".to_string(),
+            ));
+        }
+        if self.tls_client_ca_path.is_some() && self.tls_cert_path.is_none() {
+            return Err(IndexerError::ConfigError(
+                "tls_client_ca_path provided but TLS is not enabled.".to_string(),
+            ));
+        }
+        if self.node_request_timeout_secs == Some(0) {
+            return Err(IndexerError::ConfigError(
+                "node_request_timeout_secs must be greater than 0.".to_string(),
+            ));
+        }
+        self.validator_failover_uris()?;
+        let mempool_poll_config = self.mempool_poll_config();
+        if mempool_poll_config.poll_interval.is_zero() {
+            return Err(IndexerError::ConfigError(
+                "mempool_poll_interval_ms must be greater than 0.".to_string(),
+            ));
+        }
+        if mempool_poll_config.poll_interval > mempool_poll_config.max_poll_interval {
+            return Err(IndexerError::ConfigError(
+                "mempool_poll_interval_ms must not be greater than mempool_max_poll_interval_ms."
+                    .to_string(),
+            ));
+        }
+        if self.new_block_channel_capacity == Some(0) {
+            return Err(IndexerError::ConfigError(
+                "new_block_channel_capacity must be greater than 0.".to_string(),
+            ));
+        }
+        if self.reorg_confirmation_depth == Some(0) {
+            return Err(IndexerError::ConfigError(
+                "reorg_confirmation_depth must be greater than 0.".to_string(),
+            ));
+        }
+        if self.sync_progress_log_interval_secs == Some(0) {
+            return Err(IndexerError::ConfigError(
+                "sync_progress_log_interval_secs must be greater than 0.".to_string(),
+            ));
+        }
+        if self.nym_max_body_len == Some(0) {
+            return Err(IndexerError::ConfigError(
+                "nym_max_body_len must be greater than 0.".to_string(),
+            ));
+        }
+        if self.block_range_prefetch == Some(0) {
+            return Err(IndexerError::ConfigError(
+                "block_range_prefetch must be greater than 0.".to_string(),
+            ));
+        }
+        if self.node_max_connections == Some(0) {
+            return Err(IndexerError::ConfigError(
+                "node_max_connections must be greater than 0.".to_string(),
+            ));
+        }
+        if self.grpc_max_concurrent_requests_per_connection == Some(0)
+            || self.grpc_max_concurrent_requests == Some(0)
+            || self.grpc_max_requests_per_second == Some(0)
+        {
+            return Err(IndexerError::ConfigError(
+                "gRPC request limits must be greater than 0 if set.".to_string(),
+            ));
+        }
+        if self.upstream_queue_capacity == Some(0) {
+            return Err(IndexerError::ConfigError(
+                "upstream_queue_capacity must be greater than 0.".to_string(),
+            ));
+        }
+        if let Some(level) = &self.grpc_request_log_level {
+            if RequestLogLevel::from_str(level).is_err() {
+                return Err(IndexerError::ConfigError(format!(
+                    "Invalid gRPC request log level \"{}\", expected one of error, warn, info, debug or trace.",
+                    level
+                )));
+            }
+        }
+        for method in self
+            .grpc_enabled_methods
+            .iter()
+            .chain(self.grpc_disabled_methods.iter())
+            .flatten()
+        {
+            if !GRPC_METHODS.contains(&method.as_str()) {
+                return Err(IndexerError::ConfigError(format!(
+                    "Unknown gRPC method \"{}\", expected a CompactTxStreamer method such as GetLightdInfo.",
+                    method
+                )));
+            }
+        }
         Ok(())
     }
 }
@@ -74,12 +583,49 @@ impl Default for IndexerConfig {
             nym_active: true,
             nym_conf_path: Some("/tmp/indexer/nym".to_string()),
             lightwalletd_port: 9067,
-            zebrad_port: 18232,
+            network: NetworkKind::Testnet,
+            zebrad_port: None,
             node_user: Some("xxxxxx".to_string()),
             node_password: Some("xxxxxx".to_string()),
+            node_cookie_path: None,
             max_queue_size: 1024,
             max_worker_pool_size: 32,
             idle_worker_pool_size: 4,
+            block_cache_capacity: Some(10_000),
+            block_cache_gc_target: Some(1_000),
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_client_ca_path: None,
+            metrics_listen_addr: None,
+            nym_allowed_methods: None,
+            node_request_timeout_secs: Some(60),
+            health_listen_addr: None,
+            health_max_staleness_secs: Some(30),
+            health_max_block_lag: Some(10),
+            block_cache_dir: None,
+            validator_failover_uris: None,
+            mempool_poll_interval_ms: Some(1_000),
+            mempool_max_poll_interval_ms: Some(10_000),
+            transaction_cache_capacity: Some(10_000),
+            grpc_uds_path: None,
+            grpc_reflection: Some(false),
+            new_block_channel_capacity: Some(64),
+            grpc_max_concurrent_requests_per_connection: None,
+            grpc_max_concurrent_requests: None,
+            grpc_max_requests_per_second: None,
+            node_max_connections: Some(8),
+            warmup_blocks: None,
+            warmup_start_degraded: None,
+            reorg_confirmation_depth: Some(100),
+            block_range_prefetch: Some(16),
+            info_cache_ttl_ms: Some(5_000),
+            grpc_request_log: Some(false),
+            grpc_request_log_level: Some("info".to_string()),
+            upstream_queue_capacity: Some(64),
+            grpc_enabled_methods: None,
+            grpc_disabled_methods: None,
+            sync_progress_log_interval_secs: Some(10),
+            nym_max_body_len: Some(2_000_000),
         }
     }
 }
@@ -93,37 +639,49 @@ impl Default for IndexerConfig {
             nym_active: false,
             nym_conf_path: None,
             lightwalletd_port: 8080,
-            zebrad_port: 18232,
+            network: NetworkKind::Testnet,
+            zebrad_port: None,
             node_user: Some("xxxxxx".to_string()),
             node_password: Some("xxxxxx".to_string()),
+            node_cookie_path: None,
             max_queue_size: 1024,
             max_worker_pool_size: 32,
             idle_worker_pool_size: 4,
+            block_cache_capacity: Some(10_000),
+            block_cache_gc_target: Some(1_000),
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_client_ca_path: None,
+            metrics_listen_addr: None,
+            nym_allowed_methods: None,
+            node_request_timeout_secs: Some(60),
+            health_listen_addr: None,
+            health_max_staleness_secs: Some(30),
+            health_max_block_lag: Some(10),
+            block_cache_dir: None,
+            validator_failover_uris: None,
+            mempool_poll_interval_ms: Some(1_000),
+            mempool_max_poll_interval_ms: Some(10_000),
+            transaction_cache_capacity: Some(10_000),
+            grpc_uds_path: None,
+            grpc_reflection: Some(false),
+            new_block_channel_capacity: Some(64),
+            grpc_max_concurrent_requests_per_connection: None,
+            grpc_max_concurrent_requests: None,
+            grpc_max_requests_per_second: None,
+            node_max_connections: Some(8),
+            warmup_blocks: None,
+            warmup_start_degraded: None,
+            reorg_confirmation_depth: Some(100),
+            block_range_prefetch: Some(16),
+            info_cache_ttl_ms: Some(5_000),
+            grpc_request_log: Some(false),
+            grpc_request_log_level: Some("info".to_string()),
+            upstream_queue_capacity: Some(64),
+            grpc_enabled_methods: None,
+            grpc_disabled_methods: None,
+            sync_progress_log_interval_secs: Some(10),
+            nym_max_body_len: Some(2_000_000),
         }
     }
 }
-
-/// Attempts to load config data from a toml file at the specified path.
-pub fn load_config(file_path: &std::path::PathBuf) -> IndexerConfig {
-    let mut config = IndexerConfig::default();
-
-    if let Ok(contents) = std::fs::read_to_string(file_path) {
-        if let Ok(parsed_config) = toml::from_str::<IndexerConfig>(&contents) {
-            config = IndexerConfig {
-                tcp_active: parsed_config.tcp_active,
-                listen_port: parsed_config.listen_port.or(config.listen_port),
-                nym_active: parsed_config.nym_active,
-                nym_conf_path: parsed_config.nym_conf_path.or(config.nym_conf_path),
-                lightwalletd_port: parsed_config.lightwalletd_port,
-                zebrad_port: parsed_config.zebrad_port,
-                node_user: parsed_config.node_user.or(config.node_user),
-                node_password: parsed_config.node_password.or(config.node_password),
-                max_queue_size: parsed_config.max_queue_size,
-                max_worker_pool_size: parsed_config.max_worker_pool_size,
-                idle_worker_pool_size: parsed_config.idle_worker_pool_size,
-            };
-        }
-    }
-
-    config
-}
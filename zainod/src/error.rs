@@ -1,6 +1,6 @@
 //! Hold error types for the Indexer and related functionality.
 
-use zaino_fetch::jsonrpc::error::JsonRpcConnectorError;
+use zaino_fetch::{jsonrpc::error::JsonRpcConnectorError, primitives::error::NetworkError};
 use zaino_serve::server::error::ServerError;
 
 /// Zingo-Indexer errors.
@@ -15,12 +15,24 @@ pub enum IndexerError {
     /// JSON RPC connector errors.
     #[error("JSON RPC connector error: {0}")]
     JsonRpcConnectorError(#[from] JsonRpcConnectorError),
+    /// The node serves an unknown chain.
+    #[error("Network error: {0}")]
+    NetworkError(#[from] NetworkError),
     /// HTTP related errors due to invalid URI.
     #[error("HTTP error: Invalid URI {0}")]
     HttpError(#[from] http::Error),
     /// Returned from tokio joinhandles..
     #[error("Join handle error: Invalid URI {0}")]
     TokioJoinError(#[from] tokio::task::JoinError),
+    /// Metrics server errors.
+    #[error("Metrics server error: {0}")]
+    MetricsError(String),
+    /// Health check server errors.
+    #[error("Health check server error: {0}")]
+    HealthError(String),
+    /// Warm-up errors, returned when the caches could not be warmed up and a degraded start is disabled.
+    #[error("Warm-up error: {0}")]
+    WarmupError(String),
     /// Custom indexor errors.
     #[error("Misc indexer error: {0}")]
     MiscIndexerError(String),
@@ -2,7 +2,7 @@
 
 use std::{
     net::SocketAddr,
-    process,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -10,12 +10,46 @@ use std::{
 };
 
 use http::Uri;
-use zaino_fetch::jsonrpc::connector::test_node_and_return_uri;
+use zaino_fetch::{
+    chain::{
+        blockcache::{disk::DiskCache, BlockCache, BlockCacheStatus, MAX_REORG_DEPTH},
+        reorg::{ChainTipMonitor, ChainTipStatus},
+    },
+    health::{spawn_health_server, HealthConfig, WarmupStatus},
+    jsonrpc::{
+        connection_pool::{ConnectionPool, ConnectionPoolStatus},
+        connector::{
+            set_default_request_timeout, test_node_and_return_uri_with_auth, Auth,
+            JsonRpcConnector, DEFAULT_REQUEST_TIMEOUT,
+        },
+        validator_pool::{FailoverConfig, ValidatorPool, ValidatorPoolStatus},
+    },
+    metrics::spawn_metrics_server,
+    task::spawn_named,
+};
 use zaino_serve::server::{
+    capabilities::ServerFeatures,
     director::{Server, ServerStatus},
     error::ServerError,
+    tls::load_tls_config,
     AtomicStatus, StatusType,
 };
+use zaino_state::{
+    blocks::BlockNotifier,
+    fetch::FetchService,
+    mempool::MempoolPoller,
+    status::{MempoolPollStatus, SyncProgress, SyncProgressCache},
+    sync::{SyncStatus, SyncStatusCache},
+};
+
+/// Number of warm-up attempts made before the indexer starts degraded or exits.
+const WARMUP_ATTEMPTS: u32 = 3;
+
+/// Time waited between warm-up attempts.
+const WARMUP_RETRY_DELAY: tokio::time::Duration = tokio::time::Duration::from_secs(5);
+
+/// Time a warm-up attempt waits for the mempool poller's first snapshot.
+const WARMUP_MEMPOOL_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(30);
 
 use crate::{config::IndexerConfig, error::IndexerError};
 
@@ -24,18 +58,100 @@ use crate::{config::IndexerConfig, error::IndexerError};
 pub struct IndexerStatus {
     indexer_status: AtomicStatus,
     server_status: ServerStatus,
-    // block_cache_status: BlockCacheStatus,
+    block_cache_status: BlockCacheStatus,
+    chain_tip_status: ChainTipStatus,
+    validator_pool_status: Option<ValidatorPoolStatus>,
+    mempool_poll_status: MempoolPollStatus,
+    connection_pool_status: ConnectionPoolStatus,
+    warmup_status: WarmupStatus,
+    sync_progress: SyncProgressCache,
+    sync_status: SyncStatusCache,
+    server_features: ServerFeatures,
 }
 
 impl IndexerStatus {
     /// Creates a new IndexerStatus.
-    pub fn new(max_workers: u16) -> Self {
+    pub fn new(
+        max_workers: u16,
+        block_cache_status: BlockCacheStatus,
+        chain_tip_status: ChainTipStatus,
+        validator_pool_status: Option<ValidatorPoolStatus>,
+        mempool_poll_status: MempoolPollStatus,
+        connection_pool_status: ConnectionPoolStatus,
+        warmup_status: WarmupStatus,
+        sync_progress: SyncProgressCache,
+        sync_status: SyncStatusCache,
+        server_features: ServerFeatures,
+    ) -> Self {
         IndexerStatus {
             indexer_status: AtomicStatus::new(5),
             server_status: ServerStatus::new(max_workers),
+            block_cache_status,
+            chain_tip_status,
+            validator_pool_status,
+            mempool_poll_status,
+            connection_pool_status,
+            warmup_status,
+            sync_progress,
+            sync_status,
+            server_features,
         }
     }
 
+    /// Returns the block cache's hit and miss counts.
+    pub fn block_cache_status(&self) -> &BlockCacheStatus {
+        &self.block_cache_status
+    }
+
+    /// Returns the most recently detected chain reorg and the number of reorgs detected.
+    pub fn chain_tip_status(&self) -> &ChainTipStatus {
+        &self.chain_tip_status
+    }
+
+    /// Returns the active validator endpoint and failover count, None if failover is disabled.
+    pub fn validator_pool_status(&self) -> Option<&ValidatorPoolStatus> {
+        self.validator_pool_status.as_ref()
+    }
+
+    /// Returns the mempool poller's effective polling interval and poll count.
+    pub fn mempool_poll_status(&self) -> &MempoolPollStatus {
+        &self.mempool_poll_status
+    }
+
+    /// Returns the utilization and retry counts of the validator connection pool.
+    pub fn connection_pool_status(&self) -> &ConnectionPoolStatus {
+        &self.connection_pool_status
+    }
+
+    /// Returns the nym address the indexer listens at, None if Nym is not active.
+    ///
+    /// The address is stable across restarts while the identity stored in [`IndexerConfig::nym_conf_path`] is kept.
+    pub fn nym_address(&self) -> Option<String> {
+        self.server_status.nym_address()
+    }
+
+    /// Returns whether the indexer has finished warming up its caches.
+    pub fn warmup_status(&self) -> &WarmupStatus {
+        &self.warmup_status
+    }
+
+    /// Returns the indexer's synced tip height, the validator's estimated network height and the fraction synced,
+    /// None before the validator has been reached.
+    pub fn sync_progress(&self) -> Option<SyncProgress> {
+        self.sync_progress.sync_progress()
+    }
+
+    /// Returns whether the initial sync has caught up, with its smoothed rate and ETA, None before the validator has
+    /// been reached.
+    pub fn sync_status(&self) -> Option<SyncStatus> {
+        self.sync_status.sync_status()
+    }
+
+    /// Returns the optional features enabled on the server, as reported by GetServerCapabilities.
+    pub fn server_features(&self) -> &ServerFeatures {
+        &self.server_features
+    }
+
     /// Returns the IndexerStatus.
     pub fn load(&self) -> IndexerStatus {
         self.indexer_status.load();
@@ -50,8 +166,22 @@ pub struct Indexer {
     _config: IndexerConfig,
     /// GRPC server.
     server: Option<Server>,
-    // /// Internal block cache.
-    // block_cache: BlockCache,
+    /// Handle of the block cache GC task.
+    block_cache_gc_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Handle of the metrics server task.
+    metrics_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Handle of the health check server task.
+    health_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Handle of the validator pool recovery check task.
+    validator_pool_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Handle of the task dropping FetchService cached transactions rolled back by reorgs.
+    fetch_service_reorg_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Chain tip monitor, used to detect chain reorgs.
+    chain_tip_monitor: Option<ChainTipMonitor>,
+    /// Mempool poller, pushes mempool changes to subscribers.
+    mempool_poller: Option<MempoolPoller>,
+    /// Block notifier, pushes blocks added to the best chain to subscribers.
+    block_notifier: Option<BlockNotifier>,
     /// Indexers status.
     status: IndexerStatus,
     /// Online status of the indexer.
@@ -62,22 +192,35 @@ impl Indexer {
     /// Starts Indexer service.
     ///
     /// Currently only takes an IndexerConfig.
+    ///
+    /// If [`IndexerConfig::warmup_blocks`] is set the block cache and mempool snapshot are warmed up before the gRPC
+    /// server accepts connections, `/healthz` reports `starting` until warm-up completes.
+    ///
+    /// Runs until the indexer closes or a Ctrl-C / SIGTERM signal is received,
+    /// then gives in-flight requests [`Indexer::SHUTDOWN_GRACE_PERIOD`] to drain before aborting.
     pub async fn start(config: IndexerConfig) -> Result<(), IndexerError> {
-        let online = Arc::new(AtomicBool::new(true));
-        set_ctrlc(online.clone());
         startup_message();
-        self::Indexer::start_indexer_service(config, online)
-            .await?
-            .await?
+        let mut indexer_handle = self::Indexer::start_indexer_service(config).await?;
+        tokio::select! {
+            result = indexer_handle.closed() => return result,
+            _ = shutdown_signal() => {
+                println!("Received shutdown signal, closing Zingdexer..");
+            }
+        }
+        indexer_handle.shutdown().await
     }
 
+    /// Time given to the indexer to close gracefully after a shutdown signal.
+    pub const SHUTDOWN_GRACE_PERIOD: tokio::time::Duration = tokio::time::Duration::from_secs(10);
+
     /// Launches an Indexer service.
     ///
-    /// Spawns an indexer service in a new task.
+    /// Spawns an indexer service in a new task, closed with [`IndexerHandle::shutdown`]. Each service has its own
+    /// online status, so several indexers can run in one process.
     pub async fn start_indexer_service(
         config: IndexerConfig,
-        online: Arc<AtomicBool>,
-    ) -> Result<tokio::task::JoinHandle<Result<(), IndexerError>>, IndexerError> {
+    ) -> Result<IndexerHandle, IndexerError> {
+        let online = Arc::new(AtomicBool::new(true));
         // NOTE: This interval may need to be reduced or removed / moved once scale testing begins.
         let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(50));
         if config.nym_active {
@@ -85,7 +228,7 @@ impl Indexer {
         }
         println!("Launching Zingdexer!");
         let mut indexer: Indexer = Indexer::new(config, online.clone()).await?;
-        Ok(tokio::task::spawn(async move {
+        let handle = spawn_named("indexer", async move {
             let server_handle = if let Some(server) = indexer.server.take() {
                 Some(server.serve().await)
             } else {
@@ -106,7 +249,8 @@ impl Indexer {
                 }
                 interval.tick().await;
             }
-        }))
+        });
+        Ok(IndexerHandle { online, handle })
     }
 
     /// Creates a new Indexer.
@@ -114,7 +258,6 @@ impl Indexer {
     /// Currently only takes an IndexerConfig.
     async fn new(config: IndexerConfig, online: Arc<AtomicBool>) -> Result<Self, IndexerError> {
         config.check_config()?;
-        let status = IndexerStatus::new(config.max_worker_pool_size);
         let tcp_ingestor_listen_addr: Option<SocketAddr> = config
             .listen_port
             .map(|port| SocketAddr::new(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST), port));
@@ -123,25 +266,195 @@ impl Indexer {
             .authority(format!("localhost:{}", config.lightwalletd_port))
             .path_and_query("/")
             .build()?;
+        set_default_request_timeout(
+            config
+                .node_request_timeout_secs
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(DEFAULT_REQUEST_TIMEOUT),
+        );
+        let connection_pool = ConnectionPool::new(config.connection_pool_config());
+        let connection_pool_status = connection_pool.status();
         println!("Checking connection with node..");
-        let zebrad_uri = test_node_and_return_uri(
-            &config.zebrad_port,
-            config.node_user.clone(),
-            config.node_password.clone(),
+        let failover_uris = config.validator_failover_uris()?;
+        // NOTE: With failover endpoints configured the indexer starts on the first endpoint answering, so a primary
+        // node that is down at startup does not stop the indexer.
+        let (zebrad_uri, validator_pool) = if failover_uris.is_empty() {
+            let zebrad_uri =
+                test_node_and_return_uri_with_auth(&config.node_rpc_port(), config.node_auth())
+                    .await?;
+            (zebrad_uri, None)
+        } else {
+            let primary_uri = Uri::builder()
+                .scheme("http")
+                .authority(format!("127.0.0.1:{}", config.node_rpc_port()))
+                .path_and_query("/")
+                .build()?;
+            let pool = ValidatorPool::new(
+                std::iter::once(primary_uri).chain(failover_uris).collect(),
+                FailoverConfig::default(),
+            )?;
+            let zebrad_uri = pool.probe(config.node_auth().as_ref()).await?;
+            (zebrad_uri, Some(pool))
+        };
+        // Every connector to the node shares the indexer's connection pool and, if configured, fails over through
+        // its validator pool.
+        let node_connector = || {
+            match &validator_pool {
+                Some(pool) => JsonRpcConnector::new_with_pool(pool.clone(), config.node_auth()),
+                None => JsonRpcConnector::new_with_auth(zebrad_uri.clone(), config.node_auth()),
+            }
+            .with_connection_pool(connection_pool.clone())
+        };
+        let node_network = node_connector().get_blockchain_info().await?.network()?;
+        if node_network != config.network {
+            return Err(IndexerError::ConfigError(format!(
+                "Configured network is {:?} but the node serves {:?}.",
+                config.network, node_network
+            )));
+        }
+        let validator_pool_handle = validator_pool
+            .as_ref()
+            .map(|pool| pool.spawn_recheck(config.node_auth(), online.clone()));
+        let metrics_handle = match config.metrics_listen_addr {
+            Some(metrics_listen_addr) => Some(
+                spawn_metrics_server(metrics_listen_addr, node_connector(), online.clone())
+                    .map_err(|e| IndexerError::MetricsError(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let tls_config = match (&config.tls_cert_path, &config.tls_key_path) {
+            (Some(cert_path), Some(key_path)) => Some(load_tls_config(
+                cert_path,
+                key_path,
+                config.tls_client_ca_path.as_deref(),
+            )?),
+            _ => None,
+        };
+        let mut block_cache = BlockCache::new(
+            config.block_cache_capacity.unwrap_or(10_000),
+            config.block_cache_gc_target.unwrap_or(1_000),
         )
-        .await?;
+        .with_reorg_confirmation_depth(config.reorg_confirmation_depth.unwrap_or(MAX_REORG_DEPTH));
+        if let Some(block_cache_dir) = &config.block_cache_dir {
+            let disk_cache = DiskCache::open(block_cache_dir).map_err(|e| {
+                IndexerError::ConfigError(format!(
+                    "Failed to open block cache directory {}: {}",
+                    block_cache_dir, e
+                ))
+            })?;
+            block_cache = block_cache.with_disk_cache(disk_cache);
+        }
+        let chain_tip_monitor = ChainTipMonitor::spawn(
+            node_connector(),
+            block_cache.clone(),
+            tokio::time::Duration::from_secs(5),
+            online.clone(),
+        );
+        let mempool_poller = MempoolPoller::spawn(
+            node_connector(),
+            config.mempool_poll_config(),
+            online.clone(),
+        );
+        let block_notifier = BlockNotifier::spawn(
+            node_connector(),
+            config.block_notifier_config(),
+            online.clone(),
+        );
+        let fetch_service = FetchService::from_node(node_connector())
+            .with_info_cache_ttl(config.info_cache_ttl())
+            .with_upstream_queue(config.upstream_queue_capacity.unwrap_or(64))
+            .with_mempool_poller(&mempool_poller);
+        // NOTE: A transaction cache capacity of 0 disables caching.
+        let fetch_service = Arc::new(match config.transaction_cache_capacity.unwrap_or(10_000) {
+            0 => fetch_service,
+            capacity => fetch_service.with_transaction_cache(capacity),
+        });
+        let fetch_service_reorg_handle = Some(FetchService::spawn_reorg_handler(
+            fetch_service.clone(),
+            chain_tip_monitor.subscribe(),
+        ));
+        let warmup = match config.warmup_blocks {
+            Some(_) => WarmupStatus::starting(),
+            None => WarmupStatus::complete(),
+        };
+        let status = IndexerStatus::new(
+            config.max_worker_pool_size,
+            block_cache.status(),
+            chain_tip_monitor.status(),
+            validator_pool.as_ref().map(|pool| pool.status()),
+            mempool_poller.status(),
+            connection_pool_status,
+            warmup.clone(),
+            block_notifier.sync_progress(),
+            block_notifier.sync_status(),
+            config.server_features(),
+        );
+        let health_handle = match config.health_listen_addr {
+            Some(health_listen_addr) => Some(
+                spawn_health_server(
+                    health_listen_addr,
+                    status.chain_tip_status().clone(),
+                    warmup.clone(),
+                    HealthConfig {
+                        max_staleness: tokio::time::Duration::from_secs(
+                            config.health_max_staleness_secs.unwrap_or(30),
+                        ),
+                        max_block_lag: config.health_max_block_lag.unwrap_or(10),
+                    },
+                    online.clone(),
+                )
+                .map_err(|e| IndexerError::HealthError(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let block_cache_gc_handle = Some(block_cache.spawn_gc(
+            tokio::time::Duration::from_secs(60),
+            tokio::time::Duration::from_secs(30),
+            online.clone(),
+        ));
+        if let Some(warmup_blocks) = config.warmup_blocks {
+            warm_up(
+                &zebrad_uri,
+                config.node_auth(),
+                &block_cache,
+                &status.mempool_poll_status,
+                warmup_blocks,
+                &warmup,
+                config.warmup_start_degraded.unwrap_or(false),
+            )
+            .await?;
+        }
         status.indexer_status.store(0);
         let server = Some(
             Server::spawn(
                 config.tcp_active,
                 tcp_ingestor_listen_addr,
+                config.grpc_uds_path.clone().map(PathBuf::from),
                 config.nym_active,
                 config.nym_conf_path.clone(),
+                config
+                    .nym_allowed_methods
+                    .clone()
+                    .map(|methods| methods.into_iter().collect()),
+                config.nym_request_limits(),
                 lightwalletd_uri,
                 zebrad_uri,
+                config.node_auth(),
+                validator_pool,
+                connection_pool,
+                config.network,
+                block_cache,
+                fetch_service,
+                config.block_range_prefetch.unwrap_or(16),
                 config.max_queue_size,
                 config.max_worker_pool_size,
                 config.idle_worker_pool_size,
+                tls_config,
+                config.grpc_reflection.unwrap_or(false),
+                config.request_limits(),
+                config.request_logger(),
+                config.method_filter(),
+                config.server_features(),
                 status.server_status.clone(),
                 online.clone(),
             )
@@ -151,6 +464,14 @@ impl Indexer {
         Ok(Indexer {
             _config: config,
             server,
+            block_cache_gc_handle,
+            metrics_handle,
+            health_handle,
+            validator_pool_handle,
+            fetch_service_reorg_handle,
+            chain_tip_monitor: Some(chain_tip_monitor),
+            mempool_poller: Some(mempool_poller),
+            block_notifier: Some(block_notifier),
             status,
             online,
         })
@@ -181,6 +502,30 @@ impl Indexer {
             self.status.server_status.server_status.store(4);
             handle.await.ok();
         }
+        if let Some(handle) = self.block_cache_gc_handle.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.metrics_handle.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.health_handle.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.validator_pool_handle.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.fetch_service_reorg_handle.take() {
+            handle.abort();
+        }
+        if let Some(monitor) = self.chain_tip_monitor.take() {
+            monitor.shutdown();
+        }
+        if let Some(poller) = self.mempool_poller.take() {
+            poller.shutdown();
+        }
+        if let Some(notifier) = self.block_notifier.take() {
+            notifier.shutdown();
+        }
     }
 
     /// Returns the indexers current status usize.
@@ -205,12 +550,157 @@ impl Indexer {
     }
 }
 
-fn set_ctrlc(online: Arc<AtomicBool>) {
-    ctrlc::set_handler(move || {
-        online.store(false, Ordering::SeqCst);
-        process::exit(0);
+/// Handle of an indexer service spawned by [`Indexer::start_indexer_service`].
+///
+/// Dropping the handle leaves the service running, it is closed with [`IndexerHandle::shutdown`].
+#[derive(Debug)]
+pub struct IndexerHandle {
+    /// Online status of the indexer service.
+    online: Arc<AtomicBool>,
+    /// Handle of the indexer task.
+    handle: tokio::task::JoinHandle<Result<(), IndexerError>>,
+}
+
+impl IndexerHandle {
+    /// Returns true if the indexer task has returned.
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Returns the service's online status, storing false signals the service to close without awaiting it, e.g. from a
+    /// panic hook.
+    pub fn shutdown_signal(&self) -> Arc<AtomicBool> {
+        self.online.clone()
+    }
+
+    /// Awaits the indexer task returning without signalling it to close, e.g. on a fatal server error.
+    ///
+    /// Cancel safe, the service keeps running if the returned future is dropped.
+    pub async fn closed(&mut self) -> Result<(), IndexerError> {
+        (&mut self.handle).await?
+    }
+
+    /// Signals the indexer service to close and awaits in-flight requests for [`Indexer::SHUTDOWN_GRACE_PERIOD`].
+    ///
+    /// Returns once the server and all indexer components have been closed. Aborts the service and returns an error
+    /// if the grace period elapses.
+    pub async fn shutdown(mut self) -> Result<(), IndexerError> {
+        self.online.store(false, Ordering::SeqCst);
+        match tokio::time::timeout(Indexer::SHUTDOWN_GRACE_PERIOD, &mut self.handle).await {
+            Ok(result) => result?,
+            Err(_) => {
+                self.handle.abort();
+                Err(IndexerError::MiscIndexerError(
+                    "Shutdown grace period elapsed, indexer tasks aborted.".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// Warms the block cache with the node's `blocks` most recent blocks and awaits the mempool poller's first snapshot.
+///
+/// Failed attempts are retried up to [`WARMUP_ATTEMPTS`] times, if all attempts fail the indexer starts with cold
+/// caches when `start_degraded` is true and an error is returned otherwise.
+async fn warm_up(
+    zebrad_uri: &Uri,
+    auth: Option<Auth>,
+    block_cache: &BlockCache,
+    mempool_poll_status: &MempoolPollStatus,
+    blocks: u32,
+    warmup: &WarmupStatus,
+    start_degraded: bool,
+) -> Result<(), IndexerError> {
+    println!("Warming up caches..");
+    let mut last_error = String::new();
+    for attempt in 1..=WARMUP_ATTEMPTS {
+        match warm_up_once(
+            zebrad_uri,
+            auth.clone(),
+            block_cache,
+            mempool_poll_status,
+            blocks,
+        )
+        .await
+        {
+            Ok(cached) => {
+                println!("Warm-up complete, {} blocks cached.", cached);
+                warmup.mark_complete();
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!(
+                    "Warm-up attempt {} of {} failed: {}.",
+                    attempt, WARMUP_ATTEMPTS, e
+                );
+                last_error = e;
+            }
+        }
+        if attempt < WARMUP_ATTEMPTS {
+            tokio::time::sleep(WARMUP_RETRY_DELAY).await;
+        }
+    }
+    if start_degraded {
+        eprintln!("Warm-up failed, starting with cold caches.");
+        warmup.mark_degraded();
+        Ok(())
+    } else {
+        Err(IndexerError::WarmupError(last_error))
+    }
+}
+
+/// Makes a single warm-up attempt, returns the number of blocks cached.
+async fn warm_up_once(
+    zebrad_uri: &Uri,
+    auth: Option<Auth>,
+    block_cache: &BlockCache,
+    mempool_poll_status: &MempoolPollStatus,
+    blocks: u32,
+) -> Result<u32, String> {
+    let cached = block_cache
+        .preload(zebrad_uri, auth, blocks)
+        .await
+        .map_err(|e| e.to_string())?;
+    // NOTE: A mempool marked unavailable (e.g. `getrawmempool` disabled on the node) does not hold up warm-up.
+    tokio::time::timeout(WARMUP_MEMPOOL_TIMEOUT, async {
+        while mempool_poll_status.snapshots() == 0 && mempool_poll_status.is_available() {
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        }
     })
-    .expect("Error setting Ctrl-C handler");
+    .await
+    .map_err(|_| "Timed out waiting for the first mempool snapshot".to_string())?;
+    Ok(cached)
+}
+
+/// Resolves when a Ctrl-C or (on unix) SIGTERM signal is received.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            eprintln!("Failed to listen for Ctrl-C signal: {}.", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                eprintln!("Failed to listen for SIGTERM signal: {}.", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
 
 fn startup_message() {